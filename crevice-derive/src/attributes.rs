@@ -0,0 +1,232 @@
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Expr, ExprLit, Lit, LitInt, LitStr, Meta};
+
+/// The names of all layouts, which can be used to scope attributes to a
+/// single layout, like `#[crevice(std430(assert_size = 48))]`.
+const LAYOUT_NAMES: &[&str] = &["std140", "std430", "hlsl_cbuffer", "msl", "opencl"];
+
+/// Options given to a layout derive with `#[crevice(...)]` attributes on the
+/// struct.
+#[derive(Default)]
+pub struct StructAttributes {
+    /// The exact size the generated struct must have.
+    pub assert_size: Option<LitInt>,
+
+    /// The exact alignment the generated struct must have.
+    pub assert_align: Option<LitInt>,
+
+    /// The largest size the generated struct may have.
+    pub max_size: Option<LitInt>,
+
+    /// How fields should be reordered to reduce padding, if at all.
+    pub reorder: Option<Reorder>,
+
+    /// How names that aren't valid in GLSL should be changed, instead of
+    /// reporting an error.
+    pub rename: Option<Rename>,
+}
+
+/// Which layout fields are ordered for, given with `#[crevice(reorder)]` or
+/// `#[crevice(reorder(std430))]`.
+#[derive(Clone, Copy)]
+pub enum Reorder {
+    /// Each derive orders fields for the layout it generates code for.
+    Own,
+
+    /// Every derive orders fields for the named layout.
+    Layout(&'static str),
+}
+
+impl Reorder {
+    /// The name of the layout whose order a derive for `own` should use.
+    pub fn layout(self, own: &'static str) -> &'static str {
+        match self {
+            Self::Own => own,
+            Self::Layout(layout) => layout,
+        }
+    }
+}
+
+/// A strategy for renaming identifiers that aren't valid in GLSL, given with
+/// `#[crevice(rename(prefix = "..."))]` or `#[crevice(rename(suffix = "..."))]`.
+pub enum Rename {
+    Prefix(LitStr),
+    Suffix(LitStr),
+}
+
+impl StructAttributes {
+    /// Parses the attributes that apply to the layout with the given module
+    /// name, skipping any scoped to other layouts.
+    pub fn parse(attrs: &[Attribute], mod_name: &str) -> syn::Result<Self> {
+        let mut output = Self::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("crevice") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("reorder") {
+                    if meta.input.is_empty() || meta.input.peek(syn::Token![,]) {
+                        output.reorder = Some(Reorder::Own);
+                        return Ok(());
+                    }
+
+                    return meta.parse_nested_meta(|meta| {
+                        let layout = LAYOUT_NAMES
+                            .iter()
+                            .find(|layout| meta.path.is_ident(layout))
+                            .ok_or_else(|| {
+                                meta.error(format!(
+                                    "expected a layout name: {}",
+                                    LAYOUT_NAMES.join(", ")
+                                ))
+                            })?;
+
+                        if matches!(output.reorder, Some(Reorder::Layout(_))) {
+                            return Err(meta.error("`reorder` takes a single layout"));
+                        }
+
+                        output.reorder = Some(Reorder::Layout(layout));
+                        Ok(())
+                    });
+                }
+
+                if meta.path.is_ident("rename") {
+                    return meta.parse_nested_meta(|meta| {
+                        let rename = if meta.path.is_ident("prefix") {
+                            Rename::Prefix
+                        } else if meta.path.is_ident("suffix") {
+                            Rename::Suffix
+                        } else {
+                            return Err(meta.error("expected `prefix` or `suffix`"));
+                        };
+
+                        output.rename = Some(rename(meta.value()?.parse()?));
+                        Ok(())
+                    });
+                }
+
+                for &layout in LAYOUT_NAMES {
+                    if meta.path.is_ident(layout) {
+                        return meta.parse_nested_meta(|meta| {
+                            if layout == mod_name {
+                                output.parse_option(meta)
+                            } else {
+                                Self::default().parse_option(meta)
+                            }
+                        });
+                    }
+                }
+
+                output.parse_option(meta)
+            })?;
+        }
+
+        Ok(output)
+    }
+
+    /// Parses the attributes that apply to every layout, like `reorder`,
+    /// skipping any scoped to a single layout.
+    pub fn parse_shared(attrs: &[Attribute]) -> syn::Result<Self> {
+        Self::parse(attrs, "")
+    }
+
+    /// Whether any compile-time assertions were requested.
+    pub fn has_assertions(&self) -> bool {
+        self.assert_size.is_some() || self.assert_align.is_some() || self.max_size.is_some()
+    }
+
+    fn parse_option(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        let slot = if meta.path.is_ident("assert_size") {
+            &mut self.assert_size
+        } else if meta.path.is_ident("assert_align") {
+            &mut self.assert_align
+        } else if meta.path.is_ident("max_size") {
+            &mut self.max_size
+        } else if meta.path.is_ident("reorder") {
+            return Err(meta.error(
+                "`reorder` can't be scoped to a layout; use `reorder(<layout>)` to order fields for that layout everywhere",
+            ));
+        } else if meta.path.is_ident("rename") {
+            return Err(
+                meta.error("`rename` applies to generated GLSL, and can't be scoped to a layout")
+            );
+        } else {
+            return Err(meta.error("unknown crevice attribute"));
+        };
+
+        let value: LitInt = meta.value()?.parse()?;
+        value.base10_parse::<usize>()?;
+        *slot = Some(value);
+
+        Ok(())
+    }
+}
+
+/// Collects the doc comments in `attrs` into one string, with the common
+/// indentation and any blank lines at the start or end removed.
+pub fn doc_comment(attrs: &[Attribute]) -> String {
+    let mut lines = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident("doc") {
+            continue;
+        }
+
+        if let Meta::NameValue(meta) = &attr.meta {
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) = &meta.value
+            {
+                // A trailing backslash would continue the generated `//`
+                // comment onto the declaration after it.
+                let mut comment: Vec<String> = value
+                    .value()
+                    .split('\n')
+                    .map(|line| line.trim_end().trim_end_matches('\\').trim_end().to_owned())
+                    .collect();
+
+                // Block comments often start each line with `*`, which isn't
+                // part of the text. The first line follows the opening `/**`,
+                // as in `/** Summary`, so it doesn't count.
+                let mut rest = comment
+                    .iter()
+                    .skip(1)
+                    .filter(|line| !line.trim_start().is_empty())
+                    .peekable();
+                let starred =
+                    rest.peek().is_some() && rest.all(|line| line.trim_start().starts_with('*'));
+                if starred {
+                    for line in comment.iter_mut().skip(1) {
+                        *line = line.trim_start().trim_start_matches('*').to_owned();
+                    }
+                }
+
+                lines.extend(comment);
+            }
+        }
+    }
+
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let lines: Vec<_> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .skip_while(|line| line.is_empty())
+        .collect();
+
+    let len = lines.len()
+        - lines
+            .iter()
+            .rev()
+            .take_while(|line| line.is_empty())
+            .count();
+    lines[..len].join("\n")
+}
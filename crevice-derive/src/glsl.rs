@@ -1,6 +1,10 @@
 use proc_macro2::{Literal, TokenStream};
 use quote::quote;
-use syn::{parse_quote, Data, DeriveInput, Fields, Path};
+use syn::{parse_quote, Data, DeriveInput, Fields, FieldsNamed, Ident, Path};
+
+use crate::attributes::{doc_comment, Rename, StructAttributes};
+use crate::layout::{self, Layout};
+use crate::names::glsl_name;
 
 pub fn emit(input: DeriveInput) -> TokenStream {
     let fields = match &input.data {
@@ -16,31 +20,205 @@ pub fn emit(input: DeriveInput) -> TokenStream {
     let struct_trait_path: Path = parse_quote!(::crevice::glsl::GlslStruct);
 
     let name = input.ident;
-    let name_str = Literal::string(&name.to_string());
 
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    let glsl_fields = fields.named.iter().map(|field| {
-        let field_ty = &field.ty;
-        let field_name_str = Literal::string(&field.ident.as_ref().unwrap().to_string());
+    let attributes = match StructAttributes::parse_shared(&input.attrs) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    if attributes.reorder.is_some() && !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "`reorder` is not supported on generic structs",
+        )
+        .to_compile_error();
+    }
+
+    let (glsl_struct_name, glsl_field_names) =
+        match glsl_names(&name, fields, attributes.rename.as_ref()) {
+            Ok(names) => names,
+            Err(err) => return err.to_compile_error(),
+        };
+    let name_str = Literal::string(&glsl_struct_name);
+    let doc_str = Literal::string(&doc_comment(&input.attrs));
+
+    let glsl_fields = fields
+        .named
+        .iter()
+        .zip(&glsl_field_names)
+        .map(|(field, field_name)| {
+            let field_ty = &field.ty;
+            let field_name_str = Literal::string(field_name);
+            let field_doc_str = Literal::string(&doc_comment(&field.attrs));
+
+            quote! {
+                ::crevice::glsl::GlslField {
+                    ty: <#field_ty as ::crevice::glsl::Glsl>::NAME,
+                    name: #field_name_str,
+                    doc: #field_doc_str,
+                    msl_ty: <#field_ty as ::crevice::glsl::Glsl>::MSL_NAME,
+                    definition: <#field_ty as ::crevice::glsl::Glsl>::DEFINITION,
+                    array: <#field_ty as ::crevice::glsl::Glsl>::ARRAY,
+                }
+            }
+        });
+
+    let order = attributes.reorder.map(|reorder| {
+        let layout = Layout::named(reorder.layout(layout::STD140.mod_name));
+        layout::field_order(&fields.named.iter().collect::<Vec<_>>(), layout)
+    });
+
+    let fields_value = match &order {
+        Some(order) => quote! {
+            &::crevice::internal::permute([#( #glsl_fields, )*], #order)
+        },
+        None => quote! {
+            &[#( #glsl_fields, )*]
+        },
+    };
 
+    // The definition is built from the names of the field types, which are
+    // only known once consts are evaluated. Building it in a const needs its
+    // length as a const too, which can't depend on generic parameters.
+    let glsl_definition_const = if input.generics.params.is_empty() {
         quote! {
-            ::crevice::glsl::GlslField {
-                ty: <#field_ty as ::crevice::glsl::Glsl>::NAME,
-                name: #field_name_str,
+            impl #name {
+                /// The GLSL definition of this struct, generated at compile
+                /// time. This is the same code that
+                /// `GlslStruct::glsl_definition` generates.
+                pub const GLSL_DEFINITION: &'static str = {
+                    const FIELDS: &[::crevice::glsl::GlslField] =
+                        <#name as ::crevice::glsl::GlslStruct>::FIELDS;
+                    const LEN: usize =
+                        ::crevice::internal::glsl_struct_len(#name_str, #doc_str, FIELDS);
+                    const BYTES: [u8; LEN] =
+                        ::crevice::internal::glsl_struct_definition(#name_str, #doc_str, FIELDS);
+
+                    ::crevice::internal::str_from_utf8(&BYTES)
+                };
             }
         }
-    });
+    } else {
+        TokenStream::new()
+    };
 
     quote! {
         unsafe impl #impl_generics #base_trait_path for #name #ty_generics #where_clause {
             const NAME: &'static str = #name_str;
+            const MSL_NAME: ::core::option::Option<&'static str> = ::core::option::Option::Some(#name_str);
+            const DEFINITION: ::core::option::Option<&'static ::crevice::glsl::GlslStructDefinition> =
+                ::core::option::Option::Some(&::crevice::glsl::GlslStructDefinition {
+                    name: #name_str,
+                    doc: #doc_str,
+                    fields: #fields_value,
+                });
         }
 
         unsafe impl #impl_generics #struct_trait_path for #name #ty_generics #where_clause {
-            const FIELDS: &'static [::crevice::glsl::GlslField] = &[
-                #( #glsl_fields, )*
-            ];
+            const DOC: &'static str = #doc_str;
+            const FIELDS: &'static [::crevice::glsl::GlslField] =
+                match <Self as #base_trait_path>::DEFINITION {
+                    ::core::option::Option::Some(definition) => definition.fields,
+                    ::core::option::Option::None => &[],
+                };
         }
+
+        #glsl_definition_const
+    }
+}
+
+pub fn emit_value(input: DeriveInput) -> TokenStream {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields,
+            Fields::Unnamed(_) => panic!("Tuple structs are not supported"),
+            Fields::Unit => panic!("Unit structs are not supported"),
+        },
+        Data::Enum(_) | Data::Union(_) => panic!("Only structs are supported"),
+    };
+
+    let name = input.ident;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let attributes = match StructAttributes::parse_shared(&input.attrs) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    if attributes.reorder.is_some() && !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "`reorder` is not supported on generic structs",
+        )
+        .to_compile_error();
+    }
+
+    let arguments = fields.named.iter().map(|field| {
+        let field_name = field.ident.as_ref().unwrap();
+
+        quote! {
+            &self.#field_name as &dyn ::crevice::glsl::GlslValue
+        }
+    });
+
+    // Constructor arguments follow the order of the GLSL struct's fields.
+    let arguments_value = if let Some(reorder) = attributes.reorder {
+        let layout = Layout::named(reorder.layout(layout::STD140.mod_name));
+        let order = layout::field_order(&fields.named.iter().collect::<Vec<_>>(), layout);
+
+        quote! {
+            ::crevice::internal::permute([#( #arguments, )*], #order)
+        }
+    } else {
+        quote! {
+            [#( #arguments, )*]
+        }
+    };
+
+    quote! {
+        impl #impl_generics ::crevice::glsl::GlslValue for #name #ty_generics #where_clause {
+            fn write_glsl_value(
+                &self,
+                output: &mut dyn ::core::fmt::Write,
+            ) -> ::core::fmt::Result {
+                output.write_str(<Self as ::crevice::glsl::Glsl>::NAME)?;
+                ::crevice::glsl::write_arguments(output, &#arguments_value)
+            }
+        }
+    }
+}
+
+/// Gives the GLSL names of a struct and its fields, reporting every name that
+/// can't be used in GLSL at once.
+fn glsl_names(
+    name: &Ident,
+    fields: &FieldsNamed,
+    rename: Option<&Rename>,
+) -> syn::Result<(String, Vec<String>)> {
+    let mut errors: Option<syn::Error> = None;
+    let mut check = |ident: &Ident| match glsl_name(ident, rename) {
+        Ok(name) => name,
+        Err(err) => {
+            match &mut errors {
+                Some(errors) => errors.combine(err),
+                None => errors = Some(err),
+            }
+            String::new()
+        }
+    };
+
+    let struct_name = check(name);
+    let field_names = fields
+        .named
+        .iter()
+        .map(|field| check(field.ident.as_ref().unwrap()))
+        .collect();
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok((struct_name, field_names)),
     }
 }
@@ -0,0 +1,76 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+use crate::attributes::StructAttributes;
+use crate::layout::{self, Layout};
+
+pub fn emit(input: DeriveInput) -> TokenStream {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields,
+            Fields::Unnamed(_) => panic!("Tuple structs are not supported"),
+            Fields::Unit => panic!("Unit structs are not supported"),
+        },
+        Data::Enum(_) | Data::Union(_) => panic!("Only structs are supported"),
+    };
+
+    let name = input.ident;
+    let name_str = Literal::string(&name.to_string());
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let attributes = match StructAttributes::parse_shared(&input.attrs) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    if attributes.reorder.is_some() && !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "`reorder` is not supported on generic structs",
+        )
+        .to_compile_error();
+    }
+
+    let hlsl_fields = fields.named.iter().map(|field| {
+        let field_ty = &field.ty;
+        let field_name_str = Literal::string(&field.ident.as_ref().unwrap().to_string());
+
+        quote! {
+            ::crevice::hlsl::HlslField {
+                ty: <#field_ty as ::crevice::hlsl::Hlsl>::NAME,
+                name: #field_name_str,
+                hlsl_type: <#field_ty as ::crevice::hlsl::Hlsl>::TYPE,
+            }
+        }
+    });
+
+    // HLSL lays out struct members in the order they're declared, so fields
+    // must be listed in the same order as the constant buffer layout.
+    let fields_value = if let Some(reorder) = attributes.reorder {
+        let layout = Layout::named(reorder.layout(layout::HLSL_CBUFFER.mod_name));
+        let order = layout::field_order(&fields.named.iter().collect::<Vec<_>>(), layout);
+
+        quote! {
+            &::crevice::internal::permute([#( #hlsl_fields, )*], #order)
+        }
+    } else {
+        quote! {
+            &[#( #hlsl_fields, )*]
+        }
+    };
+
+    quote! {
+        unsafe impl #impl_generics ::crevice::hlsl::Hlsl for #name #ty_generics #where_clause {
+            const NAME: &'static str = #name_str;
+            const TYPE: ::crevice::hlsl::HlslType =
+                ::crevice::hlsl::HlslType::Struct(&::crevice::hlsl::HlslStructDefinition {
+                    name: #name_str,
+                    fields: #fields_value,
+                });
+        }
+
+        unsafe impl #impl_generics ::crevice::hlsl::HlslStruct for #name #ty_generics #where_clause {}
+    }
+}
@@ -0,0 +1,211 @@
+use std::path::PathBuf;
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::quote;
+use syn::{Ident, LitStr, Type};
+
+use crevice_glsl_source::{BlockLayout, Declaration, DeclarationKind};
+
+pub fn emit(path: LitStr) -> TokenStream {
+    match expand(&path) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn expand(path: &LitStr) -> syn::Result<TokenStream> {
+    // Proc macros can't find the file they're invoked from, so paths are
+    // relative to the crate root, like `CARGO_MANIFEST_DIR`.
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = PathBuf::from(root).join(path.value());
+
+    let source = std::fs::read_to_string(&full_path).map_err(|err| {
+        syn::Error::new(
+            path.span(),
+            format!("couldn't read `{}`: {}", full_path.display(), err),
+        )
+    })?;
+
+    let declarations = crevice_glsl_source::parse(&source).map_err(|err| error(path, &err))?;
+
+    let items = declarations
+        .iter()
+        .enumerate()
+        .map(|(index, declaration)| emit_declaration(declaration, &declarations[..index], path))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    // Including the file makes Cargo rebuild when it changes.
+    let full_path = LitStr::new(&full_path.to_string_lossy(), path.span());
+
+    Ok(quote! {
+        const _: &str = ::core::include_str!(#full_path);
+
+        #( #items )*
+    })
+}
+
+/// Emits the Rust struct for a declaration. Structs it uses must be among
+/// the `earlier` declarations.
+fn emit_declaration(
+    declaration: &Declaration,
+    earlier: &[Declaration],
+    path: &LitStr,
+) -> syn::Result<TokenStream> {
+    let name = ident(&declaration.name, path)?;
+
+    if let Some(member) = &declaration.runtime_array {
+        return Err(error(
+            path,
+            &crevice_glsl_source::Error {
+                line: member.line,
+                column: member.column,
+                message: format!(
+                    "`{}` is a runtime-sized array, which can't be part of a Rust struct; \
+                     give it a fixed length",
+                    member.name
+                ),
+            },
+        ));
+    }
+
+    let layouts = match declaration.kind {
+        DeclarationKind::Struct => quote! {
+            ::crevice::std140::AsStd140, ::crevice::std430::AsStd430,
+        },
+        DeclarationKind::Block(BlockLayout::Std140) => quote!(::crevice::std140::AsStd140,),
+        DeclarationKind::Block(BlockLayout::Std430) => quote!(::crevice::std430::AsStd430,),
+    };
+
+    // Doc comments are carried back into GLSL generated from the struct, so
+    // the GLSL comments are used as they are.
+    let doc = doc_attributes(&declaration.doc);
+
+    let fields = declaration
+        .members
+        .iter()
+        .map(|member| {
+            let field_name = ident(&member.name, path)?;
+            let element: Type = if let Some(rust) = primitive(&member.ty) {
+                syn::parse_str(rust)?
+            } else if earlier.iter().any(|declaration| {
+                declaration.kind == DeclarationKind::Struct && declaration.name == member.ty
+            }) {
+                Type::Path(syn::TypePath {
+                    qself: None,
+                    path: ident(&member.ty, path)?.into(),
+                })
+            } else {
+                return Err(error(
+                    path,
+                    &crevice_glsl_source::Error {
+                        line: member.line,
+                        column: member.column,
+                        message: format!("`{}` isn't a type Crevice supports", member.ty),
+                    },
+                ));
+            };
+
+            // Array dimensions are listed outermost first, so the innermost
+            // array is built first.
+            let ty = member.array.iter().rev().fold(quote!(#element), |ty, len| {
+                let len = Literal::usize_unsuffixed(*len);
+                quote!([#ty; #len])
+            });
+            let doc = doc_attributes(&member.doc);
+
+            Ok(quote! {
+                #doc
+                pub #field_name: #ty,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        #doc
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            #layouts
+            ::crevice::glsl::GlslStruct,
+        )]
+        #[allow(missing_docs, non_camel_case_types, non_snake_case)]
+        pub struct #name {
+            #( #fields )*
+        }
+    })
+}
+
+/// Reports an error in the GLSL file at the macro's path argument.
+fn error(path: &LitStr, err: &crevice_glsl_source::Error) -> syn::Error {
+    syn::Error::new(path.span(), format!("{}:{}", path.value(), err))
+}
+
+/// The Rust equivalent of a GLSL primitive type, if Crevice supports it.
+fn primitive(name: &str) -> Option<&'static str> {
+    const PRIMITIVES: &[(&str, &str)] = &[
+        ("float", "f32"),
+        ("double", "f64"),
+        ("int", "i32"),
+        ("uint", "u32"),
+        ("bool", "bool"),
+        ("vec2", "::crevice::internal::mint::Vector2<f32>"),
+        ("vec3", "::crevice::internal::mint::Vector3<f32>"),
+        ("vec4", "::crevice::internal::mint::Vector4<f32>"),
+        ("dvec2", "::crevice::internal::mint::Vector2<f64>"),
+        ("dvec3", "::crevice::internal::mint::Vector3<f64>"),
+        ("dvec4", "::crevice::internal::mint::Vector4<f64>"),
+        ("ivec2", "::crevice::internal::mint::Vector2<i32>"),
+        ("ivec3", "::crevice::internal::mint::Vector3<i32>"),
+        ("ivec4", "::crevice::internal::mint::Vector4<i32>"),
+        ("uvec2", "::crevice::internal::mint::Vector2<u32>"),
+        ("uvec3", "::crevice::internal::mint::Vector3<u32>"),
+        ("uvec4", "::crevice::internal::mint::Vector4<u32>"),
+        ("bvec2", "::crevice::internal::mint::Vector2<bool>"),
+        ("bvec3", "::crevice::internal::mint::Vector3<bool>"),
+        ("bvec4", "::crevice::internal::mint::Vector4<bool>"),
+        ("mat2", "::crevice::internal::mint::ColumnMatrix2<f32>"),
+        ("mat3", "::crevice::internal::mint::ColumnMatrix3<f32>"),
+        ("mat4", "::crevice::internal::mint::ColumnMatrix4<f32>"),
+        ("mat2x2", "::crevice::internal::mint::ColumnMatrix2<f32>"),
+        ("mat3x3", "::crevice::internal::mint::ColumnMatrix3<f32>"),
+        ("mat4x4", "::crevice::internal::mint::ColumnMatrix4<f32>"),
+        ("dmat2", "::crevice::internal::mint::ColumnMatrix2<f64>"),
+        ("dmat3", "::crevice::internal::mint::ColumnMatrix3<f64>"),
+        ("dmat4", "::crevice::internal::mint::ColumnMatrix4<f64>"),
+        ("dmat2x2", "::crevice::internal::mint::ColumnMatrix2<f64>"),
+        ("dmat3x3", "::crevice::internal::mint::ColumnMatrix3<f64>"),
+        ("dmat4x4", "::crevice::internal::mint::ColumnMatrix4<f64>"),
+    ];
+
+    PRIMITIVES
+        .iter()
+        .find(|(glsl, _)| *glsl == name)
+        .map(|(_, rust)| *rust)
+}
+
+/// Turns a GLSL identifier into a Rust one, using a raw identifier for Rust
+/// keywords.
+fn ident(name: &str, path: &LitStr) -> syn::Result<Ident> {
+    if syn::parse_str::<Ident>(name).is_ok() {
+        Ok(Ident::new(name, Span::call_site()))
+    } else if matches!(name, "self" | "Self" | "super" | "crate" | "_") {
+        Err(syn::Error::new(
+            path.span(),
+            format!("`{}` can't be used as a name in Rust", name),
+        ))
+    } else {
+        Ok(Ident::new_raw(name, Span::call_site()))
+    }
+}
+
+fn doc_attributes(doc: &str) -> TokenStream {
+    if doc.is_empty() {
+        return TokenStream::new();
+    }
+
+    let lines = doc.split('\n').map(|line| format!(" {}", line));
+    quote! {
+        #( #[doc = #lines] )*
+    }
+}
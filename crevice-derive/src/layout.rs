@@ -1,13 +1,111 @@
-use proc_macro2::{Span, TokenStream};
-use quote::{format_ident, quote};
-use syn::{parse_quote, Data, DeriveInput, Fields, Ident, Path, Type};
-
-pub fn emit(
-    input: DeriveInput,
-    trait_name: &'static str,
-    mod_name: &'static str,
-    min_struct_alignment: usize,
-) -> TokenStream {
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse_quote, Data, DeriveInput, Field, Fields, Ident, Path, Type};
+
+use crate::attributes::StructAttributes;
+
+/// Describes how fields are placed after one another within a struct.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Packing {
+    /// Every field is aligned to its own alignment and the struct is padded out
+    /// to a multiple of its alignment, as in `std140` and `std430`.
+    Aligned,
+
+    /// Fields may not straddle a 16-byte register and the struct is not padded
+    /// out at the end, as in HLSL constant buffers.
+    Registers,
+}
+
+/// The rules of a layout that structs can be derived for.
+pub struct Layout {
+    /// The name of the layout's marker trait, like `Std140`.
+    pub trait_name: &'static str,
+
+    /// The name of the module the layout lives in, like `std140`.
+    pub mod_name: &'static str,
+
+    /// The smallest alignment a struct can have.
+    pub min_struct_alignment: usize,
+
+    /// How fields are placed after one another.
+    pub packing: Packing,
+}
+
+pub const STD140: Layout = Layout {
+    trait_name: "Std140",
+    mod_name: "std140",
+    min_struct_alignment: 16,
+    packing: Packing::Aligned,
+};
+
+pub const STD430: Layout = Layout {
+    trait_name: "Std430",
+    mod_name: "std430",
+    min_struct_alignment: 0,
+    packing: Packing::Aligned,
+};
+
+pub const HLSL_CBUFFER: Layout = Layout {
+    trait_name: "HlslCbuffer",
+    mod_name: "hlsl_cbuffer",
+    min_struct_alignment: 16,
+    packing: Packing::Registers,
+};
+
+pub const MSL: Layout = Layout {
+    trait_name: "Msl",
+    mod_name: "msl",
+    min_struct_alignment: 0,
+    packing: Packing::Aligned,
+};
+
+pub const OPENCL: Layout = Layout {
+    trait_name: "OpenCl",
+    mod_name: "opencl",
+    min_struct_alignment: 0,
+    packing: Packing::Aligned,
+};
+
+impl Layout {
+    /// Finds a layout by its module name.
+    pub fn named(mod_name: &str) -> &'static Layout {
+        [&STD140, &STD430, &HLSL_CBUFFER, &MSL, &OPENCL]
+            .iter()
+            .copied()
+            .find(|layout| layout.mod_name == mod_name)
+            .expect("unknown layout")
+    }
+}
+
+pub fn emit(input: DeriveInput, layout: &'static Layout) -> TokenStream {
+    let Layout {
+        trait_name,
+        mod_name,
+        min_struct_alignment,
+        packing,
+    } = *layout;
+
+    let attributes = match StructAttributes::parse(&input.attrs, mod_name) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    if attributes.has_assertions() && !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "layout assertions are not supported on generic structs",
+        )
+        .to_compile_error();
+    }
+
+    if attributes.reorder.is_some() && !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "`reorder` is not supported on generic structs",
+        )
+        .to_compile_error();
+    }
+
     let mod_name = Ident::new(mod_name, Span::call_site());
     let trait_name = Ident::new(trait_name, Span::call_site());
 
@@ -50,6 +148,14 @@ pub fn emit(
         }
     };
 
+    // Gives an expression returning the number of bytes the type occupies in
+    // the layout, which can be less than its Rust size.
+    let layout_size_of_ty = |ty: &Type| {
+        quote! {
+            <<#ty as #as_trait_path>::Output as ::crevice::layout::LayoutValue<::crevice::layout::#trait_name>>::SIZE
+        }
+    };
+
     let field_alignments = fields.iter().map(|field| layout_alignment_of_ty(&field.ty));
     let struct_alignment = quote! {
         ::crevice::internal::max_arr([
@@ -125,44 +231,154 @@ pub fn emit(
         })
         .collect();
 
-    let generated_struct_fields: TokenStream = fields
-        .iter()
-        .enumerate()
-        .map(|(index, field)| {
+    // Reordered structs can't know their field order until the alignment of
+    // each field is known in const evaluation, and register packing can place a
+    // field inside the trailing padding of an array before it. Both store their
+    // fields as bytes at offsets computed by `FieldLayout`.
+    let layout_const = format_ident!("_{}__{}Layout", input_name, trait_name);
+    let byte_storage = attributes.reorder.is_some() || packing == Packing::Registers;
+
+    let generated_struct_fields = if byte_storage {
+        quote! {
+            _bytes: [u8; #layout_const.size],
+        }
+    } else {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let field_name = field.ident.as_ref().unwrap();
+                let field_ty = layout_version_of_ty(&field.ty);
+                let pad_field_name = format_ident!("_pad{}", index);
+                let pad_fn = &pad_fns[index];
+
+                quote! {
+                    #field_name: #field_ty,
+                    #pad_field_name: [u8; #pad_fn()],
+                }
+            })
+            .collect()
+    };
+
+    let field_layout = if byte_storage {
+        let order = if let Some(reorder) = attributes.reorder {
+            field_order(&fields, Layout::named(reorder.layout(layout.mod_name)))
+        } else {
+            let indices = 0..fields.len();
+            quote!([#(#indices,)*])
+        };
+        let field_count = fields.len();
+        let sizes = fields.iter().map(|field| layout_size_of_ty(&field.ty));
+        let alignments = fields.iter().map(|field| layout_alignment_of_ty(&field.ty));
+        let packed = packing == Packing::Registers;
+
+        quote! {
+            #[allow(non_upper_case_globals)]
+            const #layout_const: ::crevice::internal::FieldLayout<#field_count> =
+                ::crevice::internal::reordered_layout(
+                    #order,
+                    [#(#sizes,)*],
+                    [#(#alignments,)*],
+                    #struct_alignment,
+                    #packed,
+                );
+        }
+    } else {
+        quote!()
+    };
+
+    let as_trait_body = if byte_storage {
+        let field_writes = fields.iter().enumerate().map(|(index, field)| {
             let field_name = field.ident.as_ref().unwrap();
-            let field_ty = layout_version_of_ty(&field.ty);
-            let pad_field_name = format_ident!("_pad{}", index);
-            let pad_fn = &pad_fns[index];
 
             quote! {
-                #field_name: #field_ty,
-                #pad_field_name: [u8; #pad_fn()],
+                let value = self.#field_name.#as_trait_method();
+                let bytes = ::crevice::layout::LayoutValue::<::crevice::layout::#trait_name>::as_bytes(&value);
+                let offset = #layout_const.offsets[#index];
+                output._bytes[offset..offset + bytes.len()].copy_from_slice(bytes);
             }
-        })
-        .collect();
+        });
 
-    let generated_struct_field_init: TokenStream = fields
-        .iter()
-        .map(|field| {
+        quote! {
+            let mut output: Self::Output = ::crevice::internal::bytemuck::Zeroable::zeroed();
+            #(#field_writes)*
+            output
+        }
+    } else {
+        let field_init = fields.iter().map(|field| {
             let field_name = field.ident.as_ref().unwrap();
 
             quote! {
                 #field_name: self.#field_name.#as_trait_method(),
             }
+        });
+
+        quote! {
+            Self::Output {
+                #(#field_init)*
+
+                ..::crevice::internal::bytemuck::Zeroable::zeroed()
+            }
+        }
+    };
+
+    let input_struct_field_init: TokenStream = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let field_name = field.ident.as_ref().unwrap();
+            let layout_ty = layout_version_of_ty(&field.ty);
+
+            let layout_size = layout_size_of_ty(&field.ty);
+
+            // Only the field's layout size is stored, so the rest of its Rust
+            // representation is left zeroed.
+            let value = if byte_storage {
+                quote! {{
+                    let mut value: #layout_ty = ::crevice::internal::bytemuck::Zeroable::zeroed();
+                    let offset = #layout_const.offsets[#index];
+                    ::crevice::internal::bytemuck::bytes_of_mut(&mut value)[..#layout_size]
+                        .copy_from_slice(&input._bytes[offset..offset + #layout_size]);
+                    value
+                }}
+            } else {
+                quote!(input.#field_name)
+            };
+
+            quote! {
+                #field_name: #as_trait_path::#from_trait_method(#value),
+            }
         })
         .collect();
 
-    let input_struct_field_init: TokenStream = fields
+    let reflect_fields: TokenStream = fields
         .iter()
-        .map(|field| {
+        .enumerate()
+        .map(|(index, field)| {
             let field_name = field.ident.as_ref().unwrap();
+            let field_name_str = Literal::string(&field_name.to_string());
+            let field_ty = layout_version_of_ty(&field.ty);
+
+            let offset = if byte_storage {
+                quote!(#layout_const.offsets[#index])
+            } else {
+                quote!(::core::mem::offset_of!(#generated_name #ty_generics, #field_name))
+            };
 
             quote! {
-                #field_name: #as_trait_path::#from_trait_method(input.#field_name),
+                ::crevice::reflect::FieldInfo {
+                    name: #field_name_str,
+                    offset: #offset,
+                    size: <#field_ty as ::crevice::reflect::Reflect>::LAYOUT_INFO.size,
+                    glsl_type: <#field_ty as ::crevice::reflect::Reflect>::LAYOUT_INFO.name,
+                    layout: <#field_ty as ::crevice::reflect::Reflect>::LAYOUT_INFO,
+                },
             }
         })
         .collect();
 
+    let input_name_str = Literal::string(&input_name.to_string());
+
     let struct_definition = quote! {
         #[derive(Debug, Clone, Copy)]
         #[repr(C)]
@@ -173,59 +389,83 @@ pub fn emit(
         }
     };
 
-    let debug_methods = if cfg!(feature = "debug-methods") {
-        let debug_fields: TokenStream = fields
-            .iter()
-            .map(|field| {
-                let field_name = field.ident.as_ref().unwrap();
-                let field_ty = &field.ty;
-
-                quote! {
-                    fields.push(Field {
-                        name: stringify!(#field_name),
-                        size: ::core::mem::size_of::<#field_ty>(),
-                        offset: (&zeroed.#field_name as *const _ as usize)
-                            - (&zeroed as *const _ as usize),
-                    });
-                }
-            })
-            .collect();
-
-        quote! {
-            impl #impl_generics #generated_name #ty_generics #where_clause {
-                fn debug_metrics() -> String {
-                    let size = ::core::mem::size_of::<Self>();
-                    let align = <Self as #trait_path>::ALIGNMENT;
+    // Layout assertions from `#[crevice(...)]` become const assertions, spanned
+    // to the attribute so that failures point at it.
+    let mut assertions = TokenStream::new();
 
-                    let zeroed: Self = ::crevice::internal::bytemuck::Zeroable::zeroed();
+    if let Some(size) = &attributes.assert_size {
+        let message = format!("{} size of `{}` is not {}", mod_name, input_name, size);
+        assertions.extend(quote_spanned! {size.span()=>
+            if ::core::mem::size_of::<#generated_name>() != #size {
+                panic!(#message);
+            }
+        });
+    }
 
-                    #[derive(Debug)]
-                    struct Field {
-                        name: &'static str,
-                        offset: usize,
-                        size: usize,
-                    }
-                    let mut fields = Vec::new();
+    if let Some(align) = &attributes.assert_align {
+        let message = format!(
+            "{} alignment of `{}` is not {}",
+            mod_name, input_name, align
+        );
+        assertions.extend(quote_spanned! {align.span()=>
+            if <#generated_name as #trait_path>::ALIGNMENT != #align {
+                panic!(#message);
+            }
+        });
+    }
 
-                    #debug_fields
+    if let Some(max_size) = &attributes.max_size {
+        let message = format!(
+            "{} size of `{}` is larger than {}",
+            mod_name, input_name, max_size
+        );
+        assertions.extend(quote_spanned! {max_size.span()=>
+            if ::core::mem::size_of::<#generated_name>() > #max_size {
+                panic!(#message);
+            }
+        });
+    }
 
-                    format!("Size {}, Align {}, fields: {:#?}", size, align, fields)
-                }
+    let assertions = if assertions.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            const _: () = {
+                #assertions
+            };
+        }
+    };
 
-                fn debug_definitions() -> &'static str {
-                    stringify!(
-                        #struct_definition
-                        #pad_fn_impls
+    let pad_fn_impls = if byte_storage { quote!() } else { pad_fn_impls };
+
+    // Aligned structs are padded out to their alignment, which is at least the
+    // layout's array element alignment, so they need no padding in arrays.
+    let padded = match packing {
+        Packing::Aligned => quote!(Self),
+        Packing::Registers => quote! {
+            ::crevice::internal::Padded<
+                #generated_name,
+                {
+                    ::crevice::internal::array_padding::<::crevice::layout::#trait_name>(
+                        ::core::mem::size_of::<#generated_name>(),
+                        <#generated_name as #trait_path>::ALIGNMENT,
                     )
-                }
-            }
+                },
+            >
+        },
+    };
+
+    let array_element_impl = quote! {
+        unsafe impl #impl_generics ::crevice::layout::ArrayElement<::crevice::layout::#trait_name>
+            for #generated_name #ty_generics #where_clause
+        {
+            type Padded = #padded;
         }
-    } else {
-        quote!()
     };
 
     quote! {
         #pad_fn_impls
+        #field_layout
         #struct_definition
 
         impl #impl_generics #generated_name #ty_generics #where_clause {
@@ -242,15 +482,26 @@ pub fn emit(
             const ALIGNMENT: usize = #struct_alignment;
         }
 
+        #array_element_impl
+
+        unsafe impl #impl_generics ::crevice::reflect::Reflect for #generated_name #ty_generics #where_clause {
+            const LAYOUT_INFO: &'static ::crevice::reflect::LayoutInfo = &::crevice::reflect::LayoutInfo {
+                name: #input_name_str,
+                size: ::core::mem::size_of::<Self>(),
+                alignment: <Self as #trait_path>::ALIGNMENT,
+                fields: &[
+                    #reflect_fields
+                ],
+                array: None,
+                columns: None,
+            };
+        }
+
         impl #impl_generics #as_trait_path for #input_name #ty_generics #where_clause {
             type Output = #generated_name;
 
             fn #as_trait_method(&self) -> Self::Output {
-                Self::Output {
-                    #generated_struct_field_init
-
-                    ..::crevice::internal::bytemuck::Zeroable::zeroed()
-                }
+                #as_trait_body
             }
 
             fn #from_trait_method(input: Self::Output) -> Self {
@@ -260,6 +511,43 @@ pub fn emit(
             }
         }
 
-        #debug_methods
+        #assertions
+    }
+}
+
+/// Gives an expression for the order of the given fields under
+/// `#[crevice(reorder)]`, which reduces padding under the rules of `layout`.
+///
+/// Every field must implement the layout's `As` trait.
+pub fn field_order(fields: &[&Field], layout: &Layout) -> TokenStream {
+    let mod_name = Ident::new(layout.mod_name, Span::call_site());
+    let trait_name = Ident::new(layout.trait_name, Span::call_site());
+    let as_trait_name = format_ident!("As{}", trait_name);
+
+    let sizes = fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote! {
+            <<#ty as ::crevice::#mod_name::#as_trait_name>::Output as ::crevice::layout::LayoutValue<::crevice::layout::#trait_name>>::SIZE
+        }
+    });
+
+    let alignments: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            quote!(<<#ty as ::crevice::#mod_name::#as_trait_name>::Output as ::crevice::#mod_name::#trait_name>::ALIGNMENT)
+        })
+        .collect();
+
+    let min_struct_alignment = layout.min_struct_alignment;
+    let packed = layout.packing == Packing::Registers;
+
+    quote! {
+        ::crevice::internal::field_order(
+            [#(#sizes,)*],
+            [#(#alignments,)*],
+            ::crevice::internal::max_arr([#min_struct_alignment, #(#alignments,)*]),
+            #packed,
+        )
     }
 }
@@ -1,30 +1,94 @@
+mod attributes;
 mod glsl;
+mod hlsl;
+mod include_glsl;
 mod layout;
+mod names;
+mod opencl;
+mod wgsl;
 
 use proc_macro::TokenStream as CompilerTokenStream;
 
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, LitStr};
 
-#[proc_macro_derive(AsStd140)]
+#[proc_macro_derive(AsStd140, attributes(crevice))]
 pub fn derive_as_std140(input: CompilerTokenStream) -> CompilerTokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let expanded = layout::emit(input, "Std140", "std140", 16);
+    let expanded = layout::emit(input, &layout::STD140);
 
     CompilerTokenStream::from(expanded)
 }
 
-#[proc_macro_derive(AsStd430)]
+#[proc_macro_derive(AsStd430, attributes(crevice))]
 pub fn derive_as_std430(input: CompilerTokenStream) -> CompilerTokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let expanded = layout::emit(input, "Std430", "std430", 0);
+    let expanded = layout::emit(input, &layout::STD430);
 
     CompilerTokenStream::from(expanded)
 }
 
-#[proc_macro_derive(GlslStruct)]
+#[proc_macro_derive(AsHlslCbuffer, attributes(crevice))]
+pub fn derive_as_hlsl_cbuffer(input: CompilerTokenStream) -> CompilerTokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let expanded = layout::emit(input, &layout::HLSL_CBUFFER);
+
+    CompilerTokenStream::from(expanded)
+}
+
+#[proc_macro_derive(AsMsl, attributes(crevice))]
+pub fn derive_as_msl(input: CompilerTokenStream) -> CompilerTokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let expanded = layout::emit(input, &layout::MSL);
+
+    CompilerTokenStream::from(expanded)
+}
+
+#[proc_macro_derive(AsOpenCl, attributes(crevice))]
+pub fn derive_as_opencl(input: CompilerTokenStream) -> CompilerTokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let typedef = opencl::emit(&input);
+    let mut expanded = layout::emit(input, &layout::OPENCL);
+    expanded.extend(typedef);
+
+    CompilerTokenStream::from(expanded)
+}
+
+#[proc_macro_derive(GlslStruct, attributes(crevice))]
 pub fn derive_glsl_struct(input: CompilerTokenStream) -> CompilerTokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let expanded = glsl::emit(input);
 
     CompilerTokenStream::from(expanded)
 }
+
+#[proc_macro_derive(GlslValue, attributes(crevice))]
+pub fn derive_glsl_value(input: CompilerTokenStream) -> CompilerTokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let expanded = glsl::emit_value(input);
+
+    CompilerTokenStream::from(expanded)
+}
+
+#[proc_macro_derive(HlslStruct, attributes(crevice))]
+pub fn derive_hlsl_struct(input: CompilerTokenStream) -> CompilerTokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let expanded = hlsl::emit(input);
+
+    CompilerTokenStream::from(expanded)
+}
+
+#[proc_macro_derive(WgslStruct, attributes(crevice))]
+pub fn derive_wgsl_struct(input: CompilerTokenStream) -> CompilerTokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let expanded = wgsl::emit(input);
+
+    CompilerTokenStream::from(expanded)
+}
+
+#[proc_macro]
+pub fn include_glsl(input: CompilerTokenStream) -> CompilerTokenStream {
+    let input = parse_macro_input!(input as LitStr);
+    let expanded = include_glsl::emit(input);
+
+    CompilerTokenStream::from(expanded)
+}
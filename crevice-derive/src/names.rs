@@ -0,0 +1,331 @@
+use syn::ext::IdentExt;
+use syn::Ident;
+
+use crate::attributes::Rename;
+
+/// Gives the name to use in GLSL for a Rust identifier.
+///
+/// Names that GLSL reserves are an error unless a [`Rename`] strategy is
+/// given, in which case they're changed to a valid name.
+pub fn glsl_name(ident: &Ident, rename: Option<&Rename>) -> syn::Result<String> {
+    let name = ident.unraw().to_string();
+
+    if !name.is_ascii() {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!("`{}` is not a valid GLSL identifier", name),
+        ));
+    }
+
+    let problem = match problem(&name) {
+        Some(problem) => problem,
+        None => return Ok(name),
+    };
+
+    let renamed = match rename {
+        Some(Rename::Prefix(prefix)) => {
+            collapse_underscores(&format!("{}{}", prefix.value(), name))
+        }
+        Some(Rename::Suffix(suffix)) => {
+            collapse_underscores(&format!("{}{}", name, suffix.value()))
+        }
+        None => {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "`{}` can't be used in GLSL because it {}; rename it or add \
+                     `#[crevice(rename(suffix = \"_\"))]` to the struct",
+                    name, problem
+                ),
+            ))
+        }
+    };
+
+    match self::problem(&renamed) {
+        None => Ok(renamed),
+        Some(problem) => Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "`{}` was renamed to `{}`, which can't be used in GLSL because it {}",
+                name, renamed, problem
+            ),
+        )),
+    }
+}
+
+/// Describes why a name can't be used in GLSL, if it can't.
+fn problem(name: &str) -> Option<&'static str> {
+    if RESERVED.binary_search(&name).is_ok() {
+        Some("is a reserved word")
+    } else if name.starts_with("gl_") {
+        Some("starts with `gl_`")
+    } else if name.contains("__") {
+        Some("contains two consecutive underscores")
+    } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        || name.starts_with(|c: char| c.is_ascii_digit())
+    {
+        Some("is not a valid identifier")
+    } else {
+        None
+    }
+}
+
+/// Replaces each run of underscores in a name with a single underscore.
+fn collapse_underscores(name: &str) -> String {
+    let mut output = String::with_capacity(name.len());
+
+    for c in name.chars() {
+        if !(c == '_' && output.ends_with('_')) {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+/// Keywords and words reserved for future use in GLSL 4.60, GLSL ES 3.20 and
+/// Vulkan GLSL, sorted so they can be binary searched.
+const RESERVED: &[&str] = &[
+    "active",
+    "asm",
+    "atomic_uint",
+    "attribute",
+    "bool",
+    "break",
+    "buffer",
+    "bvec2",
+    "bvec3",
+    "bvec4",
+    "case",
+    "cast",
+    "centroid",
+    "class",
+    "coherent",
+    "common",
+    "const",
+    "continue",
+    "default",
+    "discard",
+    "dmat2",
+    "dmat2x2",
+    "dmat2x3",
+    "dmat2x4",
+    "dmat3",
+    "dmat3x2",
+    "dmat3x3",
+    "dmat3x4",
+    "dmat4",
+    "dmat4x2",
+    "dmat4x3",
+    "dmat4x4",
+    "do",
+    "double",
+    "dvec2",
+    "dvec3",
+    "dvec4",
+    "else",
+    "enum",
+    "extern",
+    "external",
+    "false",
+    "filter",
+    "fixed",
+    "flat",
+    "float",
+    "for",
+    "fvec2",
+    "fvec3",
+    "fvec4",
+    "goto",
+    "half",
+    "highp",
+    "hvec2",
+    "hvec3",
+    "hvec4",
+    "if",
+    "iimage1D",
+    "iimage1DArray",
+    "iimage2D",
+    "iimage2DArray",
+    "iimage2DMS",
+    "iimage2DMSArray",
+    "iimage2DRect",
+    "iimage3D",
+    "iimageBuffer",
+    "iimageCube",
+    "iimageCubeArray",
+    "image1D",
+    "image1DArray",
+    "image2D",
+    "image2DArray",
+    "image2DMS",
+    "image2DMSArray",
+    "image2DRect",
+    "image3D",
+    "imageBuffer",
+    "imageCube",
+    "imageCubeArray",
+    "in",
+    "inline",
+    "inout",
+    "input",
+    "int",
+    "interface",
+    "invariant",
+    "isampler1D",
+    "isampler1DArray",
+    "isampler2D",
+    "isampler2DArray",
+    "isampler2DMS",
+    "isampler2DMSArray",
+    "isampler2DRect",
+    "isampler3D",
+    "isamplerBuffer",
+    "isamplerCube",
+    "isamplerCubeArray",
+    "isubpassInput",
+    "isubpassInputMS",
+    "itexture1D",
+    "itexture1DArray",
+    "itexture2D",
+    "itexture2DArray",
+    "itexture2DMS",
+    "itexture2DMSArray",
+    "itexture2DRect",
+    "itexture3D",
+    "itextureBuffer",
+    "itextureCube",
+    "itextureCubeArray",
+    "ivec2",
+    "ivec3",
+    "ivec4",
+    "layout",
+    "long",
+    "lowp",
+    "mat2",
+    "mat2x2",
+    "mat2x3",
+    "mat2x4",
+    "mat3",
+    "mat3x2",
+    "mat3x3",
+    "mat3x4",
+    "mat4",
+    "mat4x2",
+    "mat4x3",
+    "mat4x4",
+    "mediump",
+    "namespace",
+    "noinline",
+    "noperspective",
+    "out",
+    "output",
+    "partition",
+    "patch",
+    "precise",
+    "precision",
+    "public",
+    "readonly",
+    "resource",
+    "restrict",
+    "return",
+    "sample",
+    "sampler",
+    "sampler1D",
+    "sampler1DArray",
+    "sampler1DArrayShadow",
+    "sampler1DShadow",
+    "sampler2D",
+    "sampler2DArray",
+    "sampler2DArrayShadow",
+    "sampler2DMS",
+    "sampler2DMSArray",
+    "sampler2DRect",
+    "sampler2DRectShadow",
+    "sampler2DShadow",
+    "sampler3D",
+    "sampler3DRect",
+    "samplerBuffer",
+    "samplerCube",
+    "samplerCubeArray",
+    "samplerCubeArrayShadow",
+    "samplerCubeShadow",
+    "samplerShadow",
+    "shared",
+    "short",
+    "sizeof",
+    "smooth",
+    "static",
+    "struct",
+    "subpassInput",
+    "subpassInputMS",
+    "subroutine",
+    "superp",
+    "switch",
+    "template",
+    "texture1D",
+    "texture1DArray",
+    "texture2D",
+    "texture2DArray",
+    "texture2DMS",
+    "texture2DMSArray",
+    "texture2DRect",
+    "texture3D",
+    "textureBuffer",
+    "textureCube",
+    "textureCubeArray",
+    "this",
+    "true",
+    "typedef",
+    "uimage1D",
+    "uimage1DArray",
+    "uimage2D",
+    "uimage2DArray",
+    "uimage2DMS",
+    "uimage2DMSArray",
+    "uimage2DRect",
+    "uimage3D",
+    "uimageBuffer",
+    "uimageCube",
+    "uimageCubeArray",
+    "uint",
+    "uniform",
+    "union",
+    "unsigned",
+    "usampler1D",
+    "usampler1DArray",
+    "usampler2D",
+    "usampler2DArray",
+    "usampler2DMS",
+    "usampler2DMSArray",
+    "usampler2DRect",
+    "usampler3D",
+    "usamplerBuffer",
+    "usamplerCube",
+    "usamplerCubeArray",
+    "using",
+    "usubpassInput",
+    "usubpassInputMS",
+    "utexture1D",
+    "utexture1DArray",
+    "utexture2D",
+    "utexture2DArray",
+    "utexture2DMS",
+    "utexture2DMSArray",
+    "utexture2DRect",
+    "utexture3D",
+    "utextureBuffer",
+    "utextureCube",
+    "utextureCubeArray",
+    "uvec2",
+    "uvec3",
+    "uvec4",
+    "varying",
+    "vec2",
+    "vec3",
+    "vec4",
+    "void",
+    "volatile",
+    "while",
+    "writeonly",
+];
@@ -0,0 +1,71 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields};
+
+use crate::attributes::StructAttributes;
+use crate::layout::{self, Layout};
+
+/// Generates the `OpenClType` and `OpenClStruct` implementations for the
+/// OpenCL C version of a struct, which is generated by `layout::emit`.
+pub fn emit(input: &DeriveInput) -> TokenStream {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields,
+            Fields::Unnamed(_) => panic!("Tuple structs are not supported"),
+            Fields::Unit => panic!("Unit structs are not supported"),
+        },
+        Data::Enum(_) | Data::Union(_) => panic!("Only structs are supported"),
+    };
+
+    let name = &input.ident;
+    let name_str = Literal::string(&name.to_string());
+    let generated_name = format_ident!("OpenCl{}", name);
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Invalid attributes are reported by `layout::emit`.
+    let attributes = match StructAttributes::parse_shared(&input.attrs) {
+        Ok(attributes) => attributes,
+        Err(_) => return TokenStream::new(),
+    };
+
+    if attributes.reorder.is_some() && !input.generics.params.is_empty() {
+        return TokenStream::new();
+    }
+
+    let opencl_fields = fields.named.iter().map(|field| {
+        let field_ty = &field.ty;
+        let field_name_str = Literal::string(&field.ident.as_ref().unwrap().to_string());
+
+        quote! {
+            ::crevice::opencl::OpenClField {
+                ty: <<#field_ty as ::crevice::opencl::AsOpenCl>::Output as ::crevice::opencl::OpenClType>::NAME,
+                name: #field_name_str,
+                array: <<#field_ty as ::crevice::opencl::AsOpenCl>::Output as ::crevice::opencl::OpenClType>::ARRAY,
+            }
+        }
+    });
+
+    let fields_value = if let Some(reorder) = attributes.reorder {
+        let layout = Layout::named(reorder.layout(layout::OPENCL.mod_name));
+        let order = layout::field_order(&fields.named.iter().collect::<Vec<_>>(), layout);
+
+        quote! {
+            &::crevice::internal::permute([#( #opencl_fields, )*], #order)
+        }
+    } else {
+        quote! {
+            &[#( #opencl_fields, )*]
+        }
+    };
+
+    quote! {
+        unsafe impl #impl_generics ::crevice::opencl::OpenClType for #generated_name #ty_generics #where_clause {
+            const NAME: &'static str = #name_str;
+        }
+
+        unsafe impl #impl_generics ::crevice::opencl::OpenClStruct for #generated_name #ty_generics #where_clause {
+            const FIELDS: &'static [::crevice::opencl::OpenClField] = #fields_value;
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+pub fn emit(input: DeriveInput) -> TokenStream {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields,
+            Fields::Unnamed(_) => panic!("Tuple structs are not supported"),
+            Fields::Unit => panic!("Unit structs are not supported"),
+        },
+        Data::Enum(_) | Data::Union(_) => panic!("Only structs are supported"),
+    };
+
+    let name = input.ident;
+    let name_str = Literal::string(&name.to_string());
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Fields are listed in declaration order. Reordered layouts are matched up
+    // by name when definitions are generated.
+    let wgsl_fields = fields.named.iter().map(|field| {
+        let field_ty = &field.ty;
+        let field_name_str = Literal::string(&field.ident.as_ref().unwrap().to_string());
+
+        quote! {
+            ::crevice::wgsl::WgslField {
+                ty: <#field_ty as ::crevice::wgsl::Wgsl>::NAME,
+                name: #field_name_str,
+                wgsl_type: <#field_ty as ::crevice::wgsl::Wgsl>::TYPE,
+            }
+        }
+    });
+
+    quote! {
+        unsafe impl #impl_generics ::crevice::wgsl::Wgsl for #name #ty_generics #where_clause {
+            const NAME: &'static str = #name_str;
+            const TYPE: ::crevice::wgsl::WgslType =
+                ::crevice::wgsl::WgslType::Struct(&::crevice::wgsl::WgslStructDefinition {
+                    name: #name_str,
+                    fields: &[#( #wgsl_fields, )*],
+                });
+        }
+
+        unsafe impl #impl_generics ::crevice::wgsl::WgslStruct for #name #ty_generics #where_clause {}
+    }
+}
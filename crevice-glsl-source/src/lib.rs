@@ -0,0 +1,777 @@
+//! A parser for the parts of GLSL source that describe memory: struct
+//! definitions and uniform and storage buffer blocks. Everything else, like
+//! functions, global variables and preprocessor directives, is skipped.
+//!
+//! This crate is an implementation detail of Crevice, shared by
+//! `crevice::glsl::validate_block` and `crevice::include_glsl!`. It has no
+//! stable API.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::fmt;
+
+/// A struct or interface block declared in GLSL.
+pub struct Declaration {
+    pub name: String,
+
+    /// The `//` comment lines directly above the declaration.
+    pub doc: String,
+    pub kind: DeclarationKind,
+    pub members: Vec<Member>,
+
+    /// A runtime-sized array ending a storage buffer, which is kept apart from
+    /// the other members since it has no size.
+    pub runtime_array: Option<Member>,
+
+    /// The position of the declaration's name.
+    pub line: usize,
+    pub column: usize,
+}
+
+/// What kind of declaration a [`Declaration`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclarationKind {
+    Struct,
+    Block(BlockLayout),
+}
+
+/// The memory layout of an interface block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockLayout {
+    Std140,
+    Std430,
+}
+
+impl BlockLayout {
+    /// The name of the layout, like `std140`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Std140 => "std140",
+            Self::Std430 => "std430",
+        }
+    }
+}
+
+/// A member of a struct or block.
+pub struct Member {
+    pub name: String,
+
+    /// The `//` comment lines directly above the member.
+    pub doc: String,
+
+    /// The name of the member's type, like `vec3` or the name of a struct.
+    pub ty: String,
+
+    /// The lengths of the member's array dimensions, outermost first. Empty if
+    /// the member isn't an array. A runtime-sized array's own dimension isn't
+    /// included.
+    pub array: Vec<usize>,
+
+    /// The values of the member's `offset` and `align` layout qualifiers.
+    pub offset: Option<usize>,
+    pub align: Option<usize>,
+
+    /// The position of the member's type.
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An error in GLSL source, located by line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Parses every struct and uniform or storage buffer block in `source`.
+pub fn parse(source: &str) -> Result<Vec<Declaration>, Error> {
+    let mut constants = Vec::new();
+    let tokens = lex(source, &mut constants)?;
+    Parser {
+        tokens,
+        position: 0,
+        constants,
+        declarations: Vec::new(),
+    }
+    .parse()
+}
+
+/// Qualifiers that may come before a member's type and don't change its
+/// layout.
+const MEMBER_QUALIFIERS: &[&str] = &[
+    "highp",
+    "mediump",
+    "lowp",
+    "precise",
+    "invariant",
+    "readonly",
+    "writeonly",
+    "coherent",
+    "volatile",
+    "restrict",
+    "flat",
+    "smooth",
+    "noperspective",
+    "centroid",
+];
+
+/// Qualifiers that may come before a block's storage qualifier.
+const BLOCK_QUALIFIERS: &[&str] = &["readonly", "writeonly", "coherent", "volatile", "restrict"];
+
+#[derive(Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Number(String),
+    Punct(char),
+}
+
+#[derive(Clone)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+    column: usize,
+
+    /// The `//` comment lines directly above this token, if it starts a line.
+    doc: String,
+}
+
+impl Token {
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(&self.kind, TokenKind::Ident(ident) if ident == name)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct(c)
+    }
+
+    fn describe(&self) -> String {
+        match &self.kind {
+            TokenKind::Ident(text) | TokenKind::Number(text) => format!("`{}`", text),
+            TokenKind::Punct(c) => format!("`{}`", c),
+        }
+    }
+}
+
+/// Parses an integer literal, like `16`, `16u` or `0x10`.
+fn parse_int(text: &str) -> Option<usize> {
+    let text = text.trim_end_matches(['u', 'U']);
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Splits `source` into tokens, dropping comments and preprocessor directives.
+/// Integer constants defined with `#define`, which are often used as array
+/// lengths, are added to `defines`.
+fn lex(source: &str, defines: &mut Vec<(String, usize)>) -> Result<Vec<Token>, Error> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut doc: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    let mut line = 1;
+    let mut line_start = 0;
+    // Whether only whitespace has been seen on the current line.
+    let mut at_line_start = true;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i - line_start + 1;
+
+        if c == '\n' {
+            if at_line_start {
+                // A blank line separates comments from what comes after.
+                doc.clear();
+            }
+            i += 1;
+            line += 1;
+            line_start = i;
+            at_line_start = true;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            let end = (i..chars.len())
+                .find(|&j| chars[j] == '\n')
+                .unwrap_or(chars.len());
+
+            if at_line_start {
+                let text: String = chars[i + 2..end].iter().collect();
+                let text = text.strip_prefix(' ').unwrap_or(&text);
+                doc.push(text.trim_end().to_owned());
+            }
+
+            // Keep the newline so that the next line starts fresh.
+            i = end;
+            at_line_start = false;
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let start_line = line;
+            i += 2;
+            loop {
+                if i + 1 >= chars.len() {
+                    return Err(Error {
+                        line: start_line,
+                        column,
+                        message: "unterminated block comment".to_owned(),
+                    });
+                }
+                if chars[i] == '*' && chars[i + 1] == '/' {
+                    i += 2;
+                    break;
+                }
+                if chars[i] == '\n' {
+                    line += 1;
+                    line_start = i + 1;
+                }
+                i += 1;
+            }
+            doc.clear();
+        } else if c == '#' && at_line_start {
+            // Preprocessor directives run to the end of the line, unless it
+            // ends with a backslash.
+            let start = i;
+            while i < chars.len() && chars[i] != '\n' {
+                if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') {
+                    i += 1;
+                    line += 1;
+                    line_start = i + 1;
+                }
+                i += 1;
+            }
+
+            let directive: String = chars[start + 1..i].iter().collect();
+            let mut words = directive.split_whitespace();
+            if let (Some("define"), Some(name), Some(value), None) =
+                (words.next(), words.next(), words.next(), words.next())
+            {
+                if let Some(value) = parse_int(value) {
+                    defines.push((name.to_owned(), value));
+                }
+            }
+            doc.clear();
+            at_line_start = false;
+        } else {
+            let start = i;
+            let kind = if c.is_ascii_alphabetic() || c == '_' {
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                TokenKind::Ident(chars[start..i].iter().collect())
+            } else if c.is_ascii_digit()
+                || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+            {
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+                TokenKind::Number(chars[start..i].iter().collect())
+            } else if c.is_ascii_punctuation() {
+                i += 1;
+                TokenKind::Punct(c)
+            } else {
+                return Err(Error {
+                    line,
+                    column,
+                    message: format!("unexpected character `{}`", c),
+                });
+            };
+
+            let token_doc = if at_line_start {
+                doc.join("\n")
+            } else {
+                String::new()
+            };
+            doc.clear();
+            at_line_start = false;
+
+            tokens.push(Token {
+                kind,
+                line,
+                column,
+                doc: token_doc,
+            });
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+
+    /// Integer constants, from `#define` or `const` declarations.
+    constants: Vec<(String, usize)>,
+    declarations: Vec<Declaration>,
+}
+
+/// A qualifier inside `layout(...)`, with its value if it has one.
+struct LayoutQualifier {
+    name: String,
+    value: Option<Token>,
+    token: Token,
+}
+
+impl Parser {
+    fn parse(mut self) -> Result<Vec<Declaration>, Error> {
+        while self.position < self.tokens.len() {
+            let start = self.position;
+
+            if self.peek().is_some_and(|token| token.is_ident("struct")) {
+                self.parse_struct()?;
+            } else if self.peek().is_some_and(|token| token.is_ident("const"))
+                && self.parse_constant()?
+            {
+                // Integer constants are recorded for use as array lengths.
+            } else if let Some(layout) = self.block_start()? {
+                self.parse_block(start, layout)?;
+            } else {
+                self.skip_statement()?;
+            }
+        }
+
+        Ok(self.declarations)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, Error> {
+        let token = self.tokens.get(self.position).cloned().ok_or_else(|| {
+            let last = self.tokens.last();
+            Error {
+                line: last.map_or(1, |token| token.line),
+                column: last.map_or(1, |token| token.column),
+                message: "unexpected end of file".to_owned(),
+            }
+        })?;
+
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<Token, Error> {
+        let token = self.next()?;
+        if token.is_punct(c) {
+            Ok(token)
+        } else {
+            Err(error(
+                &token,
+                format!("expected `{}`, found {}", c, token.describe()),
+            ))
+        }
+    }
+
+    fn expect_ident(&mut self, what: &str) -> Result<(String, Token), Error> {
+        let token = self.next()?;
+        match &token.kind {
+            TokenKind::Ident(ident) => Ok((ident.clone(), token.clone())),
+            _ => Err(error(
+                &token,
+                format!("expected {}, found {}", what, token.describe()),
+            )),
+        }
+    }
+
+    /// Evaluates an integer literal or constant.
+    fn integer(&self, token: &Token) -> Result<usize, Error> {
+        let value = match &token.kind {
+            TokenKind::Number(number) => parse_int(number),
+            TokenKind::Ident(name) => self
+                .constants
+                .iter()
+                .find(|(constant, _)| constant == name)
+                .map(|(_, value)| *value),
+            TokenKind::Punct(_) => None,
+        };
+
+        value.ok_or_else(|| {
+            error(
+                token,
+                format!("expected an integer constant, found {}", token.describe()),
+            )
+        })
+    }
+
+    /// Parses `const int NAME = N;` or `const uint NAME = N;`, giving whether
+    /// it was one. Other constants are left to be skipped.
+    fn parse_constant(&mut self) -> Result<bool, Error> {
+        let tokens = &self.tokens[self.position..];
+        let is_integer = tokens.len() >= 6
+            && (tokens[1].is_ident("int") || tokens[1].is_ident("uint"))
+            && tokens[3].is_punct('=')
+            && matches!(tokens[4].kind, TokenKind::Number(_))
+            && tokens[5].is_punct(';');
+
+        if !is_integer {
+            return Ok(false);
+        }
+
+        if let TokenKind::Ident(name) = &tokens[2].kind {
+            let value = self.integer(&tokens[4])?;
+            self.constants.push((name.clone(), value));
+        }
+
+        self.position += 6;
+        Ok(true)
+    }
+
+    /// Skips a statement or function definition that doesn't declare memory.
+    fn skip_statement(&mut self) -> Result<(), Error> {
+        let mut depth = 0usize;
+
+        loop {
+            let token = self.next()?;
+            match token.kind {
+                TokenKind::Punct('(') | TokenKind::Punct('[') => depth += 1,
+                TokenKind::Punct(')') | TokenKind::Punct(']') => depth = depth.saturating_sub(1),
+                TokenKind::Punct(';') if depth == 0 => return Ok(()),
+                TokenKind::Punct('{') if depth == 0 => {
+                    self.skip_braces()?;
+                    // Functions end at their closing brace, but declarations
+                    // end with a semicolon.
+                    if self.peek().is_some_and(|token| token.is_punct(';')) {
+                        self.position += 1;
+                    }
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips tokens up to and including the brace closing one that was just
+    /// read.
+    fn skip_braces(&mut self) -> Result<(), Error> {
+        let mut depth = 1;
+        while depth > 0 {
+            let token = self.next()?;
+            if token.is_punct('{') {
+                depth += 1;
+            } else if token.is_punct('}') {
+                depth -= 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the qualifiers in a `layout(...)`, after the `layout` keyword.
+    /// Row-major matrices aren't supported anywhere, so they're rejected here.
+    fn layout_qualifiers(&mut self) -> Result<Vec<LayoutQualifier>, Error> {
+        self.expect_punct('(')?;
+
+        let mut qualifiers = Vec::new();
+        loop {
+            let (name, token) = self.expect_ident("a layout qualifier")?;
+
+            let value = if self.peek().is_some_and(|token| token.is_punct('=')) {
+                self.position += 1;
+                Some(self.next()?)
+            } else {
+                None
+            };
+
+            if name == "row_major" {
+                return Err(error(&token, "row-major matrices aren't supported"));
+            }
+
+            qualifiers.push(LayoutQualifier { name, value, token });
+
+            let token = self.next()?;
+            if token.is_punct(')') {
+                return Ok(qualifiers);
+            } else if !token.is_punct(',') {
+                return Err(error(
+                    &token,
+                    format!("expected `,` or `)`, found {}", token.describe()),
+                ));
+            }
+        }
+    }
+
+    /// Checks whether a uniform or storage buffer block starts here, giving its
+    /// layout. Leaves the position at the block's name if so, or unchanged if
+    /// not.
+    fn block_start(&mut self) -> Result<Option<BlockLayout>, Error> {
+        let start = self.position;
+        let mut layout = None;
+        let mut push_constant = false;
+
+        while let Some(token) = self.peek().cloned() {
+            if token.is_ident("layout") {
+                self.position += 1;
+                for qualifier in self.layout_qualifiers()? {
+                    match qualifier.name.as_str() {
+                        "std140" => layout = Some(BlockLayout::Std140),
+                        "std430" => layout = Some(BlockLayout::Std430),
+                        "push_constant" => push_constant = true,
+                        "packed" | "shared" | "scalar" => {
+                            return Err(error(
+                                &qualifier.token,
+                                format!("the `{}` layout isn't supported", qualifier.name),
+                            ))
+                        }
+                        _ => {}
+                    }
+                }
+            } else if BLOCK_QUALIFIERS.iter().any(|name| token.is_ident(name)) {
+                self.position += 1;
+            } else if token.is_ident("uniform") || token.is_ident("buffer") {
+                self.position += 1;
+
+                let is_block = self
+                    .tokens
+                    .get(self.position)
+                    .is_some_and(|token| matches!(token.kind, TokenKind::Ident(_)))
+                    && self
+                        .tokens
+                        .get(self.position + 1)
+                        .is_some_and(|token| token.is_punct('{'));
+
+                if !is_block {
+                    break;
+                }
+
+                let default = if token.is_ident("buffer") || push_constant {
+                    BlockLayout::Std430
+                } else {
+                    BlockLayout::Std140
+                };
+                return Ok(Some(layout.unwrap_or(default)));
+            } else {
+                break;
+            }
+        }
+
+        self.position = start;
+        Ok(None)
+    }
+
+    fn parse_struct(&mut self) -> Result<(), Error> {
+        let keyword = self.next()?;
+        let (name, name_token) = self.expect_ident("a struct name")?;
+        self.expect_punct('{')?;
+
+        let (members, _) = self.parse_members(false)?;
+
+        // Declarators can follow the struct, like `struct Light { ... } light;`.
+        while !self.next()?.is_punct(';') {}
+
+        self.declare(Declaration {
+            name,
+            doc: keyword.doc,
+            kind: DeclarationKind::Struct,
+            members,
+            runtime_array: None,
+            line: name_token.line,
+            column: name_token.column,
+        })
+    }
+
+    fn parse_block(&mut self, start: usize, layout: BlockLayout) -> Result<(), Error> {
+        let (name, name_token) = self.expect_ident("a block name")?;
+        self.expect_punct('{')?;
+
+        let is_buffer = self.tokens[start..self.position]
+            .iter()
+            .any(|token| token.is_ident("buffer"));
+        let (members, runtime_array) = self.parse_members(is_buffer)?;
+
+        // An instance name, possibly an array, can follow the block.
+        while !self.next()?.is_punct(';') {}
+
+        self.declare(Declaration {
+            name,
+            doc: self.tokens[start].doc.clone(),
+            kind: DeclarationKind::Block(layout),
+            members,
+            runtime_array,
+            line: name_token.line,
+            column: name_token.column,
+        })
+    }
+
+    fn declare(&mut self, declaration: Declaration) -> Result<(), Error> {
+        if self
+            .declarations
+            .iter()
+            .any(|existing| existing.name == declaration.name)
+        {
+            return Err(Error {
+                line: declaration.line,
+                column: declaration.column,
+                message: format!("`{}` is declared more than once", declaration.name),
+            });
+        }
+
+        self.declarations.push(declaration);
+        Ok(())
+    }
+
+    /// Parses members up to and including the closing brace. A trailing
+    /// runtime-sized array is given separately, if one is allowed.
+    fn parse_members(
+        &mut self,
+        allow_runtime_array: bool,
+    ) -> Result<(Vec<Member>, Option<Member>), Error> {
+        let mut members = Vec::new();
+        let mut runtime_array: Option<Member> = None;
+
+        loop {
+            let first = self.next()?;
+            if first.is_punct('}') {
+                break;
+            }
+
+            if let Some(array) = &runtime_array {
+                return Err(Error {
+                    line: array.line,
+                    column: array.column,
+                    message: "only the last member can be a runtime array".to_owned(),
+                });
+            }
+
+            let doc = first.doc.clone();
+            let mut token = first;
+            let mut offset = None;
+            let mut align = None;
+
+            loop {
+                if token.is_ident("layout") {
+                    for qualifier in self.layout_qualifiers()? {
+                        let slot = match qualifier.name.as_str() {
+                            "offset" => &mut offset,
+                            "align" => &mut align,
+                            _ => continue,
+                        };
+
+                        let value = qualifier.value.as_ref().ok_or_else(|| {
+                            error(
+                                &qualifier.token,
+                                format!("`{}` needs a value", qualifier.name),
+                            )
+                        })?;
+                        *slot = Some(self.integer(value)?);
+                    }
+                } else if !MEMBER_QUALIFIERS.iter().any(|name| token.is_ident(name)) {
+                    break;
+                }
+                token = self.next()?;
+            }
+
+            let ty = match &token.kind {
+                TokenKind::Ident(name) if name == "struct" => {
+                    return Err(error(&token, "nested struct definitions aren't supported"))
+                }
+                TokenKind::Ident(name) => name.clone(),
+                _ => {
+                    return Err(error(
+                        &token,
+                        format!("expected a type, found {}", token.describe()),
+                    ))
+                }
+            };
+
+            // Array lengths can also follow the type, like `float[4] weights;`.
+            let (type_runtime, type_array) = self.array_lengths()?;
+
+            loop {
+                let (name, name_token) = self.expect_ident("a member name")?;
+
+                // `float[2] weights[3]` is three arrays of two floats.
+                let (name_runtime, mut array) = self.array_lengths()?;
+                let runtime = if !name_runtime && array.is_empty() {
+                    type_runtime
+                } else if type_runtime {
+                    return Err(error(
+                        &name_token,
+                        "only the outermost dimension of an array can be runtime-sized",
+                    ));
+                } else {
+                    name_runtime
+                };
+                array.extend_from_slice(&type_array);
+
+                let member = Member {
+                    name,
+                    doc: doc.clone(),
+                    ty: ty.clone(),
+                    array,
+                    offset,
+                    align,
+                    line: token.line,
+                    column: token.column,
+                };
+
+                if !runtime {
+                    members.push(member);
+                } else if allow_runtime_array && runtime_array.is_none() {
+                    runtime_array = Some(member);
+                } else {
+                    return Err(error(
+                        &name_token,
+                        "only the last member of a storage buffer can be a runtime array",
+                    ));
+                }
+
+                let separator = self.next()?;
+                if separator.is_punct(';') {
+                    break;
+                } else if !separator.is_punct(',') {
+                    return Err(error(
+                        &separator,
+                        format!("expected `;` or `,`, found {}", separator.describe()),
+                    ));
+                }
+            }
+        }
+
+        Ok((members, runtime_array))
+    }
+
+    /// Parses any array dimensions in brackets, giving whether the outermost
+    /// one is runtime-sized and the lengths of the rest, outermost first.
+    fn array_lengths(&mut self) -> Result<(bool, Vec<usize>), Error> {
+        let mut runtime = false;
+        let mut lengths = Vec::new();
+
+        while self.peek().is_some_and(|token| token.is_punct('[')) {
+            let open = self.next()?;
+
+            let token = self.next()?;
+            if token.is_punct(']') {
+                if runtime || !lengths.is_empty() {
+                    return Err(error(
+                        &open,
+                        "only the outermost dimension of an array can be runtime-sized",
+                    ));
+                }
+                runtime = true;
+                continue;
+            }
+
+            lengths.push(self.integer(&token)?);
+            self.expect_punct(']')?;
+        }
+
+        Ok((runtime, lengths))
+    }
+}
+
+fn error(token: &Token, message: impl Into<String>) -> Error {
+    Error {
+        line: token.line,
+        column: token.column,
+        message: message.into(),
+    }
+}
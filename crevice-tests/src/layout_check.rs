@@ -0,0 +1,81 @@
+use crevice::glsl::{Glsl, GlslStruct, LayoutDiff};
+use crevice::layout::{AsLayout, Layout, Std140, Std430};
+use crevice::naga::{validate_glsl_block, verify_glsl, NagaError};
+use crevice::reflect::{FieldInfo, LayoutInfo, Reflect};
+
+const PRIMITIVE_SHADER: &str = "#version 450
+
+layout({layout}, set = 0, binding = 0) buffer INPUT {
+    {type_name} value;
+};
+
+void main() {}";
+
+/// Checks that naga lays out the GLSL generated for `T` the same way as
+/// Crevice, without needing a GPU.
+pub fn verify_struct_layout<T>()
+where
+    T: GlslStruct + AsLayout<Std140> + AsLayout<Std430>,
+    <T as AsLayout<Std140>>::Output: Reflect,
+    <T as AsLayout<Std430>>::Output: Reflect,
+{
+    check::<Std140>(T::NAME, verify_glsl::<T, Std140>());
+    check::<Std430>(T::NAME, verify_glsl::<T, Std430>());
+}
+
+/// Like [`verify_struct_layout`], but for a primitive declared as the only
+/// member of a block.
+pub fn verify_primitive_layout<T>()
+where
+    T: Glsl + AsLayout<Std140> + AsLayout<Std430>,
+    <T as AsLayout<Std140>>::Output: Reflect,
+    <T as AsLayout<Std430>>::Output: Reflect,
+{
+    check::<Std140>(T::NAME, verify_primitive::<T, Std140>());
+    check::<Std430>(T::NAME, verify_primitive::<T, Std430>());
+}
+
+fn verify_primitive<T, L>() -> Result<LayoutDiff, NagaError>
+where
+    L: Layout,
+    T: Glsl + AsLayout<L>,
+    <T as AsLayout<L>>::Output: Reflect,
+{
+    let layout = <T as AsLayout<L>>::Output::LAYOUT_INFO;
+    let source = PRIMITIVE_SHADER
+        .replace("{layout}", L::NAME)
+        .replace("{type_name}", T::NAME);
+
+    // The block has a single member, so its layout is built by hand. Leaking
+    // it is fine in tests.
+    let fields = Box::leak(Box::new([FieldInfo {
+        name: "value",
+        offset: 0,
+        size: layout.size,
+        glsl_type: layout.name,
+        layout,
+    }]));
+    let block = LayoutInfo {
+        name: "INPUT",
+        size: layout.size,
+        alignment: layout.alignment,
+        fields,
+        array: None,
+        columns: None,
+    };
+
+    validate_glsl_block(&source, "INPUT", &block)
+}
+
+fn check<L: Layout>(name: &str, result: Result<LayoutDiff, NagaError>) {
+    match result {
+        Ok(diff) if diff.is_match() => {}
+        Ok(diff) => panic!(
+            "naga lays out {} differently in {}:\n{}",
+            name,
+            L::NAME,
+            diff
+        ),
+        Err(err) => panic!("naga couldn't lay out {} in {}: {}", name, L::NAME, err),
+    }
+}
@@ -2,6 +2,8 @@
 
 #[cfg(feature = "wgpu-validation")]
 mod gpu;
+#[cfg(feature = "naga-validation")]
+mod layout_check;
 
 #[cfg(feature = "wgpu-validation")]
 use gpu::{test_round_trip_primitive, test_round_trip_struct};
@@ -12,20 +14,30 @@ fn test_round_trip_struct<T>(_value: T) {}
 #[cfg(not(feature = "wgpu-validation"))]
 fn test_round_trip_primitive<T>(_value: T) {}
 
+#[cfg(feature = "naga-validation")]
+use layout_check::{verify_primitive_layout, verify_struct_layout};
+
+#[cfg(not(feature = "naga-validation"))]
+fn verify_struct_layout<T>() {}
+
+#[cfg(not(feature = "naga-validation"))]
+fn verify_primitive_layout<T>() {}
+
 #[macro_use]
 mod util;
 
+use crevice::hlsl_cbuffer::AsHlslCbuffer;
+use crevice::msl::AsMsl;
+use crevice::opencl::AsOpenCl;
 use crevice::std140::AsStd140;
 use crevice::std430::AsStd430;
 use mint::{ColumnMatrix2, ColumnMatrix3, ColumnMatrix4, Vector2, Vector3, Vector4};
 
-#[cfg(feature = "wgpu-validation")]
 use crevice::glsl::GlslStruct;
 
 #[test]
 fn two_f32() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct TwoF32 {
         x: f32,
         y: f32,
@@ -41,13 +53,13 @@ fn two_f32() {
         y: 4,
     });
 
+    verify_struct_layout::<TwoF32>();
     test_round_trip_struct(TwoF32 { x: 5.0, y: 7.0 });
 }
 
 #[test]
 fn vec2() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct UseVec2 {
         one: Vector2<f32>,
     }
@@ -56,6 +68,7 @@ fn vec2() {
         one: 0,
     });
 
+    verify_struct_layout::<UseVec2>();
     test_round_trip_struct(UseVec2 {
         one: [1.0, 2.0].into(),
     });
@@ -94,6 +107,8 @@ fn mat3_bare() {
         z: 32,
     });
 
+    verify_primitive_layout::<Mat3>();
+
     // Naga produces invalid HLSL for mat3 value.
     // https://github.com/gfx-rs/naga/issues/1466
 
@@ -115,6 +130,7 @@ fn mat4_bare() {
         w: 48,
     });
 
+    verify_primitive_layout::<Mat4>();
     test_round_trip_primitive(Mat4 {
         x: [1.0, 2.0, 3.0, 4.0].into(),
         y: [5.0, 6.0, 7.0, 8.0].into(),
@@ -125,12 +141,14 @@ fn mat4_bare() {
 
 #[test]
 fn mat3() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[allow(dead_code)]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct TestData {
         one: ColumnMatrix3<f32>,
     }
 
+    verify_struct_layout::<TestData>();
+
     // Naga produces invalid HLSL for mat3 value.
     // https://github.com/gfx-rs/naga/issues/1466
 
@@ -141,8 +159,7 @@ fn mat3() {
 
 #[test]
 fn dvec4() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct UsingDVec4 {
         doubles: Vector4<f64>,
     }
@@ -151,6 +168,8 @@ fn dvec4() {
         doubles: 0,
     });
 
+    verify_struct_layout::<UsingDVec4>();
+
     // Naga does not appear to support doubles.
     // https://github.com/gfx-rs/naga/issues/1272
 
@@ -161,8 +180,7 @@ fn dvec4() {
 
 #[test]
 fn four_f64() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct FourF64 {
         x: f64,
         y: f64,
@@ -177,6 +195,8 @@ fn four_f64() {
         w: 24,
     });
 
+    verify_struct_layout::<FourF64>();
+
     // Naga does not appear to support doubles.
     // https://github.com/gfx-rs/naga/issues/1272
 
@@ -190,8 +210,7 @@ fn four_f64() {
 
 #[test]
 fn two_vec3() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct TwoVec3 {
         one: Vector3<f32>,
         two: Vector3<f32>,
@@ -210,6 +229,7 @@ fn two_vec3() {
         two: 16,
     });
 
+    verify_struct_layout::<TwoVec3>();
     test_round_trip_struct(TwoVec3 {
         one: [1.0, 2.0, 3.0].into(),
         two: [4.0, 5.0, 6.0].into(),
@@ -218,8 +238,7 @@ fn two_vec3() {
 
 #[test]
 fn two_vec4() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct TwoVec4 {
         one: Vector4<f32>,
         two: Vector4<f32>,
@@ -230,6 +249,7 @@ fn two_vec4() {
         two: 16,
     });
 
+    verify_struct_layout::<TwoVec4>();
     test_round_trip_struct(TwoVec4 {
         one: [1.0, 2.0, 3.0, 4.0].into(),
         two: [5.0, 6.0, 7.0, 8.0].into(),
@@ -238,8 +258,7 @@ fn two_vec4() {
 
 #[test]
 fn vec3_then_f32() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct Vec3ThenF32 {
         one: Vector3<f32>,
         two: f32,
@@ -250,6 +269,7 @@ fn vec3_then_f32() {
         two: 12,
     });
 
+    verify_struct_layout::<Vec3ThenF32>();
     test_round_trip_struct(Vec3ThenF32 {
         one: [1.0, 2.0, 3.0].into(),
         two: 4.0,
@@ -258,8 +278,7 @@ fn vec3_then_f32() {
 
 #[test]
 fn mat3_padding() {
-    #[derive(Debug, PartialEq, AsStd140, AsStd430)]
-    #[cfg_attr(feature = "wgpu-validation", derive(GlslStruct))]
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
     struct Mat3Padding {
         // Three rows of 16 bytes (3x f32 + 4 bytes padding)
         one: mint::ColumnMatrix3<f32>,
@@ -271,6 +290,8 @@ fn mat3_padding() {
         two: 48,
     });
 
+    verify_struct_layout::<Mat3Padding>();
+
     // Naga produces invalid HLSL for mat3 value.
     // https://github.com/gfx-rs/naga/issues/1466
 
@@ -353,3 +374,675 @@ fn bools_and_bool_vectors() {
         y: 8,
     });
 }
+
+#[test]
+fn arrays_of_scalars_and_vectors() {
+    #[derive(AsStd140, AsStd430, GlslStruct)]
+    struct Arrays {
+        weights: [f32; 4],
+        uv: [Vector2<f32>; 8],
+        count: u32,
+    }
+
+    assert_std140!((size = 208, align = 16) Arrays {
+        weights: 0,
+        uv: 64,
+        count: 192,
+    });
+
+    assert_std430!((size = 88, align = 8) Arrays {
+        weights: 0,
+        uv: 16,
+        count: 80,
+    });
+
+    verify_struct_layout::<Arrays>();
+}
+
+#[test]
+fn arrays_of_arrays() {
+    #[derive(AsStd140, AsStd430, GlslStruct)]
+    struct Grid {
+        cells: [[f32; 3]; 2],
+        last: f32,
+    }
+
+    assert_std140!((size = 112, align = 16) Grid {
+        cells: 0,
+        last: 96,
+    });
+
+    // naga's GLSL frontend reads `float cells[2][3]` as three arrays of two
+    // floats, so this layout can't be checked against it.
+    assert_eq!(
+        Grid::GLSL_DEFINITION,
+        "struct Grid {\n\tfloat cells[2][3];\n\tfloat last;\n};"
+    );
+}
+
+#[test]
+fn arrays_of_vec3() {
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, GlslStruct)]
+    struct Points {
+        points: [Vector3<f32>; 2],
+        scale: f32,
+    }
+
+    // Each vec3 is followed by 4 bytes of padding, and `scale` can't use the
+    // padding after the last one.
+    crevice::assert_std430_layout!((size = 48, align = 16) Points {
+        points: 0,
+        scale: 32,
+    });
+
+    let value = Points {
+        points: [[1.0, 2.0, 3.0].into(), [4.0, 5.0, 6.0].into()],
+        scale: 7.0,
+    };
+
+    let std430 = value.as_std430();
+    let floats: &[f32] = bytemuck::cast_slice(std430.as_bytes());
+    assert_eq!(
+        floats,
+        [1.0, 2.0, 3.0, 0.0, 4.0, 5.0, 6.0, 0.0, 7.0, 0.0, 0.0, 0.0]
+    );
+    assert_eq!(Points::from_std430(std430), value);
+
+    verify_struct_layout::<Points>();
+}
+
+#[test]
+fn hlsl_cbuffer_vec3_then_f32() {
+    #[derive(AsHlslCbuffer)]
+    struct Vec3ThenF32 {
+        one: Vector3<f32>,
+        two: f32,
+    }
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 16, align = 16) Vec3ThenF32 {
+        one: 0,
+        two: 12,
+    });
+}
+
+#[test]
+fn hlsl_cbuffer_no_straddling() {
+    #[derive(AsHlslCbuffer)]
+    struct NoStraddling {
+        one: f32,
+        two: f32,
+        three: f32,
+        // Would straddle the first register, so moves to the second.
+        four: Vector2<f32>,
+        // Fits in the remainder of the second register.
+        five: Vector2<f32>,
+        six: Vector3<f32>,
+    }
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 44, align = 16) NoStraddling {
+        one: 0,
+        two: 4,
+        three: 8,
+        four: 16,
+        five: 24,
+        six: 32,
+    });
+}
+
+#[test]
+fn hlsl_cbuffer_structs_start_new_register() {
+    #[derive(AsHlslCbuffer)]
+    struct Inner {
+        x: f32,
+    }
+
+    #[derive(AsHlslCbuffer)]
+    struct Outer {
+        first: f32,
+        // Structs always start on a new register, but end unpadded.
+        inner: Inner,
+        last: f32,
+    }
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 24, align = 16) Outer {
+        first: 0,
+        inner: 16,
+        last: 20,
+    });
+}
+
+#[test]
+fn hlsl_cbuffer_matrices() {
+    #[derive(AsHlslCbuffer)]
+    struct Matrices {
+        one: f32,
+        // Each column takes a register, but the last one is not padded.
+        two: ColumnMatrix3<f32>,
+        three: f32,
+        four: ColumnMatrix2<f32>,
+    }
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 88, align = 16) Matrices {
+        one: 0,
+        two: 16,
+        three: 60,
+        four: 64,
+    });
+}
+
+#[test]
+fn hlsl_cbuffer_scalar_arrays() {
+    #[derive(Debug, PartialEq, AsHlslCbuffer)]
+    struct Weights {
+        // Every element takes a register, but the last one is not padded.
+        weights: [f32; 4],
+        count: u32,
+    }
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 56, align = 16) Weights {
+        weights: 0,
+        count: 52,
+    });
+
+    let value = Weights {
+        weights: [1.0, 2.0, 3.0, 4.0],
+        count: 4,
+    };
+    let cbuffer = value.as_hlsl_cbuffer();
+    assert_eq!(cbuffer.as_bytes().len(), 56);
+    assert_eq!(Weights::from_hlsl_cbuffer(cbuffer), value);
+}
+
+#[test]
+fn hlsl_cbuffer_vector_arrays() {
+    #[derive(AsHlslCbuffer)]
+    struct Points {
+        points: [Vector3<f32>; 3],
+        scale: f32,
+        offset: Vector2<f32>,
+    }
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 56, align = 16) Points {
+        points: 0,
+        scale: 44,
+        offset: 48,
+    });
+}
+
+#[test]
+fn hlsl_cbuffer_struct_arrays() {
+    #[derive(AsHlslCbuffer)]
+    struct Light {
+        color: Vector3<f32>,
+        intensity: f32,
+        range: f32,
+    }
+
+    #[derive(AsHlslCbuffer)]
+    struct Lights {
+        // Each 20-byte light is padded out to 32 bytes, except the last.
+        lights: [Light; 2],
+        count: u32,
+    }
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 56, align = 16) Lights {
+        lights: 0,
+        count: 52,
+    });
+
+    let mut sizer = crevice::hlsl_cbuffer::Sizer::new();
+    assert_eq!(sizer.add::<[Light; 2]>(), 0);
+    assert_eq!(sizer.add::<u32>(), 52);
+
+    #[cfg(feature = "std")]
+    {
+        let lights = [
+            Light {
+                color: [1.0, 0.0, 0.0].into(),
+                intensity: 1.0,
+                range: 5.0,
+            },
+            Light {
+                color: [0.0, 1.0, 0.0].into(),
+                intensity: 2.0,
+                range: 10.0,
+            },
+        ];
+
+        let mut output = Vec::new();
+        let mut writer = crevice::hlsl_cbuffer::Writer::new(&mut output);
+        assert_eq!(writer.write(&lights).unwrap(), 0);
+        assert_eq!(writer.write(&2u32).unwrap(), 52);
+        assert_eq!(writer.len(), sizer.len());
+
+        let value = Lights { lights, count: 2 }.as_hlsl_cbuffer();
+        assert_eq!(output, value.as_bytes());
+    }
+}
+
+#[test]
+fn hlsl_cbuffer_doubles() {
+    #[derive(AsHlslCbuffer)]
+    struct Doubles {
+        one: f32,
+        two: f64,
+        three: Vector2<f64>,
+        four: Vector3<f64>,
+    }
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 56, align = 16) Doubles {
+        one: 0,
+        two: 8,
+        three: 16,
+        four: 32,
+    });
+}
+
+#[test]
+fn msl_vec3_is_padded() {
+    #[derive(AsMsl)]
+    struct Light {
+        position: Vector3<f32>,
+        intensity: f32,
+    }
+
+    crevice::assert_msl_layout!((size = 32, align = 16) Light {
+        position: 0,
+        intensity: 16,
+    });
+}
+
+#[test]
+fn msl_packed_vec3() {
+    #[derive(AsMsl)]
+    struct PackedLight {
+        position: crevice::msl::PackedVec3,
+        intensity: f32,
+    }
+
+    crevice::assert_msl_layout!((size = 16, align = 4) PackedLight {
+        position: 0,
+        intensity: 12,
+    });
+}
+
+#[test]
+fn msl_vec3_arrays() {
+    #[derive(Debug, PartialEq, AsMsl)]
+    struct Points {
+        points: [Vector3<f32>; 2],
+        packed: [crevice::msl::PackedVec3; 2],
+        scale: f32,
+    }
+
+    // `float3` is padded out to 16 bytes, while `packed_float3` isn't.
+    crevice::assert_msl_layout!((size = 64, align = 16) Points {
+        points: 0,
+        packed: 32,
+        scale: 56,
+    });
+
+    let value = Points {
+        points: [[1.0, 2.0, 3.0].into(), [4.0, 5.0, 6.0].into()],
+        packed: [
+            Vector3::from([7.0, 8.0, 9.0]).into(),
+            Vector3::from([10.0, 11.0, 12.0]).into(),
+        ],
+        scale: 13.0,
+    };
+    assert_eq!(Points::from_msl(value.as_msl()), value);
+}
+
+#[test]
+fn msl_matrices_and_bools() {
+    #[derive(AsMsl)]
+    struct MatricesAndBools {
+        one: bool,
+        two: ColumnMatrix2<f32>,
+        three: Vector3<bool>,
+        four: ColumnMatrix3<f32>,
+        five: bool,
+    }
+
+    crevice::assert_msl_layout!((size = 96, align = 16) MatricesAndBools {
+        one: 0,
+        two: 8,
+        three: 24,
+        four: 32,
+        five: 80,
+    });
+}
+
+#[test]
+fn opencl_vec3_has_vec4_layout() {
+    #[derive(AsOpenCl)]
+    struct Particle {
+        position: Vector3<f32>,
+        mass: f32,
+        velocity: Vector3<f64>,
+    }
+
+    crevice::assert_opencl_layout!((size = 64, align = 32) Particle {
+        position: 0,
+        mass: 16,
+        velocity: 32,
+    });
+}
+
+#[test]
+fn opencl_vec3_arrays() {
+    #[derive(Debug, PartialEq, AsOpenCl)]
+    struct Points {
+        points: [Vector3<f32>; 2],
+        scale: f32,
+    }
+
+    // `float3` has the size and alignment of `float4`.
+    crevice::assert_opencl_layout!((size = 48, align = 16) Points {
+        points: 0,
+        scale: 32,
+    });
+
+    let points = Points {
+        points: [[1.0, 2.0, 3.0].into(), [4.0, 5.0, 6.0].into()],
+        scale: 7.0,
+    };
+    let opencl = points.as_opencl();
+    let floats: &[f32] = bytemuck::cast_slice(bytemuck::bytes_of(&opencl));
+    assert_eq!(
+        floats,
+        [1.0, 2.0, 3.0, 0.0, 4.0, 5.0, 6.0, 0.0, 7.0, 0.0, 0.0, 0.0]
+    );
+    assert_eq!(Points::from_opencl(opencl), points);
+}
+
+#[test]
+fn opencl_nested_structs() {
+    #[derive(AsOpenCl)]
+    struct Inner {
+        x: f32,
+    }
+
+    #[derive(AsOpenCl)]
+    struct Outer {
+        first: Inner,
+        second: Vector2<u32>,
+        third: Inner,
+    }
+
+    crevice::assert_opencl_layout!((size = 24, align = 8) Outer {
+        first: 0,
+        second: 8,
+        third: 16,
+    });
+}
+
+#[test]
+fn reflect_nested_structs() {
+    use crevice::reflect::{LayoutInfo, Reflect};
+
+    #[derive(AsStd140, AsStd430)]
+    struct Inner {
+        x: f32,
+        y: Vector2<f32>,
+    }
+
+    #[derive(AsStd140, AsStd430)]
+    struct Outer {
+        first: f32,
+        inner: Inner,
+        last: ColumnMatrix3<f32>,
+    }
+
+    const STD140: &LayoutInfo = <Outer as AsStd140>::Output::LAYOUT_INFO;
+    const STD430: &LayoutInfo = <Outer as AsStd430>::Output::LAYOUT_INFO;
+
+    assert_eq!(STD140.name, "Outer");
+    assert_eq!(STD140.size, 80);
+    assert_eq!(STD140.alignment, 16);
+
+    let offsets: Vec<_> = STD140.fields.iter().map(|field| field.offset).collect();
+    assert_eq!(offsets, [0, 16, 32]);
+
+    let inner = &STD140.fields[1];
+    assert_eq!(inner.glsl_type, "Inner");
+    assert_eq!(inner.size, 16);
+    assert_eq!(inner.layout.fields[1].name, "y");
+    assert_eq!(inner.layout.fields[1].offset, 8);
+    assert_eq!(inner.layout.fields[1].layout.alignment, 8);
+
+    let last = &STD140.fields[2];
+    assert_eq!(last.glsl_type, "mat3");
+    assert_eq!(last.layout.fields.len(), 0);
+
+    let offsets: Vec<_> = STD430.fields.iter().map(|field| field.offset).collect();
+    assert_eq!(offsets, [0, 8, 32]);
+    assert_eq!(STD430.size, 80);
+}
+
+#[test]
+fn offset_of_path() {
+    use crevice::reflect::offset_of_path;
+    use crevice::{std140_offset_of, std430_offset_of};
+
+    #[derive(AsStd140, AsStd430)]
+    struct Light {
+        position: Vector3<f32>,
+        color: Vector3<f32>,
+    }
+
+    #[derive(AsStd140, AsStd430)]
+    struct Scene {
+        ambient: f32,
+        lights: [Light; 4],
+        transforms: [ColumnMatrix4<f32>; 2],
+    }
+
+    type SceneStd140 = <Scene as AsStd140>::Output;
+
+    let color = offset_of_path::<SceneStd140>("lights[3].color").unwrap();
+    assert_eq!(color.offset, 128);
+    assert_eq!(color.size, 12);
+    assert_eq!(color.layout.name, "vec3");
+
+    let lights = offset_of_path::<SceneStd140>("lights").unwrap();
+    assert_eq!(lights.offset, 16);
+    assert_eq!(lights.size, 128);
+    assert_eq!(lights.layout.array.unwrap().len, 4);
+
+    let transform = offset_of_path::<SceneStd140>(" transforms [ 1 ] ").unwrap();
+    assert_eq!(transform.offset, 208);
+    assert_eq!(transform.size, 64);
+
+    assert!(offset_of_path::<SceneStd140>("lights[4]").is_none());
+    assert!(offset_of_path::<SceneStd140>("lights[0].intensity").is_none());
+    assert!(offset_of_path::<SceneStd140>("lights.color").is_none());
+    assert!(offset_of_path::<SceneStd140>("ambient[0]").is_none());
+    assert!(offset_of_path::<SceneStd140>("lights[]").is_none());
+    assert!(offset_of_path::<SceneStd140>("lights[0]color").is_none());
+    assert!(offset_of_path::<SceneStd140>("").is_none());
+
+    const STD140_COLOR: usize = std140_offset_of!(Scene, lights[3].color).offset;
+    const STD430_COLOR: usize = std430_offset_of!(Scene, lights[3].color).offset;
+    assert_eq!(STD140_COLOR, 128);
+    assert_eq!(STD430_COLOR, 128);
+}
+
+#[test]
+fn layout_assertions() {
+    #[derive(AsStd140, AsStd430, AsHlslCbuffer)]
+    #[crevice(assert_align = 16, max_size = 64)]
+    #[crevice(std140(assert_size = 32), std430(assert_size = 32))]
+    #[crevice(hlsl_cbuffer(assert_size = 32))]
+    struct PointLight {
+        position: Vector3<f32>,
+        color: Vector3<f32>,
+        brightness: f32,
+    }
+
+    crevice::assert_std140_layout!((size = 32, align = 16) PointLight {
+        position: 0,
+        color: 16,
+        brightness: 28,
+    });
+
+    crevice::assert_std430_layout!((size = 32, align = 16) PointLight);
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 32, align = 16) PointLight {
+        color: 16,
+        brightness: 28,
+    });
+}
+
+#[test]
+fn reorder_fields() {
+    #[derive(Debug, PartialEq, AsStd140, AsStd430, AsHlslCbuffer)]
+    #[crevice(reorder, assert_size = 32)]
+    struct Reordered {
+        a: f32,
+        b: Vector3<f32>,
+        c: f32,
+        d: Vector3<f32>,
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        #[derive(AsStd140)]
+        struct Declared {
+            a: f32,
+            b: Vector3<f32>,
+            c: f32,
+            d: Vector3<f32>,
+        }
+
+        let suggestion = crevice::report::LayoutReport::std140::<Declared>().suggest_order();
+        assert_eq!(suggestion.fields, ["b", "a", "d", "c"]);
+        assert_eq!(suggestion.size, 32);
+        assert_eq!(suggestion.bytes_saved, 16);
+    }
+
+    crevice::assert_std140_layout!((size = 32, align = 16) Reordered {
+        b: 0,
+        a: 12,
+        d: 16,
+        c: 28,
+    });
+
+    let value = Reordered {
+        a: 1.0,
+        b: Vector3 {
+            x: 2.0,
+            y: 3.0,
+            z: 4.0,
+        },
+        c: 5.0,
+        d: Vector3 {
+            x: 6.0,
+            y: 7.0,
+            z: 8.0,
+        },
+    };
+
+    let std140 = value.as_std140();
+    let floats: &[f32] = bytemuck::cast_slice(std140.as_bytes());
+    assert_eq!(floats, [2.0, 3.0, 4.0, 1.0, 6.0, 7.0, 8.0, 5.0]);
+    assert_eq!(Reordered::from_std140(std140), value);
+    assert_eq!(Reordered::from_std430(value.as_std430()), value);
+    assert_eq!(Reordered::from_hlsl_cbuffer(value.as_hlsl_cbuffer()), value);
+
+    #[cfg(feature = "alloc")]
+    {
+        let report = crevice::report::LayoutReport::std140::<Reordered>();
+        assert_eq!(report.wasted_bytes(), 0);
+        assert_eq!(report.suggest_order().bytes_saved, 0);
+    }
+}
+
+#[test]
+fn reorder_fields_for_each_layout() {
+    // `PackedVec3` only has an MSL layout, so fields are ordered by MSL rules.
+    #[derive(Debug, PartialEq, AsMsl)]
+    #[crevice(reorder, assert_size = 32)]
+    struct PackedLight {
+        intensity: f32,
+        color: Vector4<f32>,
+        position: crevice::msl::PackedVec3,
+    }
+
+    crevice::assert_msl_layout!((size = 32, align = 16) PackedLight {
+        color: 0,
+        position: 16,
+        intensity: 28,
+    });
+
+    let light = PackedLight {
+        intensity: 1.0,
+        color: Vector4 {
+            x: 2.0,
+            y: 3.0,
+            z: 4.0,
+            w: 5.0,
+        },
+        position: crevice::msl::PackedVec3 {
+            x: 6.0,
+            y: 7.0,
+            z: 8.0,
+        },
+    };
+    assert_eq!(PackedLight::from_msl(light.as_msl()), light);
+
+    // Constant buffer arrays end partway through their last register, so
+    // the two vectors fit in front of the array. No order is smaller in
+    // std140, which keeps declaration order.
+    #[derive(Debug, PartialEq, AsStd140, AsHlslCbuffer)]
+    #[crevice(reorder)]
+    struct Weights {
+        weights: [f32; 3],
+        min: Vector2<f32>,
+        max: Vector2<f32>,
+    }
+
+    crevice::assert_std140_layout!((size = 64, align = 16) Weights {
+        weights: 0,
+        min: 48,
+        max: 56,
+    });
+
+    crevice::assert_hlsl_cbuffer_layout!((size = 52, align = 16) Weights {
+        min: 0,
+        max: 8,
+        weights: 16,
+    });
+
+    // Naming a layout makes every derive use that layout's order.
+    #[derive(Debug, PartialEq, AsStd140, AsHlslCbuffer, GlslStruct)]
+    #[crevice(reorder(hlsl_cbuffer))]
+    struct SharedWeights {
+        weights: [f32; 3],
+        min: Vector2<f32>,
+        max: Vector2<f32>,
+    }
+
+    crevice::assert_std140_layout!((size = 64, align = 16) SharedWeights {
+        min: 0,
+        max: 8,
+        weights: 16,
+    });
+
+    let names: Vec<_> = SharedWeights::FIELDS
+        .iter()
+        .map(|field| field.name)
+        .collect();
+    assert_eq!(names, ["min", "max", "weights"]);
+
+    let weights = SharedWeights {
+        weights: [1.0, 2.0, 3.0],
+        min: Vector2 { x: 4.0, y: 5.0 },
+        max: Vector2 { x: 6.0, y: 7.0 },
+    };
+    assert_eq!(SharedWeights::from_std140(weights.as_std140()), weights);
+    assert_eq!(
+        SharedWeights::from_hlsl_cbuffer(weights.as_hlsl_cbuffer()),
+        weights
+    );
+}
@@ -1,35 +1,40 @@
+#[cfg(feature = "alloc")]
 #[macro_export]
 macro_rules! print_std140 {
     ($type:ty) => {
         println!(
             "{}",
-            <$type as crevice::std140::AsStd140>::Output::debug_metrics()
+            crevice::report::LayoutReport::std140::<$type>().to_table()
         );
         println!();
-        println!();
-        println!(
-            "{}",
-            <$type as crevice::std140::AsStd140>::Output::debug_definitions()
-        );
     };
 }
 
+#[cfg(feature = "alloc")]
 #[macro_export]
 macro_rules! print_std430 {
     ($type:ty) => {
         println!(
             "{}",
-            <$type as crevice::std430::AsStd430>::Output::debug_metrics()
+            crevice::report::LayoutReport::std430::<$type>().to_table()
         );
         println!();
-        println!();
-        println!(
-            "{}",
-            <$type as crevice::std430::AsStd430>::Output::debug_definitions()
-        );
     };
 }
 
+// Reports need `alloc`, so nothing is printed without it.
+#[cfg(not(feature = "alloc"))]
+#[macro_export]
+macro_rules! print_std140 {
+    ($type:ty) => {};
+}
+
+#[cfg(not(feature = "alloc"))]
+#[macro_export]
+macro_rules! print_std430 {
+    ($type:ty) => {};
+}
+
 #[macro_export]
 macro_rules! assert_std140 {
     ((size = $size:literal, align = $align:literal) $struct:ident {
@@ -0,0 +1,141 @@
+//! Compile-time assertions about the layout of types.
+//!
+//! The macros defined here are exported from the crate root.
+
+/// Asserts at compile time that a type's `std140` version has the given size,
+/// alignment and field offsets.
+///
+/// Any mismatch fails the build, which catches layout regressions before they
+/// show up as garbage on the GPU. The field list is optional.
+///
+/// ```rust
+/// use crevice::std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct PointLight {
+///     position: mint::Vector3<f32>,
+///     intensity: f32,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// crevice::assert_std140_layout!((size = 32, align = 16) PointLight {
+///     position: 0,
+///     intensity: 12,
+///     color: 16,
+/// });
+/// ```
+///
+/// ```compile_fail
+/// use crevice::std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct PointLight {
+///     position: mint::Vector3<f32>,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// crevice::assert_std140_layout!((size = 32, align = 16) PointLight {
+///     color: 12,
+/// });
+/// ```
+#[macro_export]
+macro_rules! assert_std140_layout {
+    ($($tt:tt)*) => {
+        $crate::__assert_layout!(std140, AsStd140, Std140, $($tt)*);
+    };
+}
+
+/// Asserts at compile time that a type's `std430` version has the given size,
+/// alignment and field offsets.
+///
+/// See [`assert_std140_layout`][crate::assert_std140_layout] for details.
+#[macro_export]
+macro_rules! assert_std430_layout {
+    ($($tt:tt)*) => {
+        $crate::__assert_layout!(std430, AsStd430, Std430, $($tt)*);
+    };
+}
+
+/// Asserts at compile time that a type's HLSL constant buffer version has the
+/// given size, alignment and field offsets.
+///
+/// See [`assert_std140_layout`][crate::assert_std140_layout] for details.
+#[macro_export]
+macro_rules! assert_hlsl_cbuffer_layout {
+    ($($tt:tt)*) => {
+        $crate::__assert_layout!(hlsl_cbuffer, AsHlslCbuffer, HlslCbuffer, $($tt)*);
+    };
+}
+
+/// Asserts at compile time that a type's MSL version has the given size,
+/// alignment and field offsets.
+///
+/// See [`assert_std140_layout`][crate::assert_std140_layout] for details.
+#[macro_export]
+macro_rules! assert_msl_layout {
+    ($($tt:tt)*) => {
+        $crate::__assert_layout!(msl, AsMsl, Msl, $($tt)*);
+    };
+}
+
+/// Asserts at compile time that a type's OpenCL version has the given size,
+/// alignment and field offsets.
+///
+/// See [`assert_std140_layout`][crate::assert_std140_layout] for details.
+#[macro_export]
+macro_rules! assert_opencl_layout {
+    ($($tt:tt)*) => {
+        $crate::__assert_layout!(opencl, AsOpenCl, OpenCl, $($tt)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_layout {
+    (
+        $mod_name:ident, $as_trait:ident, $trait:ident,
+        (size = $size:expr, align = $align:expr) $ty:ty $({
+            $( $field:ident: $offset:expr ),* $(,)?
+        })?
+    ) => {
+        const _: () = {
+            type Target = <$ty as $crate::$mod_name::$as_trait>::Output;
+
+            if <Target as $crate::reflect::Reflect>::LAYOUT_INFO.size != $size {
+                panic!(concat!(
+                    "invalid size for ",
+                    stringify!($mod_name),
+                    " version of `",
+                    stringify!($ty),
+                    "`, expected ",
+                    stringify!($size),
+                ));
+            }
+
+            if <Target as $crate::$mod_name::$trait>::ALIGNMENT != $align {
+                panic!(concat!(
+                    "invalid alignment for ",
+                    stringify!($mod_name),
+                    " version of `",
+                    stringify!($ty),
+                    "`, expected ",
+                    stringify!($align),
+                ));
+            }
+
+            $($(
+                match <Target as $crate::reflect::Reflect>::LAYOUT_INFO.find(stringify!($field)) {
+                    Some(member) if member.offset == $offset => {}
+                    _ => panic!(concat!(
+                        "invalid offset for field `",
+                        stringify!($field),
+                        "` of `",
+                        stringify!($ty),
+                        "`, expected ",
+                        stringify!($offset),
+                    )),
+                }
+            )*)?
+        };
+    };
+}
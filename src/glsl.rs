@@ -4,6 +4,34 @@ Defines traits and types for generating GLSL code from Rust definitions.
 All GLSL primitives, like `int` or `vec3`, implement the [`Glsl`] trait. Structs
 should implement [`GlslStruct`], which can be derived.
 
+Structs that contain other structs link to their definitions through
+[`Glsl::DEFINITION`]. `GlslStruct::glsl_definition_with_dependencies` emits a
+struct along with every struct it uses, in an order GLSL accepts.
+
+Doc comments on structs and their fields are carried into the generated code
+as `//` comments above each declaration, through [`GlslStructDefinition::doc`]
+and [`GlslField::doc`].
+
+Non-generic structs deriving [`GlslStruct`] also get a `GLSL_DEFINITION`
+constant holding the same code as `GlslStruct::glsl_definition`, built at
+compile time. It's available without the `alloc` feature.
+
+[`GlslBlock`] declares uniform, storage buffer and push constant blocks whose
+members are the fields of a struct.
+
+Code can be generated for a specific GLSL dialect with [`GlslTarget`], like
+WebGL2 or Vulkan. Targeted code gets `highp` precision qualifiers on GLSL ES,
+rejects types the target lacks, like `dvec3` on WebGL2, and lists the
+`#extension` directives needed for types like `uint64_t`.
+
+Values can be written as GLSL expressions with [`GlslValue`], which is useful
+for generating constants and lookup tables.
+
+The same field metadata is used to generate Metal Shading Language structs with
+`GlslStruct::msl_definition`. Each type's MSL name comes from
+[`Glsl::MSL_NAME`], so fields using packed types like
+[`PackedVec3`][crate::msl::PackedVec3] become `packed_float3`.
+
 ## Examples
 Given this struct:
 */
@@ -34,52 +62,478 @@ struct SpotLight {
     float intensity;
 };
 ```
+
+## Reserved Names
+Struct and field names are checked when deriving [`GlslStruct`]. Names that GLSL
+reserves, like `input` or `sample`, names starting with `gl_` and names
+containing two consecutive underscores fail to compile:
+
+```compile_fail
+use crevice::glsl::GlslStruct;
+
+#[derive(GlslStruct)]
+struct Pass {
+    input: u32,
+}
+```
+
+Adding `#[crevice(rename(suffix = "_"))]` or `#[crevice(rename(prefix = "..."))]`
+renames them instead, so `input` becomes `input_`. Runs of underscores in
+renamed names are collapsed into one.
 */
 
+#[cfg(feature = "alloc")]
+mod block;
+#[cfg(feature = "alloc")]
+mod msl;
+#[cfg(feature = "alloc")]
+mod target;
+#[cfg(feature = "alloc")]
+mod validate;
+mod value;
+
+#[cfg(feature = "alloc")]
+pub use self::block::*;
+#[cfg(feature = "alloc")]
+pub use self::msl::*;
+#[cfg(feature = "alloc")]
+pub(crate) use self::target::extension_directives;
+#[cfg(feature = "alloc")]
+pub use self::target::GlslTarget;
+#[cfg(feature = "alloc")]
+pub use self::validate::*;
+pub use self::value::*;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
 pub use crevice_derive::GlslStruct;
 
 /// Trait for types that have a GLSL equivalent. Useful for generating GLSL code
 /// from Rust structs.
+///
+/// # Safety
+/// `NAME` must name a GLSL type with the same memory representation as the
+/// implementing type, or as its elements if `ARRAY` is present. `MSL_NAME`, if
+/// present, must do the same for MSL.
 pub unsafe trait Glsl {
     /// The name of this type in GLSL, like `vec2` or `mat4`.
     const NAME: &'static str;
+
+    /// The name of this type in MSL, like `float2` or `packed_float3`, which
+    /// is `None` for types that have no MSL equivalent, like `double`.
+    ///
+    /// Defaults to the MSL equivalent of a GLSL primitive named `NAME`.
+    const MSL_NAME: Option<&'static str> = msl_name(Self::NAME);
+
+    /// The definition of this type if it is a struct, which is `None` for
+    /// GLSL primitives.
+    const DEFINITION: Option<&'static GlslStructDefinition> = None;
+
+    /// The dimensions of this type if it is an array, which is `None`
+    /// otherwise. Arrays are named after their element type, so `[f32; 4]` has
+    /// the `NAME` `float` and is declared as `float name[4]`.
+    const ARRAY: Option<&'static GlslArray> = None;
+}
+
+/// The dimensions of a GLSL array, like the `[4]` in `float weights[4]`.
+#[derive(Debug, Clone, Copy)]
+pub struct GlslArray {
+    /// The number of elements in the array.
+    pub len: usize,
+
+    /// The dimensions of the array's elements if they are arrays themselves.
+    pub element: Option<&'static GlslArray>,
+}
+
+impl GlslArray {
+    /// Writes the array's dimensions as they follow a declaration's name, like
+    /// `[4][2]`.
+    pub fn write_suffix(array: Option<&GlslArray>, output: &mut dyn fmt::Write) -> fmt::Result {
+        let mut array = array;
+        while let Some(dimension) = array {
+            write!(output, "[{}]", dimension.len)?;
+            array = dimension.element;
+        }
+        Ok(())
+    }
 }
 
 /// A field contained within a GLSL struct definition.
+#[derive(Debug, Clone, Copy)]
 pub struct GlslField {
     /// The type of the field, like `vec2` or `mat3`.
     pub ty: &'static str,
 
     /// The field's name. This must be a valid GLSL identifier.
     pub name: &'static str,
+
+    /// The field's documentation, which is written as a `//` comment above
+    /// its declaration. Empty if the field has none.
+    pub doc: &'static str,
+
+    /// The type of the field in MSL, like `float2` or `packed_float3`, if it
+    /// has an MSL equivalent.
+    pub msl_ty: Option<&'static str>,
+
+    /// The definition of the field's type if it is a struct.
+    pub definition: Option<&'static GlslStructDefinition>,
+
+    /// The dimensions of the field if it is an array.
+    pub array: Option<&'static GlslArray>,
+}
+
+#[cfg(feature = "alloc")]
+impl GlslField {
+    /// The field's name followed by its array dimensions, if any, like
+    /// `weights[4]`.
+    pub(crate) fn declarator(&self) -> String {
+        let mut output = String::from(self.name);
+        GlslArray::write_suffix(self.array, &mut output).unwrap();
+        output
+    }
+}
+
+/// The definition of a GLSL struct, which links to the definitions of any
+/// structs used by its fields.
+#[derive(Debug)]
+pub struct GlslStructDefinition {
+    /// The name of the struct.
+    pub name: &'static str,
+
+    /// The struct's documentation, which is written as a `//` comment above
+    /// its definition. Empty if the struct has none.
+    pub doc: &'static str,
+
+    /// The fields contained in the struct.
+    pub fields: &'static [GlslField],
+}
+
+#[cfg(feature = "alloc")]
+impl GlslStructDefinition {
+    /// Generates GLSL code that defines this struct, without any of the structs
+    /// it depends on.
+    pub fn glsl_definition(&self) -> String {
+        self.definition(None)
+    }
+
+    /// Generates GLSL code that defines this struct for `target`, without any
+    /// of the structs it depends on or the `#extension` directives it needs.
+    ///
+    /// # Panics
+    /// Panics if a field's type isn't available on `target`.
+    pub fn glsl_definition_for(&self, target: GlslTarget) -> String {
+        self.definition(Some(target))
+    }
+
+    fn definition(&self, target: Option<GlslTarget>) -> String {
+        let mut output = String::new();
+        write_doc_comment(&mut output, self.doc, "");
+        output.push_str("struct ");
+        output.push_str(self.name);
+        output.push_str(" {\n");
+
+        for field in self.fields {
+            write_doc_comment(&mut output, field.doc, "\t");
+            output.push('\t');
+            match target {
+                Some(target) => {
+                    output.push_str(&target.declaration(self.name, field.ty, &field.declarator()))
+                }
+                None => {
+                    output.push_str(field.ty);
+                    output.push(' ');
+                    output.push_str(&field.declarator());
+                }
+            }
+            output.push_str(";\n");
+        }
+
+        output.push_str("};");
+        output
+    }
+
+    /// Gives every struct this struct depends on, transitively, followed by
+    /// this struct. Each struct comes after all of the structs it depends on
+    /// and appears only once.
+    pub fn with_dependencies(&'static self) -> Vec<&'static GlslStructDefinition> {
+        let mut output = Vec::new();
+        visit_definition(self, &mut output);
+        output
+    }
+}
+
+/// Generates GLSL code defining all of the given structs and every struct they
+/// depend on.
+///
+/// Definitions are de-duplicated by name and ordered so that each struct is
+/// defined after the structs it uses.
+#[cfg(feature = "alloc")]
+pub fn glsl_definitions(structs: &[&'static GlslStructDefinition]) -> String {
+    let mut definitions = Vec::new();
+    for definition in structs {
+        visit_definition(definition, &mut definitions);
+    }
+
+    definitions
+        .iter()
+        .map(|definition| definition.glsl_definition())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Generates GLSL code for `target` defining all of the given structs and every
+/// struct they depend on, preceded by any `#extension` directives they need.
+///
+/// # Panics
+/// Panics if a field's type isn't available on `target`.
+#[cfg(feature = "alloc")]
+pub fn glsl_definitions_for(
+    structs: &[&'static GlslStructDefinition],
+    target: GlslTarget,
+) -> String {
+    let mut extensions = Vec::new();
+    let definitions = target_definitions(structs, target, &mut extensions);
+
+    let mut output = extension_directives(&extensions);
+    output.push_str(&definitions);
+    output
+}
+
+/// Like [`glsl_definitions_for`], but adds the extensions the structs need to
+/// `extensions` instead of writing them.
+#[cfg(feature = "alloc")]
+pub(crate) fn target_definitions(
+    structs: &[&'static GlslStructDefinition],
+    target: GlslTarget,
+    extensions: &mut Vec<&'static str>,
+) -> String {
+    let mut definitions = Vec::new();
+    for definition in structs {
+        visit_definition(definition, &mut definitions);
+    }
+
+    for definition in &definitions {
+        target.add_extensions(definition.fields.iter().map(|field| field.ty), extensions);
+    }
+
+    definitions
+        .iter()
+        .map(|definition| definition.glsl_definition_for(target))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Writes `doc` as `//` comments, one per line, each preceded by `indent`.
+#[cfg(feature = "alloc")]
+pub(crate) fn write_doc_comment(output: &mut String, doc: &str, indent: &str) {
+    if doc.is_empty() {
+        return;
+    }
+
+    for line in doc.split('\n') {
+        output.push_str(indent);
+        output.push_str("//");
+        if !line.is_empty() {
+            output.push(' ');
+            output.push_str(line);
+        }
+        output.push('\n');
+    }
+}
+
+/// Adds `definition` to `output` after all of its dependencies, skipping any
+/// structs that are already present.
+#[cfg(feature = "alloc")]
+fn visit_definition(
+    definition: &'static GlslStructDefinition,
+    output: &mut Vec<&'static GlslStructDefinition>,
+) {
+    // Structs can't contain themselves, so no struct can be visited while
+    // it's being visited.
+    if output
+        .iter()
+        .any(|existing| existing.name == definition.name)
+    {
+        return;
+    }
+
+    for field in definition.fields {
+        if let Some(field_definition) = field.definition {
+            visit_definition(field_definition, output);
+        }
+    }
+
+    output.push(definition);
 }
 
 /// Trait for types that can be represented as a struct in GLSL.
 ///
 /// This trait should not generally be implemented by hand, but can be derived.
-#[cfg(feature = "std")]
+///
+/// # Safety
+/// `FIELDS` must list every field of the struct, in the order they are laid out
+/// in memory. That's declaration order, unless `#[crevice(reorder)]` orders
+/// them for `std140` or for the layout it names.
 pub unsafe trait GlslStruct: Glsl {
     /// The fields contained in this struct.
     const FIELDS: &'static [GlslField];
 
+    /// The struct's documentation, which is written as a `//` comment above
+    /// its definition. Empty if the struct has none.
+    const DOC: &'static str = "";
+
     /// Generates GLSL code that represents this struct and its fields.
+    #[cfg(feature = "alloc")]
     fn glsl_definition() -> String {
-        let mut output = String::new();
-        output.push_str("struct ");
-        output.push_str(Self::NAME);
-        output.push_str(" {\n");
+        GlslStructDefinition {
+            name: Self::NAME,
+            doc: Self::DOC,
+            fields: Self::FIELDS,
+        }
+        .glsl_definition()
+    }
 
-        for field in Self::FIELDS {
-            output.push('\t');
-            output.push_str(field.ty);
-            output.push(' ');
-            output.push_str(field.name);
-            output.push_str(";\n");
+    /// Generates GLSL code that defines this struct and every struct it
+    /// depends on, in an order GLSL accepts.
+    #[cfg(feature = "alloc")]
+    fn glsl_definition_with_dependencies() -> String {
+        match Self::DEFINITION {
+            Some(definition) => glsl_definitions(&[definition]),
+            None => Self::glsl_definition(),
         }
+    }
 
-        output.push_str("};");
-        output
+    /// Generates GLSL code for `target` that represents this struct and its
+    /// fields. See [`GlslTarget`].
+    ///
+    /// # Panics
+    /// Panics if a field's type isn't available on `target`.
+    #[cfg(feature = "alloc")]
+    fn glsl_definition_for(target: GlslTarget) -> String {
+        GlslStructDefinition {
+            name: Self::NAME,
+            doc: Self::DOC,
+            fields: Self::FIELDS,
+        }
+        .glsl_definition_for(target)
     }
+
+    /// Generates GLSL code for `target` that defines this struct and every
+    /// struct it depends on, preceded by any `#extension` directives they
+    /// need.
+    ///
+    /// # Panics
+    /// Panics if a field's type isn't available on `target`.
+    #[cfg(feature = "alloc")]
+    fn glsl_definition_with_dependencies_for(target: GlslTarget) -> String {
+        match Self::DEFINITION {
+            Some(definition) => glsl_definitions_for(&[definition], target),
+            None => {
+                let mut extensions = Vec::new();
+                target.add_extensions(Self::FIELDS.iter().map(|field| field.ty), &mut extensions);
+
+                let mut output = extension_directives(&extensions);
+                output.push_str(&Self::glsl_definition_for(target));
+                output
+            }
+        }
+    }
+
+    /// Generates MSL code that represents this struct and its fields.
+    ///
+    /// # Panics
+    /// Panics if a field has no MSL equivalent.
+    #[cfg(feature = "alloc")]
+    fn msl_definition() -> String {
+        GlslStructDefinition {
+            name: Self::NAME,
+            doc: Self::DOC,
+            fields: Self::FIELDS,
+        }
+        .msl_definition(None)
+    }
+
+    /// Generates MSL code that represents this struct as an argument buffer,
+    /// with each field annotated with `[[id(n)]]` counting up from 0.
+    ///
+    /// # Panics
+    /// Panics if a field has no MSL equivalent.
+    #[cfg(feature = "alloc")]
+    fn msl_argument_buffer_definition() -> String {
+        GlslStructDefinition {
+            name: Self::NAME,
+            doc: Self::DOC,
+            fields: Self::FIELDS,
+        }
+        .msl_definition(Some(0))
+    }
+
+    /// Generates MSL code that defines this struct and every struct it depends
+    /// on, in an order MSL accepts.
+    ///
+    /// # Panics
+    /// Panics if a field has no MSL equivalent.
+    #[cfg(feature = "alloc")]
+    fn msl_definition_with_dependencies() -> String {
+        match Self::DEFINITION {
+            Some(definition) => msl_definitions(&[definition]),
+            None => Self::msl_definition(),
+        }
+    }
+}
+
+/// Gives the MSL name of the GLSL primitive with the given name.
+const fn msl_name(glsl_name: &str) -> Option<&'static str> {
+    const NAMES: &[(&str, &str)] = &[
+        ("float", "float"),
+        ("int", "int"),
+        ("uint", "uint"),
+        ("bool", "bool"),
+        ("vec2", "float2"),
+        ("vec3", "float3"),
+        ("vec4", "float4"),
+        ("ivec2", "int2"),
+        ("ivec3", "int3"),
+        ("ivec4", "int4"),
+        ("uvec2", "uint2"),
+        ("uvec3", "uint3"),
+        ("uvec4", "uint4"),
+        ("bvec2", "bool2"),
+        ("bvec3", "bool3"),
+        ("bvec4", "bool4"),
+        ("mat2", "float2x2"),
+        ("mat3", "float3x3"),
+        ("mat4", "float4x4"),
+    ];
+
+    let mut i = 0;
+    while i < NAMES.len() {
+        if str_eq(NAMES[i].0, glsl_name) {
+            return Some(NAMES[i].1);
+        }
+        i += 1;
+    }
+
+    None
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
 }
 
 unsafe impl Glsl for f32 {
@@ -97,3 +551,25 @@ unsafe impl Glsl for i32 {
 unsafe impl Glsl for u32 {
     const NAME: &'static str = "uint";
 }
+
+unsafe impl Glsl for bool {
+    const NAME: &'static str = "bool";
+}
+
+unsafe impl Glsl for i64 {
+    const NAME: &'static str = "int64_t";
+}
+
+unsafe impl Glsl for u64 {
+    const NAME: &'static str = "uint64_t";
+}
+
+unsafe impl<T: Glsl, const N: usize> Glsl for [T; N] {
+    const NAME: &'static str = T::NAME;
+    const MSL_NAME: Option<&'static str> = T::MSL_NAME;
+    const DEFINITION: Option<&'static GlslStructDefinition> = T::DEFINITION;
+    const ARRAY: Option<&'static GlslArray> = Some(&GlslArray {
+        len: N,
+        element: T::ARRAY,
+    });
+}
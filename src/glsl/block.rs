@@ -0,0 +1,526 @@
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::glsl::target::extension_directives;
+use crate::glsl::{
+    glsl_definitions, target_definitions, write_doc_comment, Glsl, GlslField, GlslStruct,
+    GlslStructDefinition, GlslTarget,
+};
+use crate::layout::{AsLayout, Layout};
+use crate::reflect::Reflect;
+
+/// The kind of interface block to declare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    /// A uniform block, like `uniform Camera { ... };`.
+    Uniform,
+
+    /// A shader storage buffer block, like `buffer Lights { ... };`.
+    Buffer,
+
+    /// A Vulkan push constant block, like
+    /// `layout(push_constant) uniform Constants { ... };`.
+    PushConstant,
+}
+
+/// The memory layout of an interface block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockLayout {
+    /// `std140` layout, matching [`AsStd140`][crate::std140::AsStd140].
+    Std140,
+
+    /// `std430` layout, matching [`AsStd430`][crate::std430::AsStd430].
+    Std430,
+}
+
+impl BlockLayout {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Std140 => "std140",
+            Self::Std430 => "std430",
+        }
+    }
+}
+
+/// A memory qualifier for a storage buffer block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryQualifier {
+    /// `readonly`: the shader may only read from the buffer.
+    ReadOnly,
+
+    /// `writeonly`: the shader may only write to the buffer.
+    WriteOnly,
+
+    /// `coherent`: writes are visible to other shader invocations.
+    Coherent,
+}
+
+impl MemoryQualifier {
+    fn name(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "readonly",
+            Self::WriteOnly => "writeonly",
+            Self::Coherent => "coherent",
+        }
+    }
+}
+
+/// Generates the declaration of a GLSL interface block from a struct, like
+/// `layout(std140, set = 0, binding = 1) uniform Camera { ... } camera;`.
+///
+/// The block's members are the struct's fields. Uniform blocks default to
+/// `std140` layout, while storage buffers and push constants default to
+/// `std430`.
+///
+/// ```rust
+/// use crevice::glsl::{GlslBlock, GlslStruct};
+/// use crevice::std430::AsStd430;
+///
+/// #[derive(GlslStruct)]
+/// struct PointLight {
+///     position: mint::Vector3<f32>,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// #[derive(AsStd430, GlslStruct)]
+/// struct Lights {
+///     count: u32,
+/// }
+///
+/// let block = GlslBlock::buffer::<Lights>()
+///     .set(0)
+///     .binding(2)
+///     .readonly()
+///     .runtime_array::<PointLight>("lights")
+///     .instance_name("lights");
+///
+/// assert_eq!(
+///     block.glsl_definition(),
+///     "layout(std430, set = 0, binding = 2) readonly buffer Lights {\n\
+///     \tuint count;\n\
+///     \tPointLight lights[];\n\
+///     } lights;"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct GlslBlock {
+    kind: BlockKind,
+    layout: BlockLayout,
+    set: Option<u32>,
+    binding: Option<u32>,
+    qualifiers: Vec<MemoryQualifier>,
+    block_name: String,
+    instance_name: Option<String>,
+    doc: &'static str,
+    fields: &'static [GlslField],
+    offsets: Option<Vec<MemberOffset>>,
+    runtime_array: Option<RuntimeArray>,
+    target: Option<GlslTarget>,
+}
+
+/// The explicit placement of a block member.
+#[derive(Debug, Clone, Copy)]
+struct MemberOffset {
+    offset: usize,
+    align: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct RuntimeArray {
+    ty: &'static str,
+    name: String,
+    definition: Option<&'static GlslStructDefinition>,
+}
+
+impl GlslBlock {
+    /// Creates a block of the given kind whose members are the fields of `T`.
+    pub fn new<T: GlslStruct>(kind: BlockKind) -> Self {
+        let layout = match kind {
+            BlockKind::Uniform => BlockLayout::Std140,
+            BlockKind::Buffer | BlockKind::PushConstant => BlockLayout::Std430,
+        };
+
+        Self {
+            kind,
+            layout,
+            set: None,
+            binding: None,
+            qualifiers: Vec::new(),
+            block_name: T::NAME.to_owned(),
+            instance_name: None,
+            doc: T::DOC,
+            fields: T::FIELDS,
+            offsets: None,
+            runtime_array: None,
+            target: None,
+        }
+    }
+
+    /// Creates a uniform block whose members are the fields of `T`.
+    pub fn uniform<T: GlslStruct>() -> Self {
+        Self::new::<T>(BlockKind::Uniform)
+    }
+
+    /// Creates a storage buffer block whose members are the fields of `T`.
+    pub fn buffer<T: GlslStruct>() -> Self {
+        Self::new::<T>(BlockKind::Buffer)
+    }
+
+    /// Creates a push constant block whose members are the fields of `T`.
+    pub fn push_constant<T: GlslStruct>() -> Self {
+        Self::new::<T>(BlockKind::PushConstant)
+    }
+
+    /// Sets the memory layout of the block.
+    pub fn layout(mut self, layout: BlockLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets the descriptor set of the block.
+    ///
+    /// # Panics
+    /// Panics if this is a push constant block, which can't be in a set.
+    pub fn set(mut self, set: u32) -> Self {
+        assert!(
+            self.kind != BlockKind::PushConstant,
+            "push constant blocks can't have a descriptor set"
+        );
+
+        self.set = Some(set);
+        self
+    }
+
+    /// Sets the binding of the block.
+    ///
+    /// # Panics
+    /// Panics if this is a push constant block, which can't have a binding.
+    pub fn binding(mut self, binding: u32) -> Self {
+        assert!(
+            self.kind != BlockKind::PushConstant,
+            "push constant blocks can't have a binding"
+        );
+
+        self.binding = Some(binding);
+        self
+    }
+
+    /// Overrides the name of the block, which defaults to the name of the
+    /// struct.
+    pub fn block_name(mut self, name: &str) -> Self {
+        self.block_name = name.to_owned();
+        self
+    }
+
+    /// Sets the instance name of the block, which is used to refer to its
+    /// members in GLSL. Without an instance name, members are in global scope.
+    pub fn instance_name(mut self, name: &str) -> Self {
+        self.instance_name = Some(name.to_owned());
+        self
+    }
+
+    /// Adds a memory qualifier to the block.
+    ///
+    /// # Panics
+    /// Panics if this isn't a storage buffer block.
+    pub fn qualifier(mut self, qualifier: MemoryQualifier) -> Self {
+        assert!(
+            self.kind == BlockKind::Buffer,
+            "only storage buffer blocks can have memory qualifiers"
+        );
+
+        if !self.qualifiers.contains(&qualifier) {
+            self.qualifiers.push(qualifier);
+        }
+
+        self
+    }
+
+    /// Marks the block `readonly`. See [`GlslBlock::qualifier`].
+    pub fn readonly(self) -> Self {
+        self.qualifier(MemoryQualifier::ReadOnly)
+    }
+
+    /// Marks the block `writeonly`. See [`GlslBlock::qualifier`].
+    pub fn writeonly(self) -> Self {
+        self.qualifier(MemoryQualifier::WriteOnly)
+    }
+
+    /// Marks the block `coherent`. See [`GlslBlock::qualifier`].
+    pub fn coherent(self) -> Self {
+        self.qualifier(MemoryQualifier::Coherent)
+    }
+
+    /// Adds a runtime-sized array of `T` with the given name as the last
+    /// member of the block.
+    ///
+    /// # Panics
+    /// Panics if this isn't a storage buffer block.
+    pub fn runtime_array<T: Glsl>(mut self, name: &str) -> Self {
+        assert!(
+            self.kind == BlockKind::Buffer,
+            "only storage buffer blocks can end with a runtime array"
+        );
+
+        self.runtime_array = Some(RuntimeArray {
+            ty: T::NAME,
+            name: name.to_owned(),
+            definition: T::DEFINITION,
+        });
+        self
+    }
+
+    /// Pins every member of the block to the offset it has when `T` is laid
+    /// out according to `L`, using `layout(offset = N)`. Members whose
+    /// alignment is larger than their size, like `vec3`, also get
+    /// `layout(align = N)`. The block's layout is set to `L`.
+    ///
+    /// `T` should be the struct the block was created from. These qualifiers
+    /// need GLSL 4.40 or `GL_ARB_enhanced_layouts`.
+    ///
+    /// ```rust
+    /// use crevice::glsl::{GlslBlock, GlslStruct};
+    /// use crevice::layout::Std140;
+    /// use crevice::std140::AsStd140;
+    ///
+    /// #[derive(AsStd140, GlslStruct)]
+    /// struct Fog {
+    ///     color: mint::Vector3<f32>,
+    ///     density: f32,
+    ///     range: mint::Vector2<f32>,
+    /// }
+    ///
+    /// let block = GlslBlock::uniform::<Fog>().explicit_offsets::<Fog, Std140>();
+    ///
+    /// assert_eq!(
+    ///     block.glsl_definition(),
+    ///     "layout(std140) uniform Fog {\n\
+    ///     \tlayout(offset = 0, align = 16) vec3 color;\n\
+    ///     \tlayout(offset = 12) float density;\n\
+    ///     \tlayout(offset = 16) vec2 range;\n\
+    ///     };"
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `L` isn't a layout GLSL blocks can use, or if `T`'s layout
+    /// has a different number of fields than the block has members.
+    pub fn explicit_offsets<T, L>(mut self) -> Self
+    where
+        L: Layout,
+        T: AsLayout<L>,
+        <T as AsLayout<L>>::Output: Reflect,
+    {
+        self.layout = match L::NAME {
+            "std140" => BlockLayout::Std140,
+            "std430" => BlockLayout::Std430,
+            name => panic!("GLSL blocks can't use the {} layout", name),
+        };
+
+        let layout = <T as AsLayout<L>>::Output::LAYOUT_INFO;
+        assert_eq!(
+            self.fields.len(),
+            layout.fields.len(),
+            "layout of `{}` doesn't match the members of block `{}`",
+            layout.name,
+            self.block_name
+        );
+
+        // GLSL fields are listed in memory order, but may have been renamed to
+        // avoid reserved words, so they're matched to the layout by position.
+        let mut infos: Vec<_> = layout.fields.iter().collect();
+        infos.sort_by_key(|info| info.offset);
+
+        let offsets = infos
+            .into_iter()
+            .map(|info| {
+                let alignment = info.layout.alignment;
+                MemberOffset {
+                    offset: info.offset,
+                    align: (alignment > info.size).then_some(alignment),
+                }
+            })
+            .collect();
+
+        self.offsets = Some(offsets);
+        self
+    }
+
+    /// Generates the block for the given GLSL dialect. Members get precision
+    /// qualifiers on GLSL ES, and `set` and `binding` are left out on targets
+    /// that don't accept them, in which case the application must assign the
+    /// binding itself.
+    ///
+    /// Without a target, the block is written exactly as configured.
+    pub fn target(mut self, target: GlslTarget) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Generates GLSL code declaring this block.
+    ///
+    /// # Panics
+    /// Panics if the block uses a feature or member type that isn't available
+    /// on its target.
+    pub fn glsl_definition(&self) -> String {
+        if let Some(target) = self.target {
+            match self.kind {
+                BlockKind::Uniform => {}
+                BlockKind::Buffer => assert!(
+                    target.supports_storage_buffers(),
+                    "{} doesn't support storage buffer blocks",
+                    target
+                ),
+                BlockKind::PushConstant => assert!(
+                    target.supports_push_constants(),
+                    "{} doesn't support push constant blocks",
+                    target
+                ),
+            }
+
+            if self.offsets.is_some() {
+                target.check_offsets(&self.block_name);
+            }
+        }
+
+        let mut layout = Vec::new();
+        if self.kind == BlockKind::PushConstant {
+            layout.push("push_constant".to_owned());
+        }
+        layout.push(self.layout.name().to_owned());
+        if let Some(set) = self
+            .set
+            .filter(|_| self.target.is_none_or(GlslTarget::supports_set))
+        {
+            layout.push(format!("set = {}", set));
+        }
+        if let Some(binding) = self
+            .binding
+            .filter(|_| self.target.is_none_or(GlslTarget::supports_binding))
+        {
+            layout.push(format!("binding = {}", binding));
+        }
+
+        let mut output = String::new();
+        write_doc_comment(&mut output, self.doc, "");
+        write!(output, "layout({}) ", layout.join(", ")).unwrap();
+
+        for qualifier in &self.qualifiers {
+            output.push_str(qualifier.name());
+            output.push(' ');
+        }
+
+        let storage = match self.kind {
+            BlockKind::Uniform | BlockKind::PushConstant => "uniform",
+            BlockKind::Buffer => "buffer",
+        };
+        writeln!(output, "{} {} {{", storage, self.block_name).unwrap();
+
+        for (index, field) in self.fields.iter().enumerate() {
+            write_doc_comment(&mut output, field.doc, "\t");
+            output.push('\t');
+
+            if let Some(member) = self.offsets.as_ref().map(|offsets| offsets[index]) {
+                write!(output, "layout(offset = {}", member.offset).unwrap();
+                if let Some(align) = member.align {
+                    write!(output, ", align = {}", align).unwrap();
+                }
+                output.push_str(") ");
+            }
+
+            writeln!(
+                output,
+                "{};",
+                self.declaration(field.ty, &field.declarator())
+            )
+            .unwrap();
+        }
+
+        if let Some(array) = &self.runtime_array {
+            writeln!(output, "\t{}[];", self.declaration(array.ty, &array.name)).unwrap();
+        }
+
+        output.push('}');
+        if let Some(instance_name) = &self.instance_name {
+            output.push(' ');
+            output.push_str(instance_name);
+        }
+        output.push(';');
+
+        output
+    }
+
+    /// Generates GLSL code defining every struct used by this block's members,
+    /// followed by the block itself.
+    pub fn glsl_definition_with_dependencies(&self) -> String {
+        let structs = self.structs();
+
+        let mut extensions = Vec::new();
+        let definitions = match self.target {
+            Some(target) => {
+                self.add_extensions(&mut extensions);
+                target_definitions(&structs, target, &mut extensions)
+            }
+            None => glsl_definitions(&structs),
+        };
+
+        let mut output = extension_directives(&extensions);
+        if !definitions.is_empty() {
+            output.push_str(&definitions);
+            output.push_str("\n\n");
+        }
+
+        output.push_str(&self.glsl_definition());
+        output
+    }
+
+    /// The definitions of the structs used directly by this block's members.
+    pub(crate) fn structs(&self) -> Vec<&'static GlslStructDefinition> {
+        let mut structs: Vec<_> = self
+            .fields
+            .iter()
+            .filter_map(|field| field.definition)
+            .collect();
+
+        if let Some(definition) = self
+            .runtime_array
+            .as_ref()
+            .and_then(|array| array.definition)
+        {
+            structs.push(definition);
+        }
+
+        structs
+    }
+
+    /// Adds the extensions this block's own declaration needs on its target to
+    /// `extensions`, not counting the structs it uses.
+    pub(crate) fn add_extensions(&self, extensions: &mut Vec<&'static str>) {
+        let Some(target) = self.target else {
+            return;
+        };
+
+        if self.offsets.is_some() {
+            extensions.extend(
+                target
+                    .offset_extension()
+                    .filter(|extension| !extensions.contains(extension)),
+            );
+        }
+
+        let array_type = self.runtime_array.as_ref().map(|array| array.ty);
+        target.add_extensions(
+            self.fields.iter().map(|field| field.ty).chain(array_type),
+            extensions,
+        );
+    }
+
+    /// Declares a member of the block, with a precision qualifier if its
+    /// target needs one.
+    fn declaration(&self, ty: &str, name: &str) -> String {
+        match self.target {
+            Some(target) => target.declaration(&self.block_name, ty, name),
+            None => format!("{} {}", ty, name),
+        }
+    }
+}
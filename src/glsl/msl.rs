@@ -0,0 +1,64 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::glsl::{visit_definition, write_doc_comment, GlslField, GlslStructDefinition};
+
+impl GlslStructDefinition {
+    /// Generates MSL code that defines this struct, without any of the structs
+    /// it depends on.
+    ///
+    /// If `first_id` is given, each field is annotated with `[[id(n)]]`
+    /// starting from that index, for use as an argument buffer.
+    ///
+    /// # Panics
+    /// Panics if a field has no MSL equivalent.
+    pub fn msl_definition(&self, first_id: Option<u32>) -> String {
+        let mut output = String::new();
+        write_doc_comment(&mut output, self.doc, "");
+        output.push_str("struct ");
+        output.push_str(self.name);
+        output.push_str(" {\n");
+
+        for (index, field) in self.fields.iter().enumerate() {
+            write_doc_comment(&mut output, field.doc, "\t");
+            write!(output, "\t{} {}", msl_type(self, field), field.declarator()).unwrap();
+            if let Some(first_id) = first_id {
+                write!(output, " [[id({})]]", first_id + index as u32).unwrap();
+            }
+            output.push_str(";\n");
+        }
+
+        output.push_str("};");
+        output
+    }
+}
+
+/// Generates MSL code defining all of the given structs and every struct they
+/// depend on.
+///
+/// Definitions are de-duplicated by name and ordered so that each struct is
+/// defined after the structs it uses.
+///
+/// # Panics
+/// Panics if a field has no MSL equivalent.
+pub fn msl_definitions(structs: &[&'static GlslStructDefinition]) -> String {
+    let mut definitions = Vec::new();
+    for definition in structs {
+        visit_definition(definition, &mut definitions);
+    }
+
+    definitions
+        .iter()
+        .map(|definition| definition.msl_definition(None))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn msl_type(definition: &GlslStructDefinition, field: &GlslField) -> &'static str {
+    field.msl_ty.unwrap_or_else(|| {
+        panic!(
+            "field `{}` of `{}` has type `{}`, which has no MSL equivalent",
+            field.name, definition.name, field.ty
+        )
+    })
+}
@@ -0,0 +1,243 @@
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+/// The GLSL dialect that code is generated for.
+///
+/// Generating code for a target checks that every type it uses exists there,
+/// adds the precision qualifiers GLSL ES needs and only emits the `layout`
+/// qualifiers the target accepts. Functions that generate code meant to go at
+/// the top of a shader, like [`glsl_definitions_for`][crate::glsl::glsl_definitions_for],
+/// also emit the `#extension` directives the code needs.
+///
+/// ```rust
+/// use crevice::glsl::{GlslBlock, GlslStruct, GlslTarget};
+///
+/// #[derive(GlslStruct)]
+/// struct Camera {
+///     view: mint::ColumnMatrix4<f32>,
+///     exposure: f32,
+/// }
+///
+/// let block = GlslBlock::uniform::<Camera>()
+///     .set(0)
+///     .binding(1)
+///     .target(GlslTarget::WEBGL2);
+///
+/// assert_eq!(
+///     block.glsl_definition(),
+///     "layout(std140) uniform Camera {\n\
+///     \thighp mat4 view;\n\
+///     \thighp float exposure;\n\
+///     };"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlslTarget {
+    /// Desktop OpenGL, with a GLSL version like `330` or `450`.
+    OpenGl(u32),
+
+    /// OpenGL ES, with a GLSL ES version like `300` or `310`.
+    OpenGlEs(u32),
+
+    /// Vulkan, as accepted by glslang with `GL_KHR_vulkan_glsl`.
+    Vulkan,
+}
+
+/// Whether a target can use a feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Support {
+    Native,
+    Extension(&'static str),
+    Unsupported,
+}
+
+/// Prefixes of the types added by `GL_EXT_shader_explicit_arithmetic_types`.
+const EXPLICIT_ARITHMETIC_TYPES: &[&str] = &[
+    "float16_t",
+    "f16vec",
+    "f16mat",
+    "float32_t",
+    "f32vec",
+    "f32mat",
+    "float64_t",
+    "f64vec",
+    "f64mat",
+    "int8_t",
+    "i8vec",
+    "uint8_t",
+    "u8vec",
+    "int16_t",
+    "i16vec",
+    "uint16_t",
+    "u16vec",
+    "int32_t",
+    "i32vec",
+    "uint32_t",
+    "u32vec",
+    "int64_t",
+    "i64vec",
+    "uint64_t",
+    "u64vec",
+];
+
+/// Primitive types that take a precision qualifier in GLSL ES.
+const PRECISION_TYPES: &[&str] = &[
+    "float", "vec2", "vec3", "vec4", "int", "ivec2", "ivec3", "ivec4", "uint", "uvec2", "uvec3",
+    "uvec4", "mat2", "mat3", "mat4", "mat2x2", "mat2x3", "mat2x4", "mat3x2", "mat3x3", "mat3x4",
+    "mat4x2", "mat4x3", "mat4x4",
+];
+
+impl GlslTarget {
+    /// WebGL2, which uses GLSL ES 3.00.
+    pub const WEBGL2: Self = Self::OpenGlEs(300);
+
+    /// Whether the `set` layout qualifier can be used. Only Vulkan has
+    /// descriptor sets.
+    pub fn supports_set(self) -> bool {
+        matches!(self, Self::Vulkan)
+    }
+
+    /// Whether blocks can be given a `binding` layout qualifier, which needs
+    /// GLSL 4.20 or GLSL ES 3.10.
+    pub fn supports_binding(self) -> bool {
+        match self {
+            Self::OpenGl(version) => version >= 420,
+            Self::OpenGlEs(version) => version >= 310,
+            Self::Vulkan => true,
+        }
+    }
+
+    /// Whether shader storage buffer blocks can be declared, which needs GLSL
+    /// 4.30 or GLSL ES 3.10.
+    pub fn supports_storage_buffers(self) -> bool {
+        match self {
+            Self::OpenGl(version) => version >= 430,
+            Self::OpenGlEs(version) => version >= 310,
+            Self::Vulkan => true,
+        }
+    }
+
+    /// Whether push constant blocks can be declared. Only Vulkan has push
+    /// constants.
+    pub fn supports_push_constants(self) -> bool {
+        matches!(self, Self::Vulkan)
+    }
+
+    /// How this target supports a GLSL type.
+    fn type_support(self, ty: &str) -> Support {
+        if ty == "double" || ty.starts_with("dvec") || ty.starts_with("dmat") {
+            match self {
+                Self::OpenGl(version) if version >= 400 => Support::Native,
+                Self::OpenGl(version) if version >= 150 => {
+                    Support::Extension("GL_ARB_gpu_shader_fp64")
+                }
+                Self::OpenGl(_) | Self::OpenGlEs(_) => Support::Unsupported,
+                Self::Vulkan => Support::Native,
+            }
+        } else if EXPLICIT_ARITHMETIC_TYPES
+            .iter()
+            .any(|prefix| ty.starts_with(prefix))
+        {
+            match self {
+                Self::OpenGl(_) | Self::Vulkan => {
+                    Support::Extension("GL_EXT_shader_explicit_arithmetic_types")
+                }
+                Self::OpenGlEs(_) => Support::Unsupported,
+            }
+        } else {
+            Support::Native
+        }
+    }
+
+    /// How this target supports `layout(offset = N)` on block members.
+    fn offset_support(self) -> Support {
+        match self {
+            Self::OpenGl(version) if version >= 440 => Support::Native,
+            Self::OpenGl(version) if version >= 140 => {
+                Support::Extension("GL_ARB_enhanced_layouts")
+            }
+            Self::OpenGl(_) | Self::OpenGlEs(_) => Support::Unsupported,
+            Self::Vulkan => Support::Native,
+        }
+    }
+
+    /// Panics if this target can't use `layout(offset = N)`.
+    pub(crate) fn check_offsets(self, block: &str) {
+        assert!(
+            self.offset_support() != Support::Unsupported,
+            "block `{}` has explicit offsets, which {} doesn't support",
+            block,
+            self
+        );
+    }
+
+    /// The `#extension` needed for explicit member offsets, if any.
+    pub(crate) fn offset_extension(self) -> Option<&'static str> {
+        match self.offset_support() {
+            Support::Extension(extension) => Some(extension),
+            Support::Native | Support::Unsupported => None,
+        }
+    }
+
+    /// Declares a member named `name` of type `ty` in `owner`, like
+    /// `highp vec3 color`.
+    ///
+    /// # Panics
+    /// Panics if this target doesn't have the member's type.
+    pub(crate) fn declaration(self, owner: &str, ty: &str, name: &str) -> String {
+        assert!(
+            self.type_support(ty) != Support::Unsupported,
+            "`{}.{}` has type `{}`, which {} doesn't support",
+            owner,
+            name,
+            ty,
+            self
+        );
+
+        match self {
+            Self::OpenGlEs(_) if PRECISION_TYPES.contains(&ty) => {
+                format!("highp {} {}", ty, name)
+            }
+            Self::OpenGl(_) | Self::OpenGlEs(_) | Self::Vulkan => format!("{} {}", ty, name),
+        }
+    }
+
+    /// Adds the extensions needed to use each of `types` to `extensions`,
+    /// skipping any that are already present.
+    pub(crate) fn add_extensions<'a>(
+        self,
+        types: impl IntoIterator<Item = &'a str>,
+        extensions: &mut Vec<&'static str>,
+    ) {
+        for ty in types {
+            if let Support::Extension(extension) = self.type_support(ty) {
+                if !extensions.contains(&extension) {
+                    extensions.push(extension);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for GlslTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OpenGl(version) => write!(f, "GLSL {}", version),
+            Self::OpenGlEs(version) => write!(f, "GLSL ES {}", version),
+            Self::Vulkan => f.write_str("Vulkan GLSL"),
+        }
+    }
+}
+
+/// Writes an `#extension` directive for each of `extensions`, followed by a
+/// blank line if there were any.
+pub(crate) fn extension_directives(extensions: &[&str]) -> String {
+    let mut output = String::new();
+    for extension in extensions {
+        output.push_str(&format!("#extension {} : require\n", extension));
+    }
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    output
+}
@@ -0,0 +1,695 @@
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::fmt;
+
+use crevice_glsl_source::{BlockLayout as Rules, Declaration, DeclarationKind, Member};
+
+use crate::layout::{AsLayout, Layout};
+use crate::reflect::{LayoutInfo, Reflect};
+
+/// Compares the layout of a GLSL uniform or storage buffer block with the
+/// layout of a Rust type, giving every difference between them.
+///
+/// `source` is GLSL code containing the block and any structs it uses. Member
+/// offsets are computed according to the block's layout qualifier, which
+/// defaults to `std140` for uniform blocks and `std430` for storage buffers.
+/// Explicit `offset` and `align` qualifiers on members are respected.
+///
+/// Members are matched to fields of `T` by name, and members of nested structs
+/// are compared too. A runtime-sized array at the end of a storage buffer is
+/// ignored, since it has no place in a Rust struct.
+///
+/// ```rust
+/// use crevice::glsl::{validate_block, LayoutDifference};
+/// use crevice::layout::Std140;
+/// use crevice::std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Camera {
+///     view: mint::ColumnMatrix4<f32>,
+///     position: mint::Vector3<f32>,
+///     exposure: f32,
+/// }
+///
+/// let source = "
+///     layout(std140, binding = 0) uniform Camera {
+///         mat4 view;
+///         vec4 position;
+///         float exposure;
+///     };
+/// ";
+///
+/// let diff = validate_block::<Camera, Std140>(source, "Camera").unwrap();
+/// assert_eq!(
+///     diff.differences,
+///     [
+///         LayoutDifference::TypeMismatch {
+///             path: "position".into(),
+///             shader_type: "vec4".into(),
+///             rust_type: "vec3".into(),
+///         },
+///         LayoutDifference::OffsetMismatch {
+///             path: "exposure".into(),
+///             shader_offset: 80,
+///             rust_offset: 76,
+///         },
+///     ]
+/// );
+/// ```
+pub fn validate_block<T, L>(source: &str, block_name: &str) -> Result<LayoutDiff, GlslSourceError>
+where
+    L: Layout,
+    T: AsLayout<L>,
+    <T as AsLayout<L>>::Output: Reflect,
+{
+    validate_block_layout(
+        source,
+        block_name,
+        L::NAME,
+        <T as AsLayout<L>>::Output::LAYOUT_INFO,
+    )
+}
+
+/// Like [`validate_block`], but compares against an already known layout.
+/// `layout_name` is the name of the layout `info` was computed with, like
+/// `std140`.
+pub fn validate_block_layout(
+    source: &str,
+    block_name: &str,
+    layout_name: &str,
+    info: &LayoutInfo,
+) -> Result<LayoutDiff, GlslSourceError> {
+    let source = Source::parse(source)?;
+    let (block, layout) = source
+        .declarations
+        .iter()
+        .find_map(|declaration| match declaration.kind {
+            DeclarationKind::Block(layout) if declaration.name == block_name => {
+                Some((declaration, layout))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| GlslSourceError::BlockNotFound {
+            name: block_name.to_owned(),
+        })?;
+
+    let mut differences = Vec::new();
+
+    if layout.name() != layout_name {
+        differences.push(LayoutDifference::LayoutMismatch {
+            shader_layout: layout.name().to_owned(),
+            rust_layout: layout_name.to_owned(),
+        });
+    }
+
+    // A runtime-sized array ending the block has no place in a Rust struct,
+    // so only the other members are compared.
+    let members = source.struct_layout(&block.members, layout)?.members;
+    compare_layouts(&members, info, "", 0, &mut differences);
+
+    Ok(LayoutDiff {
+        block: block_name.to_owned(),
+        differences,
+    })
+}
+
+/// The differences between a shader block and a Rust type, given by
+/// [`validate_block`] or [`spirv::validate_block`][crate::spirv::validate_block].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutDiff {
+    /// The name of the block.
+    pub block: String,
+
+    /// Every difference found, in the order of the block's members. Empty if
+    /// the layouts match.
+    pub differences: Vec<LayoutDifference>,
+}
+
+impl LayoutDiff {
+    /// Whether the block and the Rust type have the same layout.
+    pub fn is_match(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl fmt::Display for LayoutDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.differences.is_empty() {
+            return write!(f, "block `{}` matches", self.block);
+        }
+
+        write!(f, "block `{}` doesn't match:", self.block)?;
+        for difference in &self.differences {
+            write!(f, "\n- {}", difference)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single difference between a shader block and a Rust type.
+///
+/// Paths name members the same way as
+/// [`offset_of_path`][crate::reflect::offset_of_path], like `lights[0].color`.
+/// Offsets are measured from the start of the block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutDifference {
+    /// The block is declared with a different layout than the Rust type uses.
+    LayoutMismatch {
+        /// The block's layout, like `std430`.
+        shader_layout: String,
+
+        /// The layout of the Rust type, like `std140`.
+        rust_layout: String,
+    },
+
+    /// A member of the block has no matching Rust field.
+    MissingField {
+        /// The path of the member.
+        path: String,
+
+        /// The member's GLSL type.
+        shader_type: String,
+    },
+
+    /// A Rust field has no matching member in the block.
+    ExtraField {
+        /// The path of the field.
+        path: String,
+
+        /// The field's GLSL type.
+        rust_type: String,
+    },
+
+    /// A member has different types in the shader and in Rust. Array lengths
+    /// are part of the type, like `float[4]`.
+    TypeMismatch {
+        /// The path of the member.
+        path: String,
+
+        /// The member's GLSL type.
+        shader_type: String,
+
+        /// The GLSL type of the Rust field.
+        rust_type: String,
+    },
+
+    /// A member is at different offsets in the shader and in Rust.
+    OffsetMismatch {
+        /// The path of the member.
+        path: String,
+
+        /// The member's offset in the shader.
+        shader_offset: usize,
+
+        /// The field's offset in Rust.
+        rust_offset: usize,
+    },
+
+    /// A member has different sizes in the shader and in Rust.
+    SizeMismatch {
+        /// The path of the member.
+        path: String,
+
+        /// The member's size in the shader.
+        shader_size: usize,
+
+        /// The field's size in Rust.
+        rust_size: usize,
+    },
+
+    /// An array has a different distance between its elements in the shader
+    /// and in Rust.
+    ArrayStrideMismatch {
+        /// The path of the array.
+        path: String,
+
+        /// The array's stride in the shader.
+        shader_stride: usize,
+
+        /// The array's stride in Rust.
+        rust_stride: usize,
+    },
+
+    /// A matrix, or an array of them, has a different distance between its
+    /// columns in the shader and in Rust.
+    MatrixStrideMismatch {
+        /// The path of the matrix.
+        path: String,
+
+        /// The matrix's stride in the shader.
+        shader_stride: usize,
+
+        /// The matrix's stride in Rust.
+        rust_stride: usize,
+    },
+}
+
+impl fmt::Display for LayoutDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LayoutMismatch {
+                shader_layout,
+                rust_layout,
+            } => write!(
+                f,
+                "the block uses {} layout, but the Rust type uses {}",
+                shader_layout, rust_layout
+            ),
+            Self::MissingField { path, shader_type } => {
+                write!(
+                    f,
+                    "`{}` ({}) is missing from the Rust type",
+                    path, shader_type
+                )
+            }
+            Self::ExtraField { path, rust_type } => {
+                write!(f, "`{}` ({}) is missing from the shader", path, rust_type)
+            }
+            Self::TypeMismatch {
+                path,
+                shader_type,
+                rust_type,
+            } => write!(
+                f,
+                "`{}` is {} in the shader but {} in Rust",
+                path, shader_type, rust_type
+            ),
+            Self::OffsetMismatch {
+                path,
+                shader_offset,
+                rust_offset,
+            } => write!(
+                f,
+                "`{}` is at offset {} in the shader but {} in Rust",
+                path, shader_offset, rust_offset
+            ),
+            Self::SizeMismatch {
+                path,
+                shader_size,
+                rust_size,
+            } => write!(
+                f,
+                "`{}` is {} bytes in the shader but {} in Rust",
+                path, shader_size, rust_size
+            ),
+            Self::ArrayStrideMismatch {
+                path,
+                shader_stride,
+                rust_stride,
+            } => write!(
+                f,
+                "`{}` has an array stride of {} in the shader but {} in Rust",
+                path, shader_stride, rust_stride
+            ),
+            Self::MatrixStrideMismatch {
+                path,
+                shader_stride,
+                rust_stride,
+            } => write!(
+                f,
+                "`{}` has a matrix stride of {} in the shader but {} in Rust",
+                path, shader_stride, rust_stride
+            ),
+        }
+    }
+}
+
+/// An error that prevented a GLSL block from being validated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlslSourceError {
+    /// The source couldn't be parsed, or uses something that can't be laid
+    /// out, at the given line and column.
+    Syntax {
+        /// The line of the error, starting from 1.
+        line: usize,
+
+        /// The column of the error, starting from 1.
+        column: usize,
+
+        /// What went wrong.
+        message: String,
+    },
+
+    /// The source doesn't declare a uniform or storage buffer block with the
+    /// given name.
+    BlockNotFound {
+        /// The name of the block.
+        name: String,
+    },
+}
+
+impl fmt::Display for GlslSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}: {}", line, column, message),
+            Self::BlockNotFound { name } => write!(f, "no block named `{}` was found", name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GlslSourceError {}
+
+/// Gives the GLSL name of an array of `len` elements of the type named
+/// `element`, like `float[2][3]` for two elements of type `float[3]`.
+pub(crate) fn array_type_name(element: &str, len: usize) -> String {
+    match element.find('[') {
+        Some(index) => format!("{}[{}]{}", &element[..index], len, &element[index..]),
+        None => format!("{}[{}]", element, len),
+    }
+}
+
+/// Gives the GLSL name of a Rust type's layout, including any array
+/// dimensions.
+fn layout_type_name(layout: &LayoutInfo) -> String {
+    match &layout.array {
+        Some(array) => array_type_name(&layout_type_name(array.element), array.len),
+        None => layout.name.to_owned(),
+    }
+}
+
+/// Compares the members of a shader struct or block with the fields of a Rust
+/// type, adding any differences to `differences`.
+pub(crate) fn compare_layouts(
+    members: &[MemberLayout],
+    info: &LayoutInfo,
+    path: &str,
+    base: usize,
+    differences: &mut Vec<LayoutDifference>,
+) {
+    for member in members {
+        let member_path = format!("{}{}", path, member.name);
+
+        let field = match info.fields.iter().find(|field| field.name == member.name) {
+            Some(field) => field,
+            None => {
+                differences.push(LayoutDifference::MissingField {
+                    path: member_path,
+                    shader_type: member.ty.clone(),
+                });
+                continue;
+            }
+        };
+
+        let rust_type = layout_type_name(field.layout);
+
+        if rust_type != member.ty {
+            differences.push(LayoutDifference::TypeMismatch {
+                path: member_path,
+                shader_type: member.ty.clone(),
+                rust_type,
+            });
+            continue;
+        }
+
+        if member.offset != field.offset {
+            differences.push(LayoutDifference::OffsetMismatch {
+                path: member_path.clone(),
+                shader_offset: base + member.offset,
+                rust_offset: base + field.offset,
+            });
+        }
+
+        if let Some(size) = member.size.filter(|&size| size != field.size) {
+            differences.push(LayoutDifference::SizeMismatch {
+                path: member_path.clone(),
+                shader_size: size,
+                rust_size: field.size,
+            });
+        }
+
+        let element = match &field.layout.array {
+            Some(array) => {
+                if let Some(stride) = member.array_stride.filter(|&stride| stride != array.stride) {
+                    differences.push(LayoutDifference::ArrayStrideMismatch {
+                        path: member_path.clone(),
+                        shader_stride: stride,
+                        rust_stride: array.stride,
+                    });
+                }
+                array.element
+            }
+            None => field.layout,
+        };
+
+        let rust_matrix_stride = element.columns.map(|columns| columns.stride);
+        if let (Some(stride), Some(rust_stride)) = (member.matrix_stride, rust_matrix_stride) {
+            if stride != rust_stride {
+                differences.push(LayoutDifference::MatrixStrideMismatch {
+                    path: member_path.clone(),
+                    shader_stride: stride,
+                    rust_stride,
+                });
+            }
+        }
+
+        if !member.fields.is_empty() {
+            // Every element of an array has the same layout, so only the first
+            // is compared.
+            let element_path = match &field.layout.array {
+                Some(_) => format!("{}[0].", member_path),
+                None => format!("{}.", member_path),
+            };
+
+            compare_layouts(
+                &member.fields,
+                element,
+                &element_path,
+                base + member.offset,
+                differences,
+            );
+        }
+    }
+
+    for field in info.fields {
+        if !members.iter().any(|member| member.name == field.name) {
+            let rust_type = layout_type_name(field.layout);
+
+            differences.push(LayoutDifference::ExtraField {
+                path: format!("{}{}", path, field.name),
+                rust_type,
+            });
+        }
+    }
+}
+
+/// The number of columns of a GLSL matrix type, like 3 for `mat3x2`.
+fn matrix_columns(ty: &str) -> Option<usize> {
+    let dims = ty.strip_prefix('d').unwrap_or(ty).strip_prefix("mat")?;
+    match dims.split_once('x') {
+        Some((columns, _)) => dimension(columns),
+        None => dimension(dims),
+    }
+}
+
+/// The layout of a member of a shader struct or block.
+pub(crate) struct MemberLayout {
+    pub name: String,
+
+    /// The member's GLSL type, with the array length if it's an array.
+    pub ty: String,
+
+    /// The offset of the member within its struct or block.
+    pub offset: usize,
+
+    /// The size of the member, if it's known.
+    pub size: Option<usize>,
+    pub array_stride: Option<usize>,
+    pub matrix_stride: Option<usize>,
+
+    /// The members of the struct type of this member or its elements.
+    pub fields: Vec<MemberLayout>,
+}
+
+/// The computed layout of a struct.
+struct StructLayout {
+    members: Vec<MemberLayout>,
+    size: usize,
+    alignment: usize,
+}
+
+/// The size and alignment of a type.
+#[derive(Clone, Copy)]
+struct TypeLayout {
+    size: usize,
+    alignment: usize,
+}
+
+fn round_up(value: usize, alignment: usize) -> usize {
+    value.div_ceil(alignment.max(1)) * alignment.max(1)
+}
+
+/// The layout of a GLSL scalar, vector or matrix type, if `ty` names one.
+fn primitive_layout(ty: &str, rules: Rules) -> Option<TypeLayout> {
+    let (scalar, rest) = match ty {
+        "float" | "int" | "uint" | "bool" => return Some(vector(4, 1)),
+        "double" => return Some(vector(8, 1)),
+        _ if ty.starts_with("dvec") || ty.starts_with("dmat") => (8, &ty[1..]),
+        _ if ty.starts_with("vec") || ty.starts_with("mat") => (4, ty),
+        _ if ty.starts_with("ivec") || ty.starts_with("uvec") || ty.starts_with("bvec") => {
+            (4, &ty[1..])
+        }
+        _ => return None,
+    };
+
+    if let Some(n) = rest.strip_prefix("vec") {
+        return dimension(n).map(|n| vector(scalar, n));
+    }
+
+    // Matrices are arrays of column vectors, like `mat3x2` having 3 columns of
+    // `vec2`.
+    let dims = rest.strip_prefix("mat")?;
+    let (columns, rows) = match dims.split_once('x') {
+        Some((columns, rows)) => (dimension(columns)?, dimension(rows)?),
+        None => (dimension(dims)?, dimension(dims)?),
+    };
+
+    Some(array_layout(vector(scalar, rows), columns, rules))
+}
+
+fn dimension(n: &str) -> Option<usize> {
+    match n {
+        "2" => Some(2),
+        "3" => Some(3),
+        "4" => Some(4),
+        _ => None,
+    }
+}
+
+/// The layout of a scalar or vector with `n` components of `scalar` bytes.
+fn vector(scalar: usize, n: usize) -> TypeLayout {
+    let alignment = match n {
+        1 => scalar,
+        2 => scalar * 2,
+        _ => scalar * 4,
+    };
+
+    TypeLayout {
+        size: scalar * n,
+        alignment,
+    }
+}
+
+/// The stride of an array whose elements have the given layout.
+fn array_stride(element: TypeLayout, rules: Rules) -> usize {
+    match rules {
+        Rules::Std140 => round_up(round_up(element.size, element.alignment), 16),
+        Rules::Std430 => round_up(element.size, element.alignment),
+    }
+}
+
+fn array_layout(element: TypeLayout, len: usize, rules: Rules) -> TypeLayout {
+    let alignment = match rules {
+        Rules::Std140 => round_up(element.alignment, 16),
+        Rules::Std430 => element.alignment,
+    };
+
+    TypeLayout {
+        size: array_stride(element, rules) * len,
+        alignment,
+    }
+}
+
+/// The structs and blocks declared in GLSL source.
+struct Source {
+    declarations: Vec<Declaration>,
+}
+
+impl Source {
+    fn parse(source: &str) -> Result<Self, GlslSourceError> {
+        let declarations = crevice_glsl_source::parse(source)
+            .map_err(|err| syntax_error(err.line, err.column, err.message))?;
+
+        Ok(Self { declarations })
+    }
+
+    fn struct_layout(
+        &self,
+        members: &[Member],
+        rules: Rules,
+    ) -> Result<StructLayout, GlslSourceError> {
+        let mut layouts = Vec::new();
+        let mut offset = 0;
+        let mut struct_alignment = 1;
+
+        for member in members {
+            let (element, fields) = match primitive_layout(&member.ty, rules) {
+                Some(layout) => (layout, Vec::new()),
+                None => {
+                    let declaration = self
+                        .declarations
+                        .iter()
+                        .find(|declaration| {
+                            declaration.kind == DeclarationKind::Struct
+                                && declaration.name == member.ty
+                        })
+                        .ok_or_else(|| {
+                            syntax_error(
+                                member.line,
+                                member.column,
+                                format!("`{}` isn't a type that can be laid out", member.ty),
+                            )
+                        })?;
+
+                    let layout = self.struct_layout(&declaration.members, rules)?;
+                    let element = TypeLayout {
+                        size: layout.size,
+                        alignment: layout.alignment,
+                    };
+                    (element, layout.members)
+                }
+            };
+
+            // Array dimensions are listed outermost first, so the innermost
+            // array is laid out first.
+            let mut layout = element;
+            let mut ty = member.ty.clone();
+            let mut stride = None;
+            for &len in member.array.iter().rev() {
+                stride = Some(array_stride(layout, rules));
+                layout = array_layout(layout, len, rules);
+                ty = array_type_name(&ty, len);
+            }
+
+            let alignment = layout.alignment.max(member.align.unwrap_or(1));
+            offset = match member.offset {
+                Some(explicit) => explicit,
+                None => round_up(offset, alignment),
+            };
+            struct_alignment = struct_alignment.max(alignment);
+
+            layouts.push(MemberLayout {
+                name: member.name.clone(),
+                ty,
+                offset,
+                size: Some(layout.size),
+                array_stride: stride,
+                matrix_stride: matrix_columns(&member.ty).map(|columns| element.size / columns),
+                fields,
+            });
+
+            offset += layout.size;
+        }
+
+        if rules == Rules::Std140 {
+            struct_alignment = round_up(struct_alignment, 16);
+        }
+
+        Ok(StructLayout {
+            members: layouts,
+            size: round_up(offset, struct_alignment),
+            alignment: struct_alignment,
+        })
+    }
+}
+
+fn syntax_error(line: usize, column: usize, message: String) -> GlslSourceError {
+    GlslSourceError::Syntax {
+        line,
+        column,
+        message,
+    }
+}
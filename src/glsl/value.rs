@@ -0,0 +1,207 @@
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+use core::fmt::{self, Write};
+
+use crate::bool::Bool;
+use crate::glsl::{Glsl, GlslArray};
+
+pub use crevice_derive::GlslValue;
+
+/// Trait for values that can be written as a GLSL expression, like
+/// `vec3(0.0, 1.0, 0.0)` or `Light(vec3(0.0, 1.0, 0.0), 0.5)`. Useful for
+/// generating GLSL constants from Rust data.
+///
+/// Floats are always written with a decimal point, doubles with an `lf` suffix
+/// and unsigned integers with a `u` suffix, so that every expression has the
+/// same type in GLSL as in Rust.
+///
+/// This trait can be derived for structs that also derive
+/// [`GlslStruct`][crate::glsl::GlslStruct], which writes a call to the struct's
+/// constructor.
+#[cfg_attr(
+    feature = "alloc",
+    doc = r##"
+```rust
+use crevice::glsl::{glsl_constant_array, GlslStruct, GlslValue};
+
+#[derive(GlslStruct, GlslValue)]
+struct Light {
+    direction: mint::Vector3<f32>,
+    intensity: f32,
+}
+
+let lights = [
+    Light {
+        direction: [0.0, 1.0, 0.0].into(),
+        intensity: 0.5,
+    },
+    Light {
+        direction: [1.0, 0.0, 0.0].into(),
+        intensity: 2.0,
+    },
+];
+
+assert_eq!(
+    glsl_constant_array("DEFAULT_LIGHTS", &lights),
+    "const Light DEFAULT_LIGHTS[2] = Light[2](\
+    Light(vec3(0.0, 1.0, 0.0), 0.5), \
+    Light(vec3(1.0, 0.0, 0.0), 2.0));"
+);
+```
+"##
+)]
+pub trait GlslValue {
+    /// Writes this value as a GLSL expression.
+    fn write_glsl_value(&self, output: &mut dyn Write) -> fmt::Result;
+
+    /// Gives this value as a GLSL expression.
+    #[cfg(feature = "alloc")]
+    fn to_glsl_value(&self) -> String {
+        let mut output = String::new();
+        self.write_glsl_value(&mut output).unwrap();
+        output
+    }
+}
+
+/// Generates a GLSL constant declaration, like
+/// `const vec3 UP = vec3(0.0, 1.0, 0.0);`.
+#[cfg(feature = "alloc")]
+pub fn glsl_constant<T: Glsl + GlslValue>(name: &str, value: &T) -> String {
+    let mut output = format!("const {} {}", T::NAME, name);
+    GlslArray::write_suffix(T::ARRAY, &mut output).unwrap();
+    write!(output, " = {};", value.to_glsl_value()).unwrap();
+    output
+}
+
+/// Generates a GLSL constant array declaration, like
+/// `const float WEIGHTS[2] = float[2](0.25, 0.75);`.
+#[cfg(feature = "alloc")]
+pub fn glsl_constant_array<T: Glsl + GlslValue>(name: &str, values: &[T]) -> String {
+    let mut output = format!("const {} {}[{}]", T::NAME, name, values.len());
+    GlslArray::write_suffix(T::ARRAY, &mut output).unwrap();
+    output.push_str(" = ");
+    write_array(&mut output, values).unwrap();
+    output.push(';');
+    output
+}
+
+/// Writes the arguments of a constructor call, like `(1.0, 2.0)`.
+#[doc(hidden)]
+pub fn write_arguments(output: &mut dyn Write, arguments: &[&dyn GlslValue]) -> fmt::Result {
+    output.write_char('(')?;
+
+    for (index, argument) in arguments.iter().enumerate() {
+        if index > 0 {
+            output.write_str(", ")?;
+        }
+        argument.write_glsl_value(output)?;
+    }
+
+    output.write_char(')')
+}
+
+fn write_array<T: Glsl + GlslValue>(output: &mut dyn Write, values: &[T]) -> fmt::Result {
+    write!(output, "{}[{}]", T::NAME, values.len())?;
+    GlslArray::write_suffix(T::ARRAY, output)?;
+    output.write_char('(')?;
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            output.write_str(", ")?;
+        }
+        value.write_glsl_value(output)?;
+    }
+    output.write_char(')')
+}
+
+impl<T: Glsl + GlslValue, const N: usize> GlslValue for [T; N] {
+    fn write_glsl_value(&self, output: &mut dyn Write) -> fmt::Result {
+        write_array(output, self)
+    }
+}
+
+/// Wraps a writer, tracking whether a decimal point has been written.
+struct DecimalPoint<'a> {
+    output: &'a mut dyn Write,
+    found: bool,
+}
+
+impl Write for DecimalPoint<'_> {
+    fn write_str(&mut self, value: &str) -> fmt::Result {
+        self.found |= value.contains('.');
+        self.output.write_str(value)
+    }
+}
+
+/// Writes a finite float so that GLSL reads it as a floating point literal.
+fn write_float(output: &mut dyn Write, value: impl fmt::Display) -> fmt::Result {
+    let mut output = DecimalPoint {
+        output,
+        found: false,
+    };
+
+    // Rust never formats floats with an exponent, so the only thing that can
+    // be missing is the decimal point.
+    write!(output, "{}", value)?;
+    if !output.found {
+        output.write_str(".0")?;
+    }
+
+    Ok(())
+}
+
+impl GlslValue for f32 {
+    fn write_glsl_value(&self, output: &mut dyn Write) -> fmt::Result {
+        if self.is_finite() {
+            write_float(output, self)
+        } else {
+            // GLSL has no literals for infinity or NaN.
+            write!(output, "uintBitsToFloat({}u)", self.to_bits())
+        }
+    }
+}
+
+impl GlslValue for f64 {
+    fn write_glsl_value(&self, output: &mut dyn Write) -> fmt::Result {
+        if self.is_finite() {
+            write_float(output, self)?;
+            output.write_str("lf")
+        } else {
+            let bits = self.to_bits();
+            write!(
+                output,
+                "packDouble2x32(uvec2({}u, {}u))",
+                bits as u32,
+                (bits >> 32) as u32
+            )
+        }
+    }
+}
+
+impl GlslValue for i32 {
+    fn write_glsl_value(&self, output: &mut dyn Write) -> fmt::Result {
+        if *self == i32::MIN {
+            // 2147483648 doesn't fit in an int, so it can't be negated.
+            output.write_str("(-2147483647 - 1)")
+        } else {
+            write!(output, "{}", self)
+        }
+    }
+}
+
+impl GlslValue for u32 {
+    fn write_glsl_value(&self, output: &mut dyn Write) -> fmt::Result {
+        write!(output, "{}u", self)
+    }
+}
+
+impl GlslValue for bool {
+    fn write_glsl_value(&self, output: &mut dyn Write) -> fmt::Result {
+        write!(output, "{}", self)
+    }
+}
+
+impl GlslValue for Bool {
+    fn write_glsl_value(&self, output: &mut dyn Write) -> fmt::Result {
+        bool::from(*self).write_glsl_value(output)
+    }
+}
@@ -0,0 +1,343 @@
+/*!
+Defines traits and types for generating HLSL code from Rust definitions.
+
+All HLSL primitives, like `int` or `float3`, implement the [`Hlsl`] trait.
+Structs should implement [`HlslStruct`], which can be derived.
+
+HLSL structs are packed according to constant buffer rules, so the Rust type
+should be uploaded using [`AsHlslCbuffer`][crate::hlsl_cbuffer::AsHlslCbuffer].
+Crevice stores matrices one column per register, which HLSL calls
+`column_major`. Matrix members are always given an explicit `row_major` or
+`column_major` qualifier so that compiler flags like `/Zpr` can't change how
+they're read.
+
+[`HlslConstantBuffer`] declares a `cbuffer` whose members are the fields of a
+struct, each pinned to the offset Crevice computed with `packoffset`.
+
+## Examples
+Given this struct:
+*/
+#![cfg_attr(
+    feature = "std",
+    doc = r##"
+```rust
+use mint::{ColumnMatrix4, Vector3};
+use crevice::hlsl::HlslStruct;
+
+#[derive(HlslStruct)]
+struct SpotLight {
+    transform: ColumnMatrix4<f32>,
+    color: Vector3<f32>,
+    intensity: f32,
+}
+
+println!("{}", SpotLight::hlsl_definition());
+```
+"##
+)]
+/*!
+The output will be:
+```hlsl
+struct SpotLight {
+    column_major float4x4 transform;
+    float3 color;
+    float intensity;
+};
+```
+*/
+
+#[cfg(feature = "alloc")]
+mod cbuffer;
+
+#[cfg(feature = "alloc")]
+pub use self::cbuffer::*;
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, vec::Vec};
+
+pub use crevice_derive::HlslStruct;
+
+/// Trait for types that have an HLSL equivalent. Useful for generating HLSL
+/// code from Rust structs.
+///
+/// # Safety
+/// `NAME` must name an HLSL type with the same memory representation as the
+/// implementing type when packed into a constant buffer, or as its elements if
+/// it is an array, and `TYPE` must describe it accurately.
+pub unsafe trait Hlsl {
+    /// The name of this type in HLSL, like `float2` or `float4x4`.
+    const NAME: &'static str;
+
+    /// Describes what kind of type this is.
+    const TYPE: HlslType;
+}
+
+/// Describes an HLSL type.
+#[derive(Debug, Clone, Copy)]
+pub enum HlslType {
+    /// A scalar or vector, like `float` or `uint3`.
+    Primitive,
+
+    /// A matrix, like `float3x3`, which is declared with a `row_major` or
+    /// `column_major` qualifier.
+    Matrix,
+
+    /// A struct with the given definition.
+    Struct(&'static HlslStructDefinition),
+
+    /// A fixed-size array, like `float weights[4]`. Arrays are named after
+    /// their element type, so `[f32; 4]` has the `NAME` `float`.
+    Array {
+        /// The number of elements in the array.
+        len: usize,
+
+        /// Describes the array's element type.
+        element: &'static HlslType,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl HlslType {
+    /// The struct this type is, or is an array of, if any.
+    pub(crate) fn definition(&self) -> Option<&'static HlslStructDefinition> {
+        match *self {
+            HlslType::Primitive | HlslType::Matrix => None,
+            HlslType::Struct(definition) => Some(definition),
+            HlslType::Array { element, .. } => element.definition(),
+        }
+    }
+}
+
+/// How matrices are declared in generated HLSL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatrixLayout {
+    /// `column_major`: each register holds one column of the Rust matrix, so
+    /// HLSL sees the same matrix as Rust. This is the default.
+    #[default]
+    ColumnMajor,
+
+    /// `row_major`: each register holds one row of the HLSL matrix, so HLSL
+    /// sees the transpose of the Rust matrix. Useful for shaders written as
+    /// `mul(vector, matrix)`.
+    RowMajor,
+}
+
+#[cfg(feature = "alloc")]
+impl MatrixLayout {
+    fn qualifier(self) -> &'static str {
+        match self {
+            Self::ColumnMajor => "column_major",
+            Self::RowMajor => "row_major",
+        }
+    }
+}
+
+/// A field contained within an HLSL struct definition.
+#[derive(Debug, Clone, Copy)]
+pub struct HlslField {
+    /// The type of the field, like `float2` or `float3x3`.
+    pub ty: &'static str,
+
+    /// The field's name. This must be a valid HLSL identifier.
+    pub name: &'static str,
+
+    /// Describes the field's type.
+    pub hlsl_type: HlslType,
+}
+
+/// The definition of an HLSL struct, which links to the definitions of any
+/// structs used by its fields.
+#[derive(Debug)]
+pub struct HlslStructDefinition {
+    /// The name of the struct.
+    pub name: &'static str,
+
+    /// The fields contained in the struct, in memory order.
+    pub fields: &'static [HlslField],
+}
+
+#[cfg(feature = "alloc")]
+impl HlslStructDefinition {
+    /// Generates HLSL code that defines this struct, without any of the structs
+    /// it depends on.
+    pub fn hlsl_definition(&self, matrices: MatrixLayout) -> String {
+        let mut output = String::new();
+        output.push_str("struct ");
+        output.push_str(self.name);
+        output.push_str(" {\n");
+
+        for field in self.fields {
+            output.push_str("    ");
+            output.push_str(&field.declaration(matrices));
+            output.push_str(";\n");
+        }
+
+        output.push_str("};");
+        output
+    }
+
+    /// Gives every struct this struct depends on, transitively, followed by
+    /// this struct. Each struct comes after all of the structs it depends on
+    /// and appears only once.
+    pub fn with_dependencies(&'static self) -> Vec<&'static HlslStructDefinition> {
+        let mut output = Vec::new();
+        visit_definition(self, &mut output);
+        output
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl HlslField {
+    /// The field's type and name, with a matrix qualifier and array
+    /// dimensions if needed.
+    fn declaration(&self, matrices: MatrixLayout) -> String {
+        let mut output = String::new();
+        let mut dimensions = String::new();
+
+        let mut hlsl_type = self.hlsl_type;
+        while let HlslType::Array { len, element } = hlsl_type {
+            dimensions.push_str(&format!("[{}]", len));
+            hlsl_type = *element;
+        }
+
+        if let HlslType::Matrix = hlsl_type {
+            output.push_str(matrices.qualifier());
+            output.push(' ');
+        }
+
+        output.push_str(&format!("{} {}{}", self.ty, self.name, dimensions));
+        output
+    }
+}
+
+/// Generates HLSL code defining all of the given structs and every struct they
+/// depend on.
+///
+/// Definitions are de-duplicated by name and ordered so that each struct is
+/// defined after the structs it uses.
+#[cfg(feature = "alloc")]
+pub fn hlsl_definitions(
+    structs: &[&'static HlslStructDefinition],
+    matrices: MatrixLayout,
+) -> String {
+    let mut definitions = Vec::new();
+    for definition in structs {
+        visit_definition(definition, &mut definitions);
+    }
+
+    definitions
+        .iter()
+        .map(|definition| definition.hlsl_definition(matrices))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Adds `definition` to `output` after all of its dependencies, skipping any
+/// structs that are already present.
+#[cfg(feature = "alloc")]
+fn visit_definition(
+    definition: &'static HlslStructDefinition,
+    output: &mut Vec<&'static HlslStructDefinition>,
+) {
+    if output
+        .iter()
+        .any(|existing| existing.name == definition.name)
+    {
+        return;
+    }
+
+    for field in definition.fields {
+        if let Some(field_definition) = field.hlsl_type.definition() {
+            visit_definition(field_definition, output);
+        }
+    }
+
+    output.push(definition);
+}
+
+/// Trait for types that can be represented as a struct in HLSL.
+///
+/// This trait should not generally be implemented by hand, but can be derived.
+///
+/// # Safety
+/// `TYPE` must be [`HlslType::Struct`] with a definition listing every field
+/// of the struct, in the order they are laid out in memory.
+pub unsafe trait HlslStruct: Hlsl {
+    /// The definition of this struct.
+    const DEFINITION: &'static HlslStructDefinition = match Self::TYPE {
+        HlslType::Struct(definition) => definition,
+        HlslType::Primitive | HlslType::Matrix | HlslType::Array { .. } => {
+            panic!("HlslStruct types must be structs")
+        }
+    };
+
+    /// Generates HLSL code that represents this struct and its fields, with
+    /// `column_major` matrices.
+    #[cfg(feature = "alloc")]
+    fn hlsl_definition() -> String {
+        Self::DEFINITION.hlsl_definition(MatrixLayout::ColumnMajor)
+    }
+
+    /// Generates HLSL code that defines this struct and every struct it
+    /// depends on, in an order HLSL accepts.
+    #[cfg(feature = "alloc")]
+    fn hlsl_definition_with_dependencies() -> String {
+        hlsl_definitions(&[Self::DEFINITION], MatrixLayout::ColumnMajor)
+    }
+}
+
+macro_rules! primitives {
+    ( $( $ty:ty => $name:literal $kind:ident, )* ) => {
+        $(
+            unsafe impl Hlsl for $ty {
+                const NAME: &'static str = $name;
+                const TYPE: HlslType = HlslType::$kind;
+            }
+        )*
+    };
+}
+
+primitives! {
+    f32 => "float" Primitive,
+    f64 => "double" Primitive,
+    i32 => "int" Primitive,
+    u32 => "uint" Primitive,
+    bool => "bool" Primitive,
+    crate::bool::Bool => "bool" Primitive,
+
+    crate::hlsl_cbuffer::Vec2 => "float2" Primitive,
+    crate::hlsl_cbuffer::Vec3 => "float3" Primitive,
+    crate::hlsl_cbuffer::Vec4 => "float4" Primitive,
+
+    crate::hlsl_cbuffer::IVec2 => "int2" Primitive,
+    crate::hlsl_cbuffer::IVec3 => "int3" Primitive,
+    crate::hlsl_cbuffer::IVec4 => "int4" Primitive,
+
+    crate::hlsl_cbuffer::UVec2 => "uint2" Primitive,
+    crate::hlsl_cbuffer::UVec3 => "uint3" Primitive,
+    crate::hlsl_cbuffer::UVec4 => "uint4" Primitive,
+
+    crate::hlsl_cbuffer::BVec2 => "bool2" Primitive,
+    crate::hlsl_cbuffer::BVec3 => "bool3" Primitive,
+    crate::hlsl_cbuffer::BVec4 => "bool4" Primitive,
+
+    crate::hlsl_cbuffer::DVec2 => "double2" Primitive,
+    crate::hlsl_cbuffer::DVec3 => "double3" Primitive,
+    crate::hlsl_cbuffer::DVec4 => "double4" Primitive,
+
+    crate::hlsl_cbuffer::Mat2 => "float2x2" Matrix,
+    crate::hlsl_cbuffer::Mat3 => "float3x3" Matrix,
+    crate::hlsl_cbuffer::Mat4 => "float4x4" Matrix,
+
+    crate::hlsl_cbuffer::DMat2 => "double2x2" Matrix,
+    crate::hlsl_cbuffer::DMat3 => "double3x3" Matrix,
+    crate::hlsl_cbuffer::DMat4 => "double4x4" Matrix,
+}
+
+unsafe impl<T: Hlsl, const N: usize> Hlsl for [T; N] {
+    const NAME: &'static str = T::NAME;
+    const TYPE: HlslType = HlslType::Array {
+        len: N,
+        element: &T::TYPE,
+    };
+}
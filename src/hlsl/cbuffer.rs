@@ -0,0 +1,196 @@
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::hlsl::{hlsl_definitions, HlslField, HlslStruct, HlslStructDefinition, MatrixLayout};
+use crate::hlsl_cbuffer::AsHlslCbuffer;
+use crate::reflect::Reflect;
+
+/// Generates the declaration of an HLSL constant buffer from a struct, like
+/// `cbuffer Camera : register(b0, space1) { ... };`.
+///
+/// The constant buffer's members are the struct's fields. Each member is
+/// pinned with `packoffset` to the offset it has in the struct's
+/// [`AsHlslCbuffer`] representation.
+///
+/// ```rust
+/// use crevice::hlsl::{HlslConstantBuffer, HlslStruct};
+/// use crevice::hlsl_cbuffer::AsHlslCbuffer;
+///
+/// #[derive(AsHlslCbuffer, HlslStruct)]
+/// struct Camera {
+///     view: mint::ColumnMatrix4<f32>,
+///     position: mint::Vector3<f32>,
+///     exposure: f32,
+/// }
+///
+/// let cbuffer = HlslConstantBuffer::new::<Camera>().register(0).space(1);
+///
+/// assert_eq!(
+///     cbuffer.hlsl_definition(),
+///     "cbuffer Camera : register(b0, space1)\n\
+///     {\n    \
+///         column_major float4x4 view : packoffset(c0);\n    \
+///         float3 position : packoffset(c4);\n    \
+///         float exposure : packoffset(c4.w);\n\
+///     };"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct HlslConstantBuffer {
+    name: String,
+    register: Option<u32>,
+    space: Option<u32>,
+    matrices: MatrixLayout,
+    members: Vec<(HlslField, usize)>,
+}
+
+impl HlslConstantBuffer {
+    /// Creates a constant buffer whose members are the fields of `T`.
+    pub fn new<T>() -> Self
+    where
+        T: HlslStruct + AsHlslCbuffer,
+        <T as AsHlslCbuffer>::Output: Reflect,
+    {
+        let definition = T::DEFINITION;
+        let layout = <T as AsHlslCbuffer>::Output::LAYOUT_INFO;
+
+        assert_eq!(
+            definition.fields.len(),
+            layout.fields.len(),
+            "layout of `{}` doesn't match its HLSL definition",
+            definition.name
+        );
+
+        let mut members: Vec<_> = definition
+            .fields
+            .iter()
+            .map(|field| {
+                let info = layout
+                    .fields
+                    .iter()
+                    .find(|info| info.name == field.name)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "layout of `{}` has no field `{}`",
+                            definition.name, field.name
+                        )
+                    });
+
+                (*field, info.offset)
+            })
+            .collect();
+
+        members.sort_by_key(|(_, offset)| *offset);
+
+        Self {
+            name: T::NAME.to_owned(),
+            register: None,
+            space: None,
+            matrices: MatrixLayout::ColumnMajor,
+            members,
+        }
+    }
+
+    /// Overrides the name of the constant buffer, which defaults to the name of
+    /// the struct.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_owned();
+        self
+    }
+
+    /// Binds the constant buffer to the given `b` register.
+    pub fn register(mut self, register: u32) -> Self {
+        self.register = Some(register);
+        self
+    }
+
+    /// Sets the register space of the constant buffer. A register must also be
+    /// given with [`HlslConstantBuffer::register`].
+    pub fn space(mut self, space: u32) -> Self {
+        self.space = Some(space);
+        self
+    }
+
+    /// Sets how matrices are declared in the constant buffer and the structs
+    /// it uses.
+    pub fn matrix_layout(mut self, matrices: MatrixLayout) -> Self {
+        self.matrices = matrices;
+        self
+    }
+
+    /// Declares matrices `row_major`. See [`HlslConstantBuffer::matrix_layout`].
+    pub fn row_major(self) -> Self {
+        self.matrix_layout(MatrixLayout::RowMajor)
+    }
+
+    /// Generates HLSL code declaring this constant buffer.
+    ///
+    /// # Panics
+    /// Panics if a register space was given without a register.
+    pub fn hlsl_definition(&self) -> String {
+        let mut output = String::new();
+        write!(output, "cbuffer {}", self.name).unwrap();
+
+        match (self.register, self.space) {
+            (Some(register), Some(space)) => {
+                write!(output, " : register(b{}, space{})", register, space).unwrap()
+            }
+            (Some(register), None) => write!(output, " : register(b{})", register).unwrap(),
+            (None, Some(_)) => panic!("constant buffers need a register to be given a space"),
+            (None, None) => {}
+        }
+
+        output.push_str("\n{\n");
+
+        for (field, offset) in &self.members {
+            writeln!(
+                output,
+                "    {} : packoffset({});",
+                field.declaration(self.matrices),
+                packoffset(*offset)
+            )
+            .unwrap();
+        }
+
+        output.push_str("};");
+        output
+    }
+
+    /// Generates HLSL code defining every struct used by this constant
+    /// buffer's members, followed by the constant buffer itself.
+    pub fn hlsl_definition_with_dependencies(&self) -> String {
+        let mut output = hlsl_definitions(&self.structs(), self.matrices);
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+
+        output.push_str(&self.hlsl_definition());
+        output
+    }
+
+    /// The definitions of the structs used directly by this constant buffer's
+    /// members.
+    pub(crate) fn structs(&self) -> Vec<&'static HlslStructDefinition> {
+        self.members
+            .iter()
+            .filter_map(|(field, _)| field.hlsl_type.definition())
+            .collect()
+    }
+}
+
+/// Formats a byte offset as a `packoffset` location, like `c4.y`.
+fn packoffset(offset: usize) -> String {
+    const COMPONENTS: [char; 4] = ['x', 'y', 'z', 'w'];
+
+    assert!(
+        offset % 4 == 0,
+        "offset {} isn't on a component boundary",
+        offset
+    );
+
+    let register = offset / 16;
+    match (offset % 16) / 4 {
+        0 => format!("c{}", register),
+        component => format!("c{}.{}", register, COMPONENTS[component]),
+    }
+}
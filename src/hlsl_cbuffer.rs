@@ -0,0 +1,25 @@
+//! Defines traits and types for working with data adhering to HLSL's constant
+//! buffer (`cbuffer`) packing rules.
+//!
+//! HLSL packs constant buffers into 16-byte registers. Members are packed
+//! tightly, but may not straddle a register boundary. Structs, matrices and
+//! array elements always begin on a new register, while the last register of a
+//! struct or array is not padded out. This means that, unlike `std140`, a
+//! `float3` followed by a `float` shares a single register and a struct may end
+//! unpadded.
+
+mod primitives;
+mod sizer;
+mod traits;
+#[cfg(feature = "std")]
+mod writer;
+
+pub use crate::bool::Bool;
+
+pub use self::primitives::*;
+pub use self::sizer::*;
+pub use self::traits::*;
+#[cfg(feature = "std")]
+pub use self::writer::*;
+
+pub use crevice_derive::AsHlslCbuffer;
@@ -0,0 +1,266 @@
+use core::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::bool::Bool;
+use crate::glsl::Glsl;
+use crate::hlsl_cbuffer::{AsHlslCbuffer, HlslCbuffer};
+use crate::internal::array_alignment;
+use crate::layout::{self, ArrayElement, LayoutValue};
+use crate::reflect::{LayoutInfo, Reflect};
+
+unsafe impl HlslCbuffer for f32 {
+    const ALIGNMENT: usize = 4;
+}
+
+unsafe impl HlslCbuffer for f64 {
+    const ALIGNMENT: usize = 8;
+}
+
+unsafe impl HlslCbuffer for i32 {
+    const ALIGNMENT: usize = 4;
+}
+
+unsafe impl HlslCbuffer for u32 {
+    const ALIGNMENT: usize = 4;
+}
+
+unsafe impl HlslCbuffer for Bool {
+    const ALIGNMENT: usize = 4;
+}
+
+impl AsHlslCbuffer for bool {
+    type Output = Bool;
+
+    fn as_hlsl_cbuffer(&self) -> Self::Output {
+        (*self).into()
+    }
+
+    fn from_hlsl_cbuffer(val: Self::Output) -> Self {
+        val.into()
+    }
+}
+
+macro_rules! vectors {
+    (
+        $(
+            #[$doc:meta] align($align:literal) $glsl_name:ident $name:ident <$prim:ident> ($($field:ident),+)
+        )+
+    ) => {
+        $(
+            #[$doc]
+            #[allow(missing_docs)]
+            #[derive(Debug, Clone, Copy, PartialEq)]
+            #[repr(C)]
+            pub struct $name {
+                $(pub $field: $prim,)+
+            }
+
+            unsafe impl Zeroable for $name {}
+            unsafe impl Pod for $name {}
+
+            unsafe impl HlslCbuffer for $name {
+                const ALIGNMENT: usize = $align;
+            }
+
+            unsafe impl Glsl for $name {
+                const NAME: &'static str = stringify!($glsl_name);
+            }
+
+            unsafe impl Reflect for $name {
+                const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo::primitive(
+                    stringify!($glsl_name),
+                    core::mem::size_of::<$name>(),
+                    $align,
+                );
+            }
+        )+
+    };
+}
+
+vectors! {
+    #[doc = "Corresponds to an HLSL `float2` in a constant buffer."] align(4) vec2 Vec2<f32>(x, y)
+    #[doc = "Corresponds to an HLSL `float3` in a constant buffer."] align(4) vec3 Vec3<f32>(x, y, z)
+    #[doc = "Corresponds to an HLSL `float4` in a constant buffer."] align(4) vec4 Vec4<f32>(x, y, z, w)
+
+    #[doc = "Corresponds to an HLSL `int2` in a constant buffer."] align(4) ivec2 IVec2<i32>(x, y)
+    #[doc = "Corresponds to an HLSL `int3` in a constant buffer."] align(4) ivec3 IVec3<i32>(x, y, z)
+    #[doc = "Corresponds to an HLSL `int4` in a constant buffer."] align(4) ivec4 IVec4<i32>(x, y, z, w)
+
+    #[doc = "Corresponds to an HLSL `uint2` in a constant buffer."] align(4) uvec2 UVec2<u32>(x, y)
+    #[doc = "Corresponds to an HLSL `uint3` in a constant buffer."] align(4) uvec3 UVec3<u32>(x, y, z)
+    #[doc = "Corresponds to an HLSL `uint4` in a constant buffer."] align(4) uvec4 UVec4<u32>(x, y, z, w)
+
+    #[doc = "Corresponds to an HLSL `bool2` in a constant buffer."] align(4) bvec2 BVec2<Bool>(x, y)
+    #[doc = "Corresponds to an HLSL `bool3` in a constant buffer."] align(4) bvec3 BVec3<Bool>(x, y, z)
+    #[doc = "Corresponds to an HLSL `bool4` in a constant buffer."] align(4) bvec4 BVec4<Bool>(x, y, z, w)
+
+    #[doc = "Corresponds to an HLSL `double2` in a constant buffer."] align(8) dvec2 DVec2<f64>(x, y)
+    #[doc = "Corresponds to an HLSL `double3` in a constant buffer."] align(8) dvec3 DVec3<f64>(x, y, z)
+    #[doc = "Corresponds to an HLSL `double4` in a constant buffer."] align(8) dvec4 DVec4<f64>(x, y, z, w)
+}
+
+macro_rules! matrices {
+    (
+        $(
+            #[$doc:meta]
+            align($align:literal)
+            $glsl_name:ident $name:ident {
+                $column:ident: $column_ty:ty,
+                $($field:ident: $field_ty:ty,)+
+            }
+        )+
+    ) => {
+        $(
+            #[$doc]
+            #[allow(missing_docs)]
+            #[derive(Debug, Clone, Copy)]
+            #[repr(C)]
+            pub struct $name {
+                pub $column: $column_ty,
+                $(pub $field: $field_ty,)+
+            }
+
+            unsafe impl Zeroable for $name {}
+            unsafe impl Pod for $name {}
+
+            unsafe impl HlslCbuffer for $name {
+                const ALIGNMENT: usize = $align;
+            }
+
+            unsafe impl Glsl for $name {
+                const NAME: &'static str = stringify!($glsl_name);
+            }
+
+            unsafe impl Reflect for $name {
+                const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo::matrix(
+                    stringify!($glsl_name),
+                    core::mem::size_of::<$name>(),
+                    $align,
+                    <$column_ty as Reflect>::LAYOUT_INFO,
+                    core::mem::offset_of!($name, y),
+                );
+            }
+        )+
+    };
+}
+
+// Matrices are stored column-major, with each column starting on a new
+// register. The last column is not padded out to fill its register.
+matrices! {
+    #[doc = "Corresponds to an HLSL `float2x2` in a constant buffer."]
+    align(16)
+    mat2 Mat2 {
+        x: Vec2,
+        _pad_x: [f32; 2],
+        y: Vec2,
+    }
+
+    #[doc = "Corresponds to an HLSL `float3x3` in a constant buffer."]
+    align(16)
+    mat3 Mat3 {
+        x: Vec3,
+        _pad_x: f32,
+        y: Vec3,
+        _pad_y: f32,
+        z: Vec3,
+    }
+
+    #[doc = "Corresponds to an HLSL `float4x4` in a constant buffer."]
+    align(16)
+    mat4 Mat4 {
+        x: Vec4,
+        y: Vec4,
+        z: Vec4,
+        w: Vec4,
+    }
+
+    #[doc = "Corresponds to an HLSL `double2x2` in a constant buffer."]
+    align(16)
+    dmat2 DMat2 {
+        x: DVec2,
+        y: DVec2,
+    }
+
+    #[doc = "Corresponds to an HLSL `double3x3` in a constant buffer."]
+    align(16)
+    dmat3 DMat3 {
+        x: DVec3,
+        _pad_x: f64,
+        y: DVec3,
+        _pad_y: f64,
+        z: DVec3,
+    }
+
+    #[doc = "Corresponds to an HLSL `double4x4` in a constant buffer."]
+    align(16)
+    dmat4 DMat4 {
+        x: DVec4,
+        y: DVec4,
+        z: DVec4,
+        w: DVec4,
+    }
+}
+
+array_elements!(layout::HlslCbuffer {
+    f32,
+    f64,
+    i32,
+    u32,
+    Bool,
+    Vec2,
+    Vec3,
+    Vec4,
+    IVec2,
+    IVec3,
+    IVec4,
+    UVec2,
+    UVec3,
+    UVec4,
+    BVec2,
+    BVec3,
+    BVec4,
+    DVec2,
+    DVec3,
+    DVec4,
+    Mat2,
+    Mat3,
+    Mat4,
+    DMat2,
+    DMat3,
+    DMat4,
+});
+
+/// Corresponds to an HLSL array in a constant buffer.
+///
+/// Constant buffers start every array element on a new 16-byte register, so
+/// elements like `float` or `float3` are each followed by padding up to the
+/// next register. The last element isn't, so the array's
+/// [`SIZE`][HlslCbuffer::SIZE] is smaller than its Rust size and the value
+/// after it can share its last register. See [`layout::Array`].
+pub type Array<T, const N: usize> = layout::Array<layout::HlslCbuffer, T, N>;
+
+unsafe impl<T: ArrayElement<layout::HlslCbuffer>, const N: usize> HlslCbuffer for Array<T, N> {
+    const ALIGNMENT: usize =
+        array_alignment::<layout::HlslCbuffer>(<T as LayoutValue<layout::HlslCbuffer>>::ALIGNMENT);
+
+    const SIZE: usize = match N {
+        0 => 0,
+        _ => (N - 1) * size_of::<T::Padded>() + <T as LayoutValue<layout::HlslCbuffer>>::SIZE,
+    };
+}
+
+impl<T: AsHlslCbuffer, const N: usize> AsHlslCbuffer for [T; N]
+where
+    T::Output: ArrayElement<layout::HlslCbuffer>,
+{
+    type Output = Array<T::Output, N>;
+
+    fn as_hlsl_cbuffer(&self) -> Self::Output {
+        Array::new(self.each_ref().map(T::as_hlsl_cbuffer))
+    }
+
+    fn from_hlsl_cbuffer(value: Self::Output) -> Self {
+        value.to_array().map(T::from_hlsl_cbuffer)
+    }
+}
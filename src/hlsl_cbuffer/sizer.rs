@@ -0,0 +1,45 @@
+use crate::layout;
+
+/**
+Type that computes the buffer size needed by a series of HLSL constant buffer
+types laid out.
+
+This type works well well when paired with `Writer`, precomputing a buffer's
+size to alleviate the need to dynamically re-allocate buffers.
+
+`Sizer` is an alias of the generic [`layout::Sizer`] using the
+[`layout::HlslCbuffer`] rules.
+
+## Example
+
+```hlsl
+struct Frob {
+    float3 size;
+    float frobiness;
+};
+
+cbuffer Frobs {
+    uint count;
+    Frob frob;
+};
+```
+
+```
+use crevice::hlsl_cbuffer::{self, AsHlslCbuffer};
+
+#[derive(AsHlslCbuffer)]
+struct Frob {
+    size: mint::Vector3<f32>,
+    frobiness: f32,
+}
+
+let mut sizer = hlsl_cbuffer::Sizer::new();
+sizer.add::<u32>();
+sizer.add::<Frob>();
+
+# fn create_buffer_with_size(size: usize) {}
+let buffer = create_buffer_with_size(sizer.len());
+# assert_eq!(sizer.len(), 32);
+```
+*/
+pub type Sizer = layout::Sizer<layout::HlslCbuffer>;
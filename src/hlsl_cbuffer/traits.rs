@@ -0,0 +1,216 @@
+use core::mem::size_of;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use bytemuck::{bytes_of, Pod, Zeroable};
+
+#[cfg(feature = "std")]
+use crate::hlsl_cbuffer::Writer;
+#[cfg(feature = "std")]
+use crate::layout::WriteLayout;
+use crate::layout::{self, AsLayout, LayoutValue};
+
+/// Trait implemented for all HLSL constant buffer primitives. Generally should
+/// not be implemented outside this crate.
+///
+/// # Safety
+/// Implementors must not contain any uninitialized padding bytes, and their
+/// `SIZE` and `ALIGNMENT` must match the constant buffer packing of the type
+/// they represent. Any bytes past `SIZE` must be padding.
+pub unsafe trait HlslCbuffer: Copy + Zeroable + Pod {
+    /// The required alignment of the type. Must be a power of two.
+    ///
+    /// Independently of this value, members of a constant buffer are moved to
+    /// the next 16-byte register if they would otherwise straddle a register
+    /// boundary.
+    ///
+    /// This is distinct from the value returned by `std::mem::align_of` because
+    /// `AsHlslCbuffer` structs do not use Rust's alignment. This enables them to
+    /// control and zero their padding bytes, making converting them to and from
+    /// slices safe.
+    const ALIGNMENT: usize;
+
+    /// The number of bytes the type occupies in a constant buffer. Arrays end
+    /// with their last element rather than filling its register, so their
+    /// `SIZE` is smaller than their Rust size.
+    const SIZE: usize = size_of::<Self>();
+
+    /// Casts the type to a byte array of length `SIZE`. Implementors should
+    /// not override this method.
+    ///
+    /// # Safety
+    /// This is always safe due to the requirements of [`bytemuck::Pod`] being a
+    /// prerequisite for this trait.
+    fn as_bytes(&self) -> &[u8] {
+        &bytes_of(self)[..Self::SIZE]
+    }
+}
+
+/**
+Trait implemented for all types that can be turned into HLSL constant buffer
+values.
+
+This trait can often be `#[derive]`'d instead of manually implementing it. Any
+struct which contains only fields that also implement `AsHlslCbuffer` can derive
+`AsHlslCbuffer`.
+
+Types from the mint crate implement `AsHlslCbuffer`, making them convenient for
+use in constant buffer types. Most Rust math crates, like cgmath, nalgebra, and
+ultraviolet support mint.
+
+## Example
+
+```hlsl
+cbuffer Light {
+    float3 position;
+    float intensity;
+};
+```
+
+```
+use crevice::hlsl_cbuffer::AsHlslCbuffer;
+
+#[derive(AsHlslCbuffer)]
+struct Light {
+    position: mint::Vector3<f32>,
+    intensity: f32,
+}
+
+let light = Light {
+    position: [1.0, 2.0, 3.0].into(),
+    intensity: 0.5,
+};
+
+# fn write_to_gpu_buffer(bytes: &[u8]) {}
+let light_cbuffer = light.as_hlsl_cbuffer();
+write_to_gpu_buffer(light_cbuffer.as_bytes());
+
+// The position and intensity share a single register.
+assert_eq!(light_cbuffer.as_bytes().len(), 16);
+```
+*/
+pub trait AsHlslCbuffer {
+    /// The constant buffer version of this value.
+    type Output: HlslCbuffer;
+
+    /// Convert this value into the constant buffer version of itself.
+    fn as_hlsl_cbuffer(&self) -> Self::Output;
+
+    /// Returns the size of the constant buffer version of this type. Useful for
+    /// pre-sizing buffers.
+    fn hlsl_cbuffer_size_static() -> usize {
+        <Self::Output as HlslCbuffer>::SIZE
+    }
+
+    /// Converts from the constant buffer version of self to self.
+    fn from_hlsl_cbuffer(value: Self::Output) -> Self;
+}
+
+impl<T> AsHlslCbuffer for T
+where
+    T: HlslCbuffer,
+{
+    type Output = Self;
+
+    fn as_hlsl_cbuffer(&self) -> Self {
+        *self
+    }
+
+    fn from_hlsl_cbuffer(value: Self) -> Self {
+        value
+    }
+}
+
+/// Trait implemented for all types that can be written into a buffer using HLSL
+/// constant buffer packing. This type is more general than [`AsHlslCbuffer`]:
+/// all `AsHlslCbuffer` types implement `WriteHlslCbuffer`, but not the other way
+/// around.
+///
+/// While `AsHlslCbuffer` requires implementers to return a type that implements
+/// the `HlslCbuffer` trait, `WriteHlslCbuffer` directly writes bytes using a
+/// [`Writer`]. This makes `WriteHlslCbuffer` usable for writing slices or other
+/// DSTs that could not implement `AsHlslCbuffer` without allocating new memory
+/// on the heap.
+///
+/// Every type implementing `WriteHlslCbuffer` also implements
+/// [`WriteLayout<layout::HlslCbuffer>`][WriteLayout], so it can be written with
+/// [`Writer::write`].
+#[cfg(feature = "std")]
+pub trait WriteHlslCbuffer {
+    /// Writes this value into the given [`Writer`] using constant buffer
+    /// packing rules.
+    ///
+    /// Should return the offset of the first byte of this type, as returned by
+    /// the first call to [`Writer::write`].
+    fn write_hlsl_cbuffer<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize>;
+
+    /// The space required to write this value using constant buffer packing
+    /// rules. This does not include alignment padding that may be needed before
+    /// or after this type when written as part of a larger buffer.
+    fn hlsl_cbuffer_size(&self) -> usize {
+        let mut writer = Writer::new(io::sink());
+        self.write_hlsl_cbuffer(&mut writer).unwrap();
+        writer.len()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteHlslCbuffer for T
+where
+    T: AsHlslCbuffer,
+{
+    fn write_hlsl_cbuffer<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        writer.write_value(&self.as_hlsl_cbuffer())
+    }
+
+    fn hlsl_cbuffer_size(&self) -> usize {
+        <<Self as AsHlslCbuffer>::Output as HlslCbuffer>::SIZE
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteHlslCbuffer for [T]
+where
+    T: WriteHlslCbuffer,
+{
+    fn write_hlsl_cbuffer<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        self.write_layout(writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteLayout<layout::HlslCbuffer> for T
+where
+    T: WriteHlslCbuffer,
+{
+    fn write_layout<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        self.write_hlsl_cbuffer(writer)
+    }
+
+    fn layout_size(&self) -> usize {
+        self.hlsl_cbuffer_size()
+    }
+}
+
+unsafe impl<T> LayoutValue<layout::HlslCbuffer> for T
+where
+    T: HlslCbuffer,
+{
+    const ALIGNMENT: usize = <T as HlslCbuffer>::ALIGNMENT;
+    const SIZE: usize = <T as HlslCbuffer>::SIZE;
+}
+
+impl<T> AsLayout<layout::HlslCbuffer> for T
+where
+    T: AsHlslCbuffer,
+{
+    type Output = <T as AsHlslCbuffer>::Output;
+
+    fn as_layout(&self) -> Self::Output {
+        self.as_hlsl_cbuffer()
+    }
+
+    fn from_layout(value: Self::Output) -> Self {
+        <T as AsHlslCbuffer>::from_hlsl_cbuffer(value)
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::{self, Write};
+
+use crate::hlsl_cbuffer::HlslCbuffer;
+use crate::layout;
+
+/**
+Type that enables writing correctly packed HLSL constant buffer values to a
+buffer.
+
+`Writer` is useful when many values need to be laid out in a row that cannot be
+represented by a struct alone, like dynamically sized arrays or dynamically
+laid-out values.
+
+`Writer` is an alias of the generic [`layout::Writer`] using the
+[`layout::HlslCbuffer`] rules.
+
+## Example
+In this example, we'll write a count followed by a list of lights to a buffer.
+`hlsl_cbuffer::Writer` keeps values from straddling 16-byte registers and starts
+every array element on a new register, which can be tricky and error-prone
+otherwise.
+
+```hlsl
+struct PointLight {
+    float3 position;
+    float brightness;
+    float3 color;
+};
+
+cbuffer PointLights {
+    uint count;
+    PointLight lights[2];
+};
+```
+
+```
+use crevice::hlsl_cbuffer::{self, AsHlslCbuffer};
+
+#[derive(AsHlslCbuffer)]
+struct PointLight {
+    position: mint::Point3<f32>,
+    brightness: f32,
+    color: mint::Vector3<f32>,
+}
+
+let lights = vec![
+    PointLight {
+        position: [0.0, 1.0, 0.0].into(),
+        brightness: 0.6,
+        color: [1.0, 0.0, 0.0].into(),
+    },
+    PointLight {
+        position: [0.0, 4.0, 3.0].into(),
+        brightness: 1.0,
+        color: [1.0, 1.0, 1.0].into(),
+    },
+];
+
+# fn map_gpu_buffer_for_write() -> &'static mut [u8] {
+#     Box::leak(vec![0; 1024].into_boxed_slice())
+# }
+let target_buffer = map_gpu_buffer_for_write();
+let mut writer = hlsl_cbuffer::Writer::new(target_buffer);
+
+let light_count = lights.len() as u32;
+writer.write(&light_count)?;
+
+// Each light starts on a new register, and is 28 bytes long. There will be 12
+// bytes of padding after the count and 4 bytes between the two lights.
+let offset = writer.write(lights.as_slice())?;
+assert_eq!(offset, 16);
+assert_eq!(writer.len(), 76);
+
+# fn unmap_gpu_buffer() {}
+unmap_gpu_buffer();
+
+# Ok::<(), std::io::Error>(())
+```
+*/
+pub type Writer<W> = layout::Writer<layout::HlslCbuffer, W>;
+
+impl<W: Write> Writer<W> {
+    /// Write an `HlslCbuffer` type to the underlying buffer.
+    pub fn write_hlsl_cbuffer<T>(&mut self, value: &T) -> io::Result<usize>
+    where
+        T: HlslCbuffer,
+    {
+        self.write_value(value)
+    }
+}
@@ -1,7 +1,15 @@
 //! This module is internal to crevice but used by its derive macro. No
 //! guarantees are made about its contents.
 
+use core::mem::size_of;
+
+use bytemuck::{bytes_of, Pod, Zeroable};
+
+use crate::glsl::{GlslArray, GlslField};
+use crate::layout::Layout;
+
 pub use bytemuck;
+pub use mint;
 
 /// Gives the number of bytes needed to make `offset` be aligned to `alignment`.
 pub const fn align_offset(offset: usize, alignment: usize) -> usize {
@@ -38,3 +46,526 @@ pub const fn max_arr<const N: usize>(input: [usize; N]) -> usize {
 
     max
 }
+
+/// Gives the number of bytes needed to place a value with the given `alignment`
+/// and `size` at `offset` without straddling a 16-byte register boundary, as
+/// required by HLSL constant buffer packing rules.
+///
+/// Values larger than a register always start on a new register.
+pub const fn align_offset_packed(offset: usize, alignment: usize, size: usize) -> usize {
+    let padding = align_offset(offset, alignment);
+    let start = offset + padding;
+
+    if size > 16 || start % 16 + size > 16 {
+        padding + align_offset(start, 16)
+    } else {
+        padding
+    }
+}
+
+/// Gives the alignment of an array laid out according to `L`, whose elements
+/// have the given `alignment`.
+pub const fn array_alignment<L: Layout>(alignment: usize) -> usize {
+    max(alignment, L::ARRAY_ELEMENT_ALIGNMENT)
+}
+
+/// Gives the number of bytes of padding that follow each element of an array
+/// laid out according to `L`, for elements of the given `size` and `alignment`.
+pub const fn array_padding<L: Layout>(size: usize, alignment: usize) -> usize {
+    align_offset(size, array_alignment::<L>(alignment))
+}
+
+/// A value followed by `P` bytes of padding, used to store the elements of
+/// arrays whose stride is larger than their element type.
+#[repr(C, packed)]
+pub struct Padded<T, const P: usize> {
+    value: T,
+    padding: [u8; P],
+}
+
+impl<T: Copy, const P: usize> Clone for Padded<T, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Copy, const P: usize> Copy for Padded<T, P> {}
+
+// SAFETY: `Padded` is packed, so it has no padding bytes of its own.
+unsafe impl<T: Zeroable, const P: usize> Zeroable for Padded<T, P> {}
+unsafe impl<T: Pod, const P: usize> Pod for Padded<T, P> {}
+
+/// Stores `value` at the start of a zeroed `P`.
+pub(crate) fn pad<T: Pod, P: Pod>(value: T) -> P {
+    let mut padded = P::zeroed();
+    bytemuck::bytes_of_mut(&mut padded)[..size_of::<T>()].copy_from_slice(bytes_of(&value));
+    padded
+}
+
+/// Reads the value stored at the start of `padded` by [`pad`].
+pub(crate) fn unpad<T: Pod, P: Pod>(padded: &P) -> T {
+    bytemuck::pod_read_unaligned(&bytes_of(padded)[..size_of::<T>()])
+}
+
+/// The largest number of fields that [`field_order_into`] tries every order of.
+/// Larger structs are ordered greedily, which may miss the smallest layout.
+pub const EXHAUSTIVE_FIELD_LIMIT: usize = 7;
+
+/// Orders fields with the given sizes and alignments to reduce the padding
+/// needed between them, writing the index of each field in its new position to
+/// `order`. `used` and `candidate` are scratch space with one entry per field.
+///
+/// With `packed`, offsets follow HLSL constant buffer packing rules and the end
+/// of the struct is not padded, as in [`reordered_layout`].
+///
+/// Fields are first placed greedily, always picking the field that needs the
+/// least padding at the current offset and preferring larger alignments and
+/// sizes. Structs with at most [`EXHAUSTIVE_FIELD_LIMIT`] fields then search
+/// every order for a smaller one. Declaration order is kept unless another
+/// order is smaller.
+pub const fn field_order_into(
+    sizes: &[usize],
+    alignments: &[usize],
+    struct_alignment: usize,
+    packed: bool,
+    used: &mut [bool],
+    order: &mut [usize],
+    candidate: &mut [usize],
+) {
+    let mut offset = 0;
+    let mut position = 0;
+
+    while position < sizes.len() {
+        let mut best = usize::MAX;
+        let mut best_padding = 0;
+        let mut i = 0;
+
+        while i < sizes.len() {
+            if !used[i] {
+                let padding = field_padding(offset, sizes[i], alignments[i], packed);
+
+                let better = best == usize::MAX
+                    || padding < best_padding
+                    || (padding == best_padding && alignments[i] > alignments[best])
+                    || (padding == best_padding
+                        && alignments[i] == alignments[best]
+                        && sizes[i] > sizes[best]);
+
+                if better {
+                    best = i;
+                    best_padding = padding;
+                }
+            }
+
+            i += 1;
+        }
+
+        used[best] = true;
+        order[position] = best;
+        offset += best_padding + sizes[best];
+        position += 1;
+    }
+
+    let mut declared = 0;
+    let mut declared_size = 0;
+    while declared < sizes.len() {
+        declared_size +=
+            field_padding(declared_size, sizes[declared], alignments[declared], packed)
+                + sizes[declared];
+        declared += 1;
+    }
+
+    let declared_size = struct_end(declared_size, struct_alignment, packed);
+    let mut best_size = struct_end(offset, struct_alignment, packed);
+
+    if best_size >= declared_size {
+        best_size = declared_size;
+
+        let mut i = 0;
+        while i < order.len() {
+            order[i] = i;
+            i += 1;
+        }
+    }
+
+    if sizes.len() <= EXHAUSTIVE_FIELD_LIMIT {
+        let mut remaining = 0;
+        let mut i = 0;
+        while i < sizes.len() {
+            used[i] = false;
+            remaining += sizes[i];
+            i += 1;
+        }
+
+        let mut search = OrderSearch {
+            sizes,
+            alignments,
+            struct_alignment,
+            packed,
+            used,
+            candidate,
+            order,
+            best_size,
+        };
+        search.search(0, 0, remaining);
+    }
+}
+
+/// The state of [`field_order_into`]'s search through every order.
+struct OrderSearch<'a> {
+    sizes: &'a [usize],
+    alignments: &'a [usize],
+    struct_alignment: usize,
+    packed: bool,
+    used: &'a mut [bool],
+    candidate: &'a mut [usize],
+    order: &'a mut [usize],
+    best_size: usize,
+}
+
+impl OrderSearch<'_> {
+    /// Tries every way of placing the unused fields from `position` on,
+    /// starting at `offset`, and copies any order smaller than the best so far
+    /// into `order`.
+    ///
+    /// `remaining` is the total size of the unused fields, which bounds how
+    /// small the struct can get.
+    const fn search(&mut self, position: usize, offset: usize, remaining: usize) {
+        if struct_end(offset + remaining, self.struct_alignment, self.packed) >= self.best_size {
+            return;
+        }
+
+        if position == self.sizes.len() {
+            let mut i = 0;
+            while i < self.order.len() {
+                self.order[i] = self.candidate[i];
+                i += 1;
+            }
+
+            self.best_size = struct_end(offset, self.struct_alignment, self.packed);
+            return;
+        }
+
+        let mut i = 0;
+        while i < self.sizes.len() {
+            if !self.used[i] && !self.has_unused_twin(i) {
+                let size = self.sizes[i];
+                let start = offset + field_padding(offset, size, self.alignments[i], self.packed);
+
+                self.used[i] = true;
+                self.candidate[position] = i;
+                self.search(position + 1, start + size, remaining - size);
+                self.used[i] = false;
+            }
+
+            i += 1;
+        }
+    }
+
+    /// Whether an unused field before `field` has the same size and alignment,
+    /// in which case placing `field` next can't lead to a different layout.
+    const fn has_unused_twin(&self, field: usize) -> bool {
+        let mut i = 0;
+        while i < field {
+            if !self.used[i]
+                && self.sizes[i] == self.sizes[field]
+                && self.alignments[i] == self.alignments[field]
+            {
+                return true;
+            }
+
+            i += 1;
+        }
+
+        false
+    }
+}
+
+/// The padding needed to place a field at `offset`.
+const fn field_padding(offset: usize, size: usize, alignment: usize, packed: bool) -> usize {
+    if packed {
+        align_offset_packed(offset, alignment, size)
+    } else {
+        align_offset(offset, alignment)
+    }
+}
+
+/// The size of a struct whose last field ends at `offset`.
+const fn struct_end(offset: usize, struct_alignment: usize, packed: bool) -> usize {
+    if packed {
+        offset
+    } else {
+        offset + align_offset(offset, struct_alignment)
+    }
+}
+
+/// Orders fields with the given sizes and alignments to reduce padding. See
+/// [`field_order_into`].
+pub const fn field_order<const N: usize>(
+    sizes: [usize; N],
+    alignments: [usize; N],
+    struct_alignment: usize,
+    packed: bool,
+) -> [usize; N] {
+    let mut used = [false; N];
+    let mut order = [0; N];
+    let mut candidate = [0; N];
+    field_order_into(
+        &sizes,
+        &alignments,
+        struct_alignment,
+        packed,
+        &mut used,
+        &mut order,
+        &mut candidate,
+    );
+    order
+}
+
+/// The offset of each field of a struct whose fields have been reordered, as
+/// well as the total size of the struct.
+pub struct FieldLayout<const N: usize> {
+    pub offsets: [usize; N],
+    pub size: usize,
+}
+
+/// Lays out fields in the given order, returning the offset of each field in
+/// declaration order.
+///
+/// With `packed`, fields follow HLSL constant buffer packing rules and the end
+/// of the struct is not padded.
+pub const fn reordered_layout<const N: usize>(
+    order: [usize; N],
+    sizes: [usize; N],
+    alignments: [usize; N],
+    struct_alignment: usize,
+    packed: bool,
+) -> FieldLayout<N> {
+    let mut offsets = [0; N];
+    let mut offset = 0;
+    let mut position = 0;
+
+    while position < N {
+        let field = order[position];
+
+        offset += field_padding(offset, sizes[field], alignments[field], packed);
+
+        offsets[field] = offset;
+        offset += sizes[field];
+        position += 1;
+    }
+
+    FieldLayout {
+        offsets,
+        size: struct_end(offset, struct_alignment, packed),
+    }
+}
+
+/// Reorders `values` so that the value at each position is the one at
+/// `order[position]`.
+pub const fn permute<T: Copy, const N: usize>(values: [T; N], order: [usize; N]) -> [T; N] {
+    let mut output = values;
+    let mut i = 0;
+
+    while i < N {
+        output[i] = values[order[i]];
+        i += 1;
+    }
+
+    output
+}
+
+/// Gives the length of the GLSL definition written by `glsl_struct_definition`
+/// for a struct with the given name, documentation and fields.
+pub const fn glsl_struct_len(name: &str, doc: &str, fields: &[GlslField]) -> usize {
+    let mut len = comment_len(doc, "") + "struct ".len() + name.len() + " {\n".len() + "};".len();
+    let mut i = 0;
+
+    while i < fields.len() {
+        let field = &fields[i];
+        len += comment_len(field.doc, "\t");
+        len += "\t".len() + field.ty.len() + " ".len() + field.name.len() + ";\n".len();
+        len += array_suffix_len(field.array);
+        i += 1;
+    }
+
+    len
+}
+
+/// Writes the same GLSL definition as `GlslStructDefinition::glsl_definition`
+/// at compile time. `LEN` must be given by `glsl_struct_len`.
+pub const fn glsl_struct_definition<const LEN: usize>(
+    name: &str,
+    doc: &str,
+    fields: &[GlslField],
+) -> [u8; LEN] {
+    let mut output = [0; LEN];
+    let mut offset = 0;
+
+    offset = write_comment(&mut output, offset, doc, "");
+    offset = write_str(&mut output, offset, "struct ");
+    offset = write_str(&mut output, offset, name);
+    offset = write_str(&mut output, offset, " {\n");
+
+    let mut i = 0;
+    while i < fields.len() {
+        let field = &fields[i];
+        offset = write_comment(&mut output, offset, field.doc, "\t");
+        offset = write_str(&mut output, offset, "\t");
+        offset = write_str(&mut output, offset, field.ty);
+        offset = write_str(&mut output, offset, " ");
+        offset = write_str(&mut output, offset, field.name);
+        offset = write_array_suffix(&mut output, offset, field.array);
+        offset = write_str(&mut output, offset, ";\n");
+        i += 1;
+    }
+
+    offset = write_str(&mut output, offset, "};");
+    assert!(offset == LEN, "GLSL definition has the wrong length");
+
+    output
+}
+
+/// Gives the length of the array dimensions written by `write_array_suffix`.
+const fn array_suffix_len(mut array: Option<&GlslArray>) -> usize {
+    let mut len = 0;
+
+    while let Some(dimension) = array {
+        len += "[]".len() + digit_count(dimension.len);
+        array = dimension.element;
+    }
+
+    len
+}
+
+/// Writes array dimensions like `[4][2]`, matching `GlslArray::write_suffix`,
+/// giving the offset just after them.
+const fn write_array_suffix<const LEN: usize>(
+    output: &mut [u8; LEN],
+    mut offset: usize,
+    mut array: Option<&GlslArray>,
+) -> usize {
+    while let Some(dimension) = array {
+        offset = write_str(output, offset, "[");
+
+        let digits = digit_count(dimension.len);
+        let mut value = dimension.len;
+        let mut i = digits;
+        while i > 0 {
+            i -= 1;
+            output[offset + i] = b'0' + (value % 10) as u8;
+            value /= 10;
+        }
+        offset += digits;
+
+        offset = write_str(output, offset, "]");
+        array = dimension.element;
+    }
+
+    offset
+}
+
+/// Gives the number of decimal digits in `value`.
+const fn digit_count(mut value: usize) -> usize {
+    let mut count = 1;
+
+    while value >= 10 {
+        value /= 10;
+        count += 1;
+    }
+
+    count
+}
+
+/// Gives the length of `doc` written as `//` comments by `write_comment`.
+const fn comment_len(doc: &str, indent: &str) -> usize {
+    let bytes = doc.as_bytes();
+    if bytes.is_empty() {
+        return 0;
+    }
+
+    // Every line gets the indent, `//` and a newline. Non-empty lines also get
+    // a space before their text.
+    let mut len = bytes.len();
+    let mut line_start = 0;
+    let mut i = 0;
+
+    while i <= bytes.len() {
+        if i == bytes.len() || bytes[i] == b'\n' {
+            len += indent.len() + "//".len();
+            if i > line_start {
+                len += " ".len();
+            }
+            line_start = i + 1;
+        }
+        i += 1;
+    }
+
+    // Each newline in `doc` is already counted as part of its length.
+    len + "\n".len()
+}
+
+/// Writes `doc` as `//` comments, matching `glsl::write_doc_comment`, giving
+/// the offset just after them.
+const fn write_comment<const LEN: usize>(
+    output: &mut [u8; LEN],
+    mut offset: usize,
+    doc: &str,
+    indent: &str,
+) -> usize {
+    let bytes = doc.as_bytes();
+    if bytes.is_empty() {
+        return offset;
+    }
+
+    let mut line_start = 0;
+    let mut i = 0;
+
+    while i <= bytes.len() {
+        if i == bytes.len() || bytes[i] == b'\n' {
+            offset = write_str(output, offset, indent);
+            offset = write_str(output, offset, "//");
+            if i > line_start {
+                offset = write_str(output, offset, " ");
+                let (_, rest) = bytes.split_at(line_start);
+                let (line, _) = rest.split_at(i - line_start);
+                offset = write_bytes(output, offset, line);
+            }
+            offset = write_str(output, offset, "\n");
+            line_start = i + 1;
+        }
+        i += 1;
+    }
+
+    offset
+}
+
+/// Copies `value` into `output` at `offset`, giving the offset just after it.
+const fn write_str<const LEN: usize>(output: &mut [u8; LEN], offset: usize, value: &str) -> usize {
+    write_bytes(output, offset, value.as_bytes())
+}
+
+/// Copies `bytes` into `output` at `offset`, giving the offset just after them.
+const fn write_bytes<const LEN: usize>(
+    output: &mut [u8; LEN],
+    offset: usize,
+    bytes: &[u8],
+) -> usize {
+    let mut i = 0;
+
+    while i < bytes.len() {
+        output[offset + i] = bytes[i];
+        i += 1;
+    }
+
+    offset + bytes.len()
+}
+
+/// Converts bytes built by a const fn, like `glsl_struct_definition`, into a
+/// string.
+pub const fn str_from_utf8(bytes: &[u8]) -> &str {
+    match core::str::from_utf8(bytes) {
+        Ok(value) => value,
+        Err(_) => panic!("generated code is not valid UTF-8"),
+    }
+}
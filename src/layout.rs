@@ -0,0 +1,482 @@
+/*!
+Defines traits and types that are generic over the layout rules in use, like
+`std140` or `std430`.
+
+Each set of layout rules supported by Crevice has a marker type in this module,
+like [`Std140`] or [`Std430`], which implements [`Layout`]. Every type
+implementing a layout-specific trait like [`AsStd140`][crate::std140::AsStd140]
+also implements [`AsLayout`] for the corresponding marker, so library code like
+buffer builders or caches can take the layout as a type parameter and be written
+only once.
+
+The layout-specific `Writer` and `Sizer` types, like
+[`std140::Writer`][crate::std140::Writer], are aliases of the generic [`Writer`]
+and [`Sizer`] in this module.
+
+## Example
+*/
+#![cfg_attr(
+    feature = "std",
+    doc = r##"
+```rust
+use crevice::layout::{Layout, Std140, Std430, WriteLayout, Writer};
+use crevice::std140::AsStd140;
+use crevice::std430::AsStd430;
+
+#[derive(AsStd140, AsStd430)]
+struct Light {
+    intensity: f32,
+}
+
+fn write_lights<L, T>(lights: &[T]) -> Vec<u8>
+where
+    L: Layout,
+    T: WriteLayout<L>,
+    u32: WriteLayout<L>,
+{
+    let mut output = Vec::new();
+    let mut writer = Writer::<L, _>::new(&mut output);
+
+    let count = lights.len() as u32;
+    writer.write(&count).unwrap();
+    writer.write(lights).unwrap();
+
+    output
+}
+
+let lights = [Light { intensity: 0.5 }, Light { intensity: 1.0 }];
+
+// std140 pads structs out to 16 bytes, while std430 does not.
+assert_eq!(write_lights::<Std140, _>(&lights).len(), 48);
+assert_eq!(write_lights::<Std430, _>(&lights).len(), 12);
+```
+"##
+)]
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::size_of;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use bytemuck::{bytes_of, Pod, Zeroable};
+
+use crate::internal::{align_offset, align_offset_packed, pad, unpad};
+use crate::reflect::{ArrayInfo, LayoutInfo, Reflect};
+
+/// Trait implemented by the marker types describing each set of layout rules.
+pub trait Layout: 'static {
+    /// The name of these layout rules, like `std140`.
+    const NAME: &'static str;
+
+    /// The alignment that every element of an array starts at, in addition to
+    /// the alignment of the element type itself.
+    const ARRAY_ELEMENT_ALIGNMENT: usize = 0;
+
+    /// Gives the number of bytes of padding needed to place a value with the
+    /// given `alignment` and `size` at `offset`.
+    fn padding(offset: usize, alignment: usize, size: usize) -> usize {
+        let _ = size;
+        align_offset(offset, alignment)
+    }
+}
+
+/// Marker type for GLSL's `std140` layout rules. See [`crate::std140`].
+pub enum Std140 {}
+
+impl Layout for Std140 {
+    const NAME: &'static str = "std140";
+
+    const ARRAY_ELEMENT_ALIGNMENT: usize = 16;
+}
+
+/// Marker type for GLSL's `std430` layout rules. See [`crate::std430`].
+pub enum Std430 {}
+
+impl Layout for Std430 {
+    const NAME: &'static str = "std430";
+}
+
+/// Marker type for HLSL's constant buffer packing rules. See
+/// [`crate::hlsl_cbuffer`].
+pub enum HlslCbuffer {}
+
+impl Layout for HlslCbuffer {
+    const NAME: &'static str = "hlsl_cbuffer";
+
+    const ARRAY_ELEMENT_ALIGNMENT: usize = 16;
+
+    fn padding(offset: usize, alignment: usize, size: usize) -> usize {
+        align_offset_packed(offset, alignment, size)
+    }
+}
+
+/// Marker type for Metal Shading Language buffer layout. See [`crate::msl`].
+pub enum Msl {}
+
+impl Layout for Msl {
+    const NAME: &'static str = "msl";
+}
+
+/// Marker type for OpenCL C struct layout. See [`crate::opencl`].
+pub enum OpenCl {}
+
+impl Layout for OpenCl {
+    const NAME: &'static str = "opencl";
+}
+
+/// Trait implemented for all primitives of the layout `L`, like
+/// [`Std140`][crate::std140::Std140] for [`Std140`].
+///
+/// This trait is implemented automatically for every type implementing the
+/// layout-specific trait and should not be implemented directly.
+///
+/// # Safety
+/// Implementors must not contain any uninitialized padding bytes, and their
+/// size and `ALIGNMENT` must match the layout `L`.
+pub unsafe trait LayoutValue<L: Layout>: Copy + Zeroable + Pod {
+    /// The required alignment of the type under the layout `L`.
+    const ALIGNMENT: usize;
+
+    /// The number of bytes the type occupies under the layout `L`. This is
+    /// smaller than the type's Rust size when the value after it can be
+    /// placed in its trailing padding, like after an HLSL constant buffer
+    /// array.
+    const SIZE: usize = size_of::<Self>();
+
+    /// Casts the type to a byte array of length `SIZE`.
+    fn as_bytes(&self) -> &[u8] {
+        &bytes_of(self)[..Self::SIZE]
+    }
+}
+
+/// Trait implemented for values that can be elements of an array laid out
+/// according to `L`.
+///
+/// Layouts like [`Std140`] start every array element at a multiple of
+/// [`Layout::ARRAY_ELEMENT_ALIGNMENT`], so small elements like `float` are
+/// followed by padding. `Padded` stores an element together with that padding.
+///
+/// # Safety
+/// `Padded` must hold a value of this type at its start, followed only by
+/// padding bytes, and its size must be the array stride of this type under `L`.
+pub unsafe trait ArrayElement<L: Layout>: LayoutValue<L> {
+    /// This type followed by the padding needed before the next array element.
+    type Padded: Pod;
+}
+
+/// Corresponds to an array laid out according to `L`.
+///
+/// Every element starts at a multiple of its own alignment and of
+/// [`Layout::ARRAY_ELEMENT_ALIGNMENT`], so elements whose size isn't a multiple
+/// of that, like `float` in `std140` or `vec3` in `std430`, are each followed
+/// by padding up to the next element. Each layout module has an alias for its
+/// own arrays, like [`std140::Array`][crate::std140::Array].
+///
+/// Use [`Array::new`] and [`Array::to_array`] to convert from and to Rust
+/// arrays.
+#[repr(transparent)]
+pub struct Array<L: Layout, T: ArrayElement<L>, const N: usize>([T::Padded; N]);
+
+impl<L: Layout, T: ArrayElement<L>, const N: usize> Array<L, T, N> {
+    /// Lays out the given values as an array.
+    pub fn new(values: [T; N]) -> Self {
+        Self(values.map(pad))
+    }
+
+    /// Gives the values stored in this array.
+    pub fn to_array(&self) -> [T; N] {
+        self.0.each_ref().map(unpad)
+    }
+}
+
+impl<L: Layout, T: ArrayElement<L>, const N: usize> Clone for Array<L, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L: Layout, T: ArrayElement<L>, const N: usize> Copy for Array<L, T, N> {}
+
+impl<L: Layout, T: ArrayElement<L> + fmt::Debug, const N: usize> fmt::Debug for Array<L, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Array").field(&self.to_array()).finish()
+    }
+}
+
+unsafe impl<L: Layout, T: ArrayElement<L>, const N: usize> Zeroable for Array<L, T, N> {}
+unsafe impl<L: Layout, T: ArrayElement<L>, const N: usize> Pod for Array<L, T, N> {}
+
+// Every element, including the last, is stored with its padding, and the
+// array's alignment divides the stride, so arrays of arrays need no more
+// padding.
+unsafe impl<L: Layout, T: ArrayElement<L>, const N: usize> ArrayElement<L> for Array<L, T, N>
+where
+    Self: LayoutValue<L>,
+{
+    type Padded = Self;
+}
+
+unsafe impl<L: Layout, T: ArrayElement<L> + Reflect, const N: usize> Reflect for Array<L, T, N>
+where
+    Self: LayoutValue<L>,
+{
+    const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo {
+        name: T::LAYOUT_INFO.name,
+        size: <Self as LayoutValue<L>>::SIZE,
+        alignment: <Self as LayoutValue<L>>::ALIGNMENT,
+        fields: &[],
+        array: Some(ArrayInfo {
+            len: N,
+            stride: size_of::<T::Padded>(),
+            element: T::LAYOUT_INFO,
+        }),
+        columns: None,
+    };
+}
+
+/// Trait implemented for all types that can be turned into values laid out
+/// according to `L`.
+///
+/// This trait is implemented automatically for every type implementing the
+/// layout-specific trait, like [`AsStd140`][crate::std140::AsStd140] for
+/// [`Std140`], and should not be implemented directly.
+pub trait AsLayout<L: Layout> {
+    /// The version of this value laid out according to `L`.
+    type Output: LayoutValue<L>;
+
+    /// Convert this value into the version of itself laid out according to
+    /// `L`.
+    fn as_layout(&self) -> Self::Output;
+
+    /// Converts from the version of self laid out according to `L` to self.
+    fn from_layout(value: Self::Output) -> Self;
+
+    /// Returns the size of the version of this type laid out according to `L`.
+    /// Useful for pre-sizing buffers.
+    fn layout_size_static() -> usize {
+        <Self::Output as LayoutValue<L>>::SIZE
+    }
+}
+
+/// Trait implemented for all types that can be written into a buffer laid out
+/// according to `L`. This type is more general than [`AsLayout`]: all
+/// `AsLayout` types implement `WriteLayout`, but not the other way around.
+///
+/// While `AsLayout` requires implementers to return a type that implements the
+/// `LayoutValue` trait, `WriteLayout` directly writes bytes using a [`Writer`].
+/// This makes `WriteLayout` usable for writing slices or other DSTs that could
+/// not implement `AsLayout` without allocating new memory on the heap.
+///
+/// This trait is implemented automatically for every type implementing the
+/// layout-specific trait, like [`WriteStd140`][crate::std140::WriteStd140] for
+/// [`Std140`], and for slices of those types. Implement the layout-specific
+/// trait instead of implementing this one directly.
+#[cfg(feature = "std")]
+pub trait WriteLayout<L: Layout> {
+    /// Writes this value into the given [`Writer`] using the layout `L`.
+    ///
+    /// Should return the offset of the first byte of this type, as returned by
+    /// the first call to [`Writer::write`].
+    fn write_layout<W: Write>(&self, writer: &mut Writer<L, W>) -> io::Result<usize>;
+
+    /// The space required to write this value using the layout `L`. This does
+    /// not include alignment padding that may be needed before or after this
+    /// type when written as part of a larger buffer.
+    fn layout_size(&self) -> usize {
+        let mut writer = Writer::<L, _>::new(io::sink());
+        self.write_layout(&mut writer).unwrap();
+        writer.len()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L, T> WriteLayout<L> for [T]
+where
+    L: Layout,
+    T: WriteLayout<L>,
+{
+    fn write_layout<W: Write>(&self, writer: &mut Writer<L, W>) -> io::Result<usize> {
+        // if no items are written, offset is current position of the writer
+        let mut offset = writer.len();
+
+        for (index, item) in self.iter().enumerate() {
+            writer.pad_to(L::ARRAY_ELEMENT_ALIGNMENT)?;
+
+            let item_offset = item.write_layout(writer)?;
+            if index == 0 {
+                offset = item_offset;
+            }
+        }
+
+        Ok(offset)
+    }
+}
+
+/**
+Type that enables writing correctly aligned values to a buffer using the layout
+`L`.
+
+`Writer` is useful when many values need to be laid out in a row that cannot be
+represented by a struct alone, like dynamically sized arrays or dynamically
+laid-out values.
+
+Each layout module has an alias for its own `Writer`, like
+[`std140::Writer`][crate::std140::Writer], which is usually more convenient to
+use outside of generic code.
+*/
+#[cfg(feature = "std")]
+pub struct Writer<L, W> {
+    writer: W,
+    offset: usize,
+    _layout: PhantomData<L>,
+}
+
+#[cfg(feature = "std")]
+impl<L: Layout, W: Write> Writer<L, W> {
+    /// Create a new `Writer`, wrapping a buffer, file, or other type that
+    /// implements [`std::io::Write`].
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            offset: 0,
+            _layout: PhantomData,
+        }
+    }
+
+    /// Write a new value to the underlying buffer, writing zeroed padding where
+    /// necessary.
+    ///
+    /// Returns the offset into the buffer that the value was written to.
+    pub fn write<T>(&mut self, value: &T) -> io::Result<usize>
+    where
+        T: WriteLayout<L> + ?Sized,
+    {
+        value.write_layout(self)
+    }
+
+    /// Write an iterator of values to the underlying buffer.
+    ///
+    /// Returns the offset into the buffer that the first value was written to.
+    /// If no values were written, returns the `len()`.
+    pub fn write_iter<I, T>(&mut self, iter: I) -> io::Result<usize>
+    where
+        I: IntoIterator<Item = T>,
+        T: WriteLayout<L>,
+    {
+        let mut offset = self.offset;
+
+        let mut iter = iter.into_iter();
+
+        if let Some(item) = iter.next() {
+            offset = item.write_layout(self)?;
+        }
+
+        for item in iter {
+            item.write_layout(self)?;
+        }
+
+        Ok(offset)
+    }
+
+    /// Write a [`LayoutValue`] to the underlying buffer.
+    pub fn write_value<T>(&mut self, value: &T) -> io::Result<usize>
+    where
+        T: LayoutValue<L>,
+    {
+        let padding = L::padding(self.offset, T::ALIGNMENT, T::SIZE);
+        self.write_padding(padding)?;
+
+        self.writer.write_all(value.as_bytes())?;
+
+        let write_here = self.offset;
+        self.offset += T::SIZE;
+
+        Ok(write_here)
+    }
+
+    /// Writes zeroed padding until the offset is a multiple of `alignment`.
+    pub(crate) fn pad_to(&mut self, alignment: usize) -> io::Result<()> {
+        self.write_padding(align_offset(self.offset, alignment))
+    }
+
+    fn write_padding(&mut self, padding: usize) -> io::Result<()> {
+        for _ in 0..padding {
+            self.writer.write_all(&[0])?;
+        }
+        self.offset += padding;
+
+        Ok(())
+    }
+
+    /// Returns the amount of data written by this `Writer`.
+    pub fn len(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` if nothing has been written by this `Writer` yet.
+    pub fn is_empty(&self) -> bool {
+        self.offset == 0
+    }
+}
+
+/**
+Type that computes the buffer size needed by a series of types laid out using
+the layout `L`.
+
+This type works well well when paired with [`Writer`], precomputing a buffer's
+size to alleviate the need to dynamically re-allocate buffers.
+
+Each layout module has an alias for its own `Sizer`, like
+[`std140::Sizer`][crate::std140::Sizer], which is usually more convenient to use
+outside of generic code.
+*/
+pub struct Sizer<L> {
+    offset: usize,
+    _layout: PhantomData<L>,
+}
+
+impl<L: Layout> Sizer<L> {
+    /// Create a new `Sizer`.
+    pub fn new() -> Self {
+        Self {
+            offset: 0,
+            _layout: PhantomData,
+        }
+    }
+
+    /// Add a type's necessary padding and size to the `Sizer`. Returns the
+    /// offset into the buffer where that type would be written.
+    pub fn add<T>(&mut self) -> usize
+    where
+        T: AsLayout<L>,
+    {
+        let size = <<T as AsLayout<L>>::Output as LayoutValue<L>>::SIZE;
+        let alignment = <<T as AsLayout<L>>::Output as LayoutValue<L>>::ALIGNMENT;
+        let padding = L::padding(self.offset, alignment, size);
+
+        self.offset += padding;
+        let write_here = self.offset;
+
+        self.offset += size;
+
+        write_here
+    }
+
+    /// Returns the number of bytes required to contain all the types added to
+    /// the `Sizer`.
+    pub fn len(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` if no types have been added to the `Sizer`.
+    pub fn is_empty(&self) -> bool {
+        self.offset == 0
+    }
+}
+
+impl<L: Layout> Default for Sizer<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
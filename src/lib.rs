@@ -143,12 +143,84 @@ unmap_gpu_buffer();
 )]
 /*!
 
+### Layout Assertions
+
+Layout derives accept `#[crevice(...)]` attributes that are checked at compile
+time, so layout regressions fail the build instead of rendering garbage.
+`assert_size` and `assert_align` require an exact size and alignment and
+`max_size` sets an upper bound on the size. Options can be scoped to a single
+layout by nesting them, like `#[crevice(std430(assert_size = 28))]`.
+
+```rust
+use crevice::std140::AsStd140;
+
+#[derive(AsStd140)]
+#[crevice(assert_size = 32, assert_align = 16, max_size = 16384)]
+struct PointLight {
+    position: mint::Point3<f32>,
+    color: mint::Vector3<f32>,
+    brightness: f32,
+}
+```
+
+A struct that doesn't match its assertions fails to compile:
+
+```compile_fail
+use crevice::std140::AsStd140;
+
+#[derive(AsStd140)]
+#[crevice(assert_size = 16)]
+struct PointLight {
+    position: mint::Point3<f32>,
+    color: mint::Vector3<f32>,
+}
+```
+
+Layouts can also be checked from outside the type with
+[`assert_std140_layout!`] and friends.
+
+### Field Reordering
+
+Declaration order can leave large gaps between fields.
+[`LayoutReport::suggest_order`][report::LayoutReport::suggest_order] suggests an
+order that needs less padding and reports how many bytes it saves. Adding
+`#[crevice(reorder)]` reorders the fields automatically.
+
+Each layout derive orders fields for its own rules, so a struct deriving
+several layouts can have a different order in each. Shader code follows one
+layout: [`GlslStruct`][glsl::GlslStruct] uses `std140`, `HlslStruct` uses
+`hlsl_cbuffer` and `AsOpenCl`'s C struct uses `opencl`. Naming a layout, as in
+`#[crevice(reorder(std430))]`, makes every derive use that layout's order
+instead, which keeps shader code and every layout in agreement.
+
+Structs with up to seven fields get the order that needs the least padding.
+Larger structs are ordered greedily, which usually but not always finds it.
+
+```rust
+use crevice::std140::AsStd140;
+
+#[derive(AsStd140)]
+#[crevice(reorder, assert_size = 32)]
+struct Particle {
+    mass: f32,
+    position: mint::Vector3<f32>,
+    charge: f32,
+    velocity: mint::Vector3<f32>,
+}
+```
+
 ## Features
 
-* `std` (default): Enables [`std::io::Write`]-based structs.
+* `std` (default): Enables [`std::io::Write`]-based structs and writing shader
+  headers to files with [`module::ShaderModuleBuilder`]. Implies `alloc`.
+* `alloc`: Enables generating shader code and layout reports as `String`s at
+  runtime. Derived GLSL structs also get a `GLSL_DEFINITION` constant, which
+  doesn't need this feature.
 * `cgmath`: Enables support for types from cgmath.
 * `nalgebra`: Enables support for types from nalgebra.
 * `glam`: Enables support for types from glam.
+* `naga`: Enables the `naga` module, which checks layouts by laying out generated GLSL
+  and WGSL with naga. Implies `std`.
 
 ## Minimum Supported Rust Version (MSRV)
 
@@ -160,15 +232,77 @@ Crevice supports Rust 1.85 and higher.
 #![deny(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[macro_use]
 mod util;
 
+mod assertions;
+
 pub mod glsl;
+pub mod hlsl;
+pub mod hlsl_cbuffer;
+pub mod layout;
+#[cfg(feature = "alloc")]
+pub mod module;
+pub mod msl;
+#[cfg(feature = "naga")]
+pub mod naga;
+pub mod opencl;
+pub mod reflect;
+#[cfg(feature = "alloc")]
+pub mod report;
+#[cfg(feature = "alloc")]
+pub mod spirv;
 pub mod std140;
 pub mod std430;
+pub mod wgsl;
 
 #[doc(hidden)]
 pub mod internal;
 
+/// Generates Rust structs from the struct and block declarations in a GLSL
+/// file, so that shaders can be written first and Rust follows them.
+///
+/// The path is relative to the root of the crate using the macro. Each GLSL
+/// struct becomes a Rust struct deriving `AsStd140`, `AsStd430` and
+/// [`GlslStruct`][glsl::GlslStruct], with fields using mint types. Uniform and
+/// storage buffer blocks become structs deriving the layout they're declared
+/// with, which defaults to `std140` for uniform blocks and `std430` for storage
+/// buffers. Comments directly above declarations become doc comments.
+///
+/// Everything else in the file, like functions, other global variables and
+/// preprocessor directives, is skipped. Arrays become Rust arrays, with lengths
+/// given by integer literals or by constants declared with `#define` or
+/// `const int`. Runtime-sized arrays have no Rust equivalent and are an error.
+///
+/// Errors in the file are reported with the line and column they're on.
+///
+/// ```rust
+/// crevice::include_glsl!("tests/shaders/lighting.glsl");
+///
+/// use crevice::glsl::GlslStruct;
+/// use crevice::std430::AsStd430;
+///
+/// let light = PointLight {
+///     position: [0.0, 2.0, 0.0].into(),
+///     radius: 10.0,
+///     color: [1.0, 1.0, 1.0].into(),
+///     r#type: 0,
+/// };
+///
+/// let lights = Lights {
+///     main: light,
+///     count: 1,
+///     lights: [light; 16],
+/// };
+///
+/// assert_eq!(Lights::std430_size_static(), 560);
+/// assert_eq!(lights.as_std430().count, 1);
+/// assert_eq!(Lights::FIELDS.len(), 3);
+/// ```
+pub use crevice_derive::include_glsl;
+
 mod bool;
 mod imp;
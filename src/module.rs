@@ -0,0 +1,535 @@
+/*!
+Generates shader headers that share types and constants with Rust, meant to be
+run from a build script.
+
+[`ShaderModuleBuilder`] collects structs, constants and resource declarations,
+then writes them as a GLSL header and optionally as WGSL and HLSL files. Every
+struct used by a registered struct or block is defined too, each exactly once
+and before the code that uses it. GLSL and HLSL headers are wrapped in include
+guards so they can be included from several shaders.
+
+Files are only rewritten when their contents change, so that tools watching
+them don't rebuild shaders needlessly.
+
+## Examples
+*/
+#![cfg_attr(
+    feature = "std",
+    doc = r##"
+```rust,no_run
+// build.rs
+use crevice::glsl::{GlslBlock, GlslStruct};
+use crevice::module::ShaderModuleBuilder;
+
+#[derive(GlslStruct)]
+struct PointLight {
+    position: mint::Vector3<f32>,
+    color: mint::Vector3<f32>,
+}
+
+#[derive(GlslStruct)]
+struct Lights {
+    main: PointLight,
+    count: u32,
+}
+
+fn main() -> std::io::Result<()> {
+    let module = ShaderModuleBuilder::new("shared_types")
+        .constant("MAX_LIGHTS", 16u32)
+        .glsl_struct::<PointLight>()
+        .glsl_block(GlslBlock::uniform::<Lights>().binding(0));
+
+    module.write_glsl("generated/shared_types.glsl")?;
+    Ok(())
+}
+```
+"##
+)]
+
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::glsl::{
+    extension_directives, glsl_definitions, target_definitions, Glsl, GlslBlock, GlslStruct,
+    GlslStructDefinition, GlslTarget, GlslValue,
+};
+use crate::hlsl::{
+    hlsl_definitions, HlslConstantBuffer, HlslStruct, HlslStructDefinition, MatrixLayout,
+};
+use crate::layout::{AsLayout, Layout};
+use crate::reflect::{LayoutInfo, Reflect};
+use crate::wgsl::{wgsl_definitions, WgslBinding, WgslStruct, WgslStructDefinition};
+
+/// The comment at the top of every generated file.
+const HEADER: &str = "// Generated by Crevice. Do not edit this file by hand.";
+
+/// The value of a constant that is written in every shading language, like an
+/// enum discriminant or an array length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConstantValue {
+    /// A 32-bit float, which must be finite to be written as WGSL.
+    Float(f32),
+
+    /// A 32-bit signed integer.
+    Int(i32),
+
+    /// A 32-bit unsigned integer.
+    Uint(u32),
+
+    /// A boolean.
+    Bool(bool),
+}
+
+impl From<f32> for ConstantValue {
+    fn from(value: f32) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<i32> for ConstantValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<u32> for ConstantValue {
+    fn from(value: u32) -> Self {
+        Self::Uint(value)
+    }
+}
+
+impl From<bool> for ConstantValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl ConstantValue {
+    fn glsl_declaration(self, name: &str) -> String {
+        match self {
+            Self::Float(value) => format!("const float {} = {};", name, value.to_glsl_value()),
+            Self::Int(value) => format!("const int {} = {};", name, value.to_glsl_value()),
+            Self::Uint(value) => format!("const uint {} = {};", name, value.to_glsl_value()),
+            Self::Bool(value) => format!("const bool {} = {};", name, value.to_glsl_value()),
+        }
+    }
+
+    fn wgsl_declaration(self, name: &str) -> String {
+        match self {
+            Self::Float(value) => {
+                assert!(
+                    value.is_finite(),
+                    "constant `{}` is {}, which WGSL can't represent",
+                    name,
+                    value
+                );
+                format!("const {}: f32 = {};", name, value.to_glsl_value())
+            }
+            Self::Int(value) => format!("const {}: i32 = {};", name, value),
+            Self::Uint(value) => format!("const {}: u32 = {}u;", name, value),
+            Self::Bool(value) => format!("const {}: bool = {};", name, value),
+        }
+    }
+
+    fn hlsl_declaration(self, name: &str) -> String {
+        match self {
+            Self::Float(value) if !value.is_finite() => format!(
+                "static const float {} = asfloat({}u);",
+                name,
+                value.to_bits()
+            ),
+            Self::Float(value) => {
+                format!("static const float {} = {};", name, value.to_glsl_value())
+            }
+            Self::Int(value) => format!("static const int {} = {};", name, value.to_glsl_value()),
+            Self::Uint(value) => format!("static const uint {} = {}u;", name, value),
+            Self::Bool(value) => format!("static const bool {} = {};", name, value),
+        }
+    }
+}
+
+/// Collects the types, constants and resource declarations shared between
+/// Rust and a set of shaders, and generates headers declaring them.
+///
+/// Structs are registered per language, since each language has its own
+/// derive. Constants registered with [`ShaderModuleBuilder::constant`] are
+/// written in every language.
+///
+/// ```rust
+/// use crevice::glsl::{GlslBlock, GlslStruct};
+/// use crevice::module::ShaderModuleBuilder;
+///
+/// #[derive(GlslStruct)]
+/// struct Camera {
+///     view: mint::ColumnMatrix4<f32>,
+///     position: mint::Vector3<f32>,
+/// }
+///
+/// let module = ShaderModuleBuilder::new("camera")
+///     .constant("CAMERA_BINDING", 0u32)
+///     .glsl_block(GlslBlock::uniform::<Camera>().binding(0).instance_name("camera"));
+///
+/// assert_eq!(
+///     module.glsl_source(),
+///     "// Generated by Crevice. Do not edit this file by hand.\n\
+///     #ifndef CAMERA_GLSL\n\
+///     #define CAMERA_GLSL\n\
+///     \n\
+///     const uint CAMERA_BINDING = 0u;\n\
+///     \n\
+///     layout(std140, binding = 0) uniform Camera {\n\
+///     \tmat4 view;\n\
+///     \tvec3 position;\n\
+///     } camera;\n\
+///     \n\
+///     #endif\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShaderModuleBuilder {
+    name: String,
+    constants: Vec<(String, ConstantValue)>,
+
+    glsl_target: Option<GlslTarget>,
+    glsl_structs: Vec<&'static GlslStructDefinition>,
+    glsl_constants: Vec<(String, String)>,
+    glsl_blocks: Vec<GlslBlock>,
+
+    wgsl_structs: Vec<(&'static WgslStructDefinition, &'static LayoutInfo)>,
+    wgsl_bindings: Vec<WgslBinding>,
+
+    hlsl_matrices: MatrixLayout,
+    hlsl_structs: Vec<&'static HlslStructDefinition>,
+    hlsl_cbuffers: Vec<HlslConstantBuffer>,
+}
+
+impl ShaderModuleBuilder {
+    /// Creates an empty module. The name is used for include guards, so
+    /// `shared_types` is guarded by `SHARED_TYPES_GLSL` in GLSL.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            constants: Vec::new(),
+            glsl_target: None,
+            glsl_structs: Vec::new(),
+            glsl_constants: Vec::new(),
+            glsl_blocks: Vec::new(),
+            wgsl_structs: Vec::new(),
+            wgsl_bindings: Vec::new(),
+            hlsl_matrices: MatrixLayout::ColumnMajor,
+            hlsl_structs: Vec::new(),
+            hlsl_cbuffers: Vec::new(),
+        }
+    }
+
+    /// Adds a scalar constant that is declared in every language.
+    ///
+    /// # Panics
+    /// Panics if a different constant with the same name was already added.
+    pub fn constant(mut self, name: &str, value: impl Into<ConstantValue>) -> Self {
+        let value = value.into();
+        if !contains_constant(&self.constants, name, &value) {
+            self.constants.push((name.to_owned(), value));
+        }
+        self
+    }
+
+    /// Sets the GLSL dialect to generate code for, which also applies to every
+    /// block. See [`GlslTarget`].
+    pub fn glsl_target(mut self, target: GlslTarget) -> Self {
+        self.glsl_target = Some(target);
+        self
+    }
+
+    /// Adds a GLSL struct, along with every struct it depends on.
+    pub fn glsl_struct<T: GlslStruct>(mut self) -> Self {
+        if let Some(definition) = T::DEFINITION {
+            self.glsl_structs.push(definition);
+        }
+        self
+    }
+
+    /// Adds a GLSL constant of any type, like `const vec3 UP = vec3(0.0, 1.0,
+    /// 0.0);`. Struct types must also be added with
+    /// [`ShaderModuleBuilder::glsl_struct`].
+    ///
+    /// # Panics
+    /// Panics if a different GLSL constant with the same name was already
+    /// added.
+    pub fn glsl_constant<T: Glsl + GlslValue>(mut self, name: &str, value: &T) -> Self {
+        let declaration = crate::glsl::glsl_constant(name, value);
+        if !contains_constant(&self.glsl_constants, name, &declaration) {
+            self.glsl_constants.push((name.to_owned(), declaration));
+        }
+        self
+    }
+
+    /// Adds a GLSL interface block, along with every struct its members use.
+    pub fn glsl_block(mut self, block: GlslBlock) -> Self {
+        self.glsl_blocks.push(block);
+        self
+    }
+
+    /// Adds a WGSL struct laid out according to `L`, along with every struct
+    /// it depends on.
+    pub fn wgsl_struct<T, L>(mut self) -> Self
+    where
+        L: Layout,
+        T: WgslStruct + AsLayout<L>,
+        <T as AsLayout<L>>::Output: Reflect,
+    {
+        self.wgsl_structs
+            .push((T::DEFINITION, <T as AsLayout<L>>::Output::LAYOUT_INFO));
+        self
+    }
+
+    /// Adds a WGSL resource binding. The struct it refers to must also be
+    /// added with [`ShaderModuleBuilder::wgsl_struct`], which gives its
+    /// layout.
+    pub fn wgsl_binding(mut self, binding: WgslBinding) -> Self {
+        self.wgsl_bindings.push(binding);
+        self
+    }
+
+    /// Sets how matrices are declared in HLSL structs and constant buffers.
+    pub fn hlsl_matrix_layout(mut self, matrices: MatrixLayout) -> Self {
+        self.hlsl_matrices = matrices;
+        self
+    }
+
+    /// Adds an HLSL struct, along with every struct it depends on.
+    pub fn hlsl_struct<T: HlslStruct>(mut self) -> Self {
+        self.hlsl_structs.push(T::DEFINITION);
+        self
+    }
+
+    /// Adds an HLSL constant buffer, along with every struct its members use.
+    pub fn hlsl_cbuffer(mut self, cbuffer: HlslConstantBuffer) -> Self {
+        self.hlsl_cbuffers.push(cbuffer);
+        self
+    }
+
+    /// Generates the GLSL header: extension directives, structs, constants and
+    /// blocks, inside an include guard.
+    ///
+    /// # Panics
+    /// Panics if anything can't be expressed on the GLSL target.
+    pub fn glsl_source(&self) -> String {
+        let blocks: Vec<_> = self
+            .glsl_blocks
+            .iter()
+            .map(|block| match self.glsl_target {
+                Some(target) => block.clone().target(target),
+                None => block.clone(),
+            })
+            .collect();
+
+        let mut structs = self.glsl_structs.clone();
+        for block in &blocks {
+            structs.extend(block.structs());
+        }
+
+        let mut extensions = Vec::new();
+        let definitions = match self.glsl_target {
+            Some(target) => {
+                for block in &blocks {
+                    block.add_extensions(&mut extensions);
+                }
+                target_definitions(&structs, target, &mut extensions)
+            }
+            None => glsl_definitions(&structs),
+        };
+
+        let constants = self
+            .constants
+            .iter()
+            .map(|(name, value)| value.glsl_declaration(name))
+            .chain(
+                self.glsl_constants
+                    .iter()
+                    .map(|(_, declaration)| declaration.clone()),
+            );
+
+        let sections = [
+            extension_directives(&extensions).trim_end().to_owned(),
+            definitions,
+            lines(constants),
+            blocks
+                .iter()
+                .map(GlslBlock::glsl_definition)
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        ];
+
+        guarded(&self.guard("GLSL"), &sections)
+    }
+
+    /// Generates the WGSL module: structs, constants and bindings. WGSL has no
+    /// preprocessor, so there is no include guard.
+    ///
+    /// # Panics
+    /// Panics if a struct's layout can't be expressed in WGSL, or a float
+    /// constant isn't finite.
+    pub fn wgsl_source(&self) -> String {
+        let sections = [
+            wgsl_definitions(&self.wgsl_structs),
+            lines(
+                self.constants
+                    .iter()
+                    .map(|(name, value)| value.wgsl_declaration(name)),
+            ),
+            lines(self.wgsl_bindings.iter().map(WgslBinding::wgsl_definition)),
+        ];
+
+        let mut output = String::new();
+        output.push_str(HEADER);
+        output.push('\n');
+        for section in sections.iter().filter(|section| !section.is_empty()) {
+            output.push('\n');
+            output.push_str(section);
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Generates the HLSL header: structs, constants and constant buffers,
+    /// inside an include guard.
+    pub fn hlsl_source(&self) -> String {
+        let cbuffers: Vec<_> = self
+            .hlsl_cbuffers
+            .iter()
+            .map(|cbuffer| cbuffer.clone().matrix_layout(self.hlsl_matrices))
+            .collect();
+
+        let mut structs = self.hlsl_structs.clone();
+        for cbuffer in &cbuffers {
+            structs.extend(cbuffer.structs());
+        }
+
+        let sections = [
+            hlsl_definitions(&structs, self.hlsl_matrices),
+            lines(
+                self.constants
+                    .iter()
+                    .map(|(name, value)| value.hlsl_declaration(name)),
+            ),
+            cbuffers
+                .iter()
+                .map(HlslConstantBuffer::hlsl_definition)
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        ];
+
+        guarded(&self.guard("HLSL"), &sections)
+    }
+
+    /// Writes the GLSL header to `path`, creating its parent directories. See
+    /// [`ShaderModuleBuilder::glsl_source`].
+    ///
+    /// The file is only written if its contents would change. Returns whether
+    /// it was written.
+    #[cfg(feature = "std")]
+    pub fn write_glsl(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<bool> {
+        write_if_changed(path.as_ref(), &self.glsl_source())
+    }
+
+    /// Writes the WGSL module to `path`, creating its parent directories. See
+    /// [`ShaderModuleBuilder::wgsl_source`].
+    ///
+    /// The file is only written if its contents would change. Returns whether
+    /// it was written.
+    #[cfg(feature = "std")]
+    pub fn write_wgsl(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<bool> {
+        write_if_changed(path.as_ref(), &self.wgsl_source())
+    }
+
+    /// Writes the HLSL header to `path`, creating its parent directories. See
+    /// [`ShaderModuleBuilder::hlsl_source`].
+    ///
+    /// The file is only written if its contents would change. Returns whether
+    /// it was written.
+    #[cfg(feature = "std")]
+    pub fn write_hlsl(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<bool> {
+        write_if_changed(path.as_ref(), &self.hlsl_source())
+    }
+
+    /// The include guard macro for the given language, like
+    /// `SHARED_TYPES_GLSL`.
+    fn guard(&self, language: &str) -> String {
+        let mut output = String::new();
+        for c in self.name.chars() {
+            if c.is_ascii_alphanumeric() {
+                output.push(c.to_ascii_uppercase());
+            } else {
+                output.push('_');
+            }
+        }
+        write!(output, "_{}", language).unwrap();
+        output
+    }
+}
+
+/// Whether `constants` already has a constant named `name` with the given
+/// value.
+///
+/// # Panics
+/// Panics if `constants` has a constant named `name` with a different value.
+fn contains_constant<T: PartialEq + core::fmt::Debug>(
+    constants: &[(String, T)],
+    name: &str,
+    value: &T,
+) -> bool {
+    match constants.iter().find(|(existing, _)| existing == name) {
+        Some((_, existing)) => {
+            assert!(
+                existing == value,
+                "constant `{}` was added as both {:?} and {:?}",
+                name,
+                existing,
+                value
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Joins declarations into one section, one per line.
+fn lines(declarations: impl Iterator<Item = String>) -> String {
+    declarations.collect::<Vec<_>>().join("\n")
+}
+
+/// Wraps the non-empty sections in an include guard, separated by blank lines.
+fn guarded(guard: &str, sections: &[String]) -> String {
+    let mut output = String::new();
+    writeln!(output, "{}", HEADER).unwrap();
+    writeln!(output, "#ifndef {}", guard).unwrap();
+    writeln!(output, "#define {}", guard).unwrap();
+
+    for section in sections.iter().filter(|section| !section.is_empty()) {
+        output.push('\n');
+        output.push_str(section);
+        output.push('\n');
+    }
+
+    output.push_str("\n#endif\n");
+    output
+}
+
+/// Writes `contents` to `path` unless the file already holds exactly that,
+/// giving whether it was written.
+#[cfg(feature = "std")]
+fn write_if_changed(path: &std::path::Path, contents: &str) -> std::io::Result<bool> {
+    if let Ok(existing) = std::fs::read(path) {
+        if existing == contents.as_bytes() {
+            return Ok(false);
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, contents)?;
+    Ok(true)
+}
@@ -0,0 +1,27 @@
+//! Defines traits and types for working with data laid out as in Metal Shading
+//! Language buffers.
+//!
+//! MSL follows C-like layout rules: structs are aligned to their most aligned
+//! member and padded out to a multiple of that alignment. MSL's `float3` is 16
+//! bytes with 16-byte alignment, while its `packed_float3` is 12 bytes with
+//! 4-byte alignment. Math library vector types convert to the unpacked types;
+//! the packed types, like [`PackedVec3`], can be used directly as fields.
+//!
+//! MSL has no 64-bit floating point types, so `f64` values are not supported.
+//!
+//! MSL struct declarations can be generated from types deriving
+//! [`GlslStruct`][crate::glsl::GlslStruct] with `GlslStruct::msl_definition`.
+
+mod primitives;
+mod sizer;
+mod traits;
+#[cfg(feature = "std")]
+mod writer;
+
+pub use self::primitives::*;
+pub use self::sizer::*;
+pub use self::traits::*;
+#[cfg(feature = "std")]
+pub use self::writer::*;
+
+pub use crevice_derive::AsMsl;
@@ -0,0 +1,305 @@
+use core::fmt::{Debug, Formatter};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::glsl::Glsl;
+use crate::internal::array_alignment;
+use crate::layout::{self, ArrayElement, LayoutValue};
+use crate::msl::{AsMsl, Msl};
+use crate::reflect::{LayoutInfo, Reflect};
+
+/// MSL's `bool` type.
+///
+/// Unlike GLSL, booleans in MSL are a single byte, like Rust's bools.
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Bool(u8);
+
+unsafe impl Zeroable for Bool {}
+unsafe impl Pod for Bool {}
+
+impl From<bool> for Bool {
+    fn from(v: bool) -> Self {
+        Self(v as u8)
+    }
+}
+
+impl From<Bool> for bool {
+    fn from(v: Bool) -> Self {
+        v.0 != 0
+    }
+}
+
+impl Debug for Bool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Bool({:?})", bool::from(*self))
+    }
+}
+
+unsafe impl Msl for f32 {
+    const ALIGNMENT: usize = 4;
+}
+
+unsafe impl Msl for i32 {
+    const ALIGNMENT: usize = 4;
+}
+
+unsafe impl Msl for u32 {
+    const ALIGNMENT: usize = 4;
+}
+
+unsafe impl Msl for Bool {
+    const ALIGNMENT: usize = 1;
+}
+
+unsafe impl Glsl for Bool {
+    const NAME: &'static str = "bool";
+}
+
+impl AsMsl for bool {
+    type Output = Bool;
+
+    fn as_msl(&self) -> Self::Output {
+        (*self).into()
+    }
+
+    fn from_msl(val: Self::Output) -> Self {
+        val.into()
+    }
+}
+
+macro_rules! vectors {
+    (
+        $(
+            #[$doc:meta] $(msl($msl_name:literal))? align($align:literal) $glsl_name:ident $name:ident <$prim:ident> ($($field:ident),+) $(pad $pad_ty:ty)?
+        )+
+    ) => {
+        $(
+            #[$doc]
+            #[allow(missing_docs)]
+            #[derive(Debug, Clone, Copy, PartialEq)]
+            #[repr(C)]
+            pub struct $name {
+                $(pub $field: $prim,)+
+                $(pub _pad: $pad_ty,)?
+            }
+
+            unsafe impl Zeroable for $name {}
+            unsafe impl Pod for $name {}
+
+            unsafe impl Msl for $name {
+                const ALIGNMENT: usize = $align;
+            }
+
+            unsafe impl Glsl for $name {
+                const NAME: &'static str = stringify!($glsl_name);
+                $(const MSL_NAME: Option<&'static str> = Some($msl_name);)?
+            }
+
+            unsafe impl Reflect for $name {
+                const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo::vector(
+                    stringify!($glsl_name),
+                    core::mem::size_of::<$name>(),
+                    $align,
+                    [$(stringify!($field)),+].len() * core::mem::size_of::<$prim>(),
+                );
+            }
+        )+
+    };
+}
+
+vectors! {
+    #[doc = "Corresponds to an MSL `float2`."] align(8) vec2 Vec2<f32>(x, y)
+    #[doc = "Corresponds to an MSL `float3`, which is padded out to 16 bytes."] align(16) vec3 Vec3<f32>(x, y, z) pad f32
+    #[doc = "Corresponds to an MSL `float4`."] align(16) vec4 Vec4<f32>(x, y, z, w)
+
+    #[doc = "Corresponds to an MSL `int2`."] align(8) ivec2 IVec2<i32>(x, y)
+    #[doc = "Corresponds to an MSL `int3`, which is padded out to 16 bytes."] align(16) ivec3 IVec3<i32>(x, y, z) pad i32
+    #[doc = "Corresponds to an MSL `int4`."] align(16) ivec4 IVec4<i32>(x, y, z, w)
+
+    #[doc = "Corresponds to an MSL `uint2`."] align(8) uvec2 UVec2<u32>(x, y)
+    #[doc = "Corresponds to an MSL `uint3`, which is padded out to 16 bytes."] align(16) uvec3 UVec3<u32>(x, y, z) pad u32
+    #[doc = "Corresponds to an MSL `uint4`."] align(16) uvec4 UVec4<u32>(x, y, z, w)
+
+    #[doc = "Corresponds to an MSL `bool2`."] align(2) bvec2 BVec2<Bool>(x, y)
+    #[doc = "Corresponds to an MSL `bool3`, which is padded out to 4 bytes."] align(4) bvec3 BVec3<Bool>(x, y, z) pad u8
+    #[doc = "Corresponds to an MSL `bool4`."] align(4) bvec4 BVec4<Bool>(x, y, z, w)
+
+    #[doc = "Corresponds to an MSL `packed_float2`."] msl("packed_float2") align(4) vec2 PackedVec2<f32>(x, y)
+    #[doc = "Corresponds to an MSL `packed_float3`."] msl("packed_float3") align(4) vec3 PackedVec3<f32>(x, y, z)
+    #[doc = "Corresponds to an MSL `packed_float4`."] msl("packed_float4") align(4) vec4 PackedVec4<f32>(x, y, z, w)
+
+    #[doc = "Corresponds to an MSL `packed_int2`."] msl("packed_int2") align(4) ivec2 PackedIVec2<i32>(x, y)
+    #[doc = "Corresponds to an MSL `packed_int3`."] msl("packed_int3") align(4) ivec3 PackedIVec3<i32>(x, y, z)
+    #[doc = "Corresponds to an MSL `packed_int4`."] msl("packed_int4") align(4) ivec4 PackedIVec4<i32>(x, y, z, w)
+
+    #[doc = "Corresponds to an MSL `packed_uint2`."] msl("packed_uint2") align(4) uvec2 PackedUVec2<u32>(x, y)
+    #[doc = "Corresponds to an MSL `packed_uint3`."] msl("packed_uint3") align(4) uvec3 PackedUVec3<u32>(x, y, z)
+    #[doc = "Corresponds to an MSL `packed_uint4`."] msl("packed_uint4") align(4) uvec4 PackedUVec4<u32>(x, y, z, w)
+}
+
+macro_rules! matrices {
+    (
+        $(
+            #[$doc:meta]
+            align($align:literal)
+            $glsl_name:ident $name:ident {
+                $column:ident: $column_ty:ty,
+                $($field:ident: $field_ty:ty,)+
+            }
+        )+
+    ) => {
+        $(
+            #[$doc]
+            #[allow(missing_docs)]
+            #[derive(Debug, Clone, Copy)]
+            #[repr(C)]
+            pub struct $name {
+                pub $column: $column_ty,
+                $(pub $field: $field_ty,)+
+            }
+
+            unsafe impl Zeroable for $name {}
+            unsafe impl Pod for $name {}
+
+            unsafe impl Msl for $name {
+                const ALIGNMENT: usize = $align;
+            }
+
+            unsafe impl Glsl for $name {
+                const NAME: &'static str = stringify!($glsl_name);
+            }
+
+            unsafe impl Reflect for $name {
+                const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo::matrix(
+                    stringify!($glsl_name),
+                    core::mem::size_of::<$name>(),
+                    $align,
+                    <$column_ty as Reflect>::LAYOUT_INFO,
+                    core::mem::offset_of!($name, y),
+                );
+            }
+        )+
+    };
+}
+
+// MSL matrices are stored column-major, with each column laid out like the
+// unpacked vector type of the same length.
+matrices! {
+    #[doc = "Corresponds to an MSL `float2x2`."]
+    align(8)
+    mat2 Mat2 {
+        x: Vec2,
+        y: Vec2,
+    }
+
+    #[doc = "Corresponds to an MSL `float3x3`."]
+    align(16)
+    mat3 Mat3 {
+        x: Vec3,
+        y: Vec3,
+        z: Vec3,
+    }
+
+    #[doc = "Corresponds to an MSL `float4x4`."]
+    align(16)
+    mat4 Mat4 {
+        x: Vec4,
+        y: Vec4,
+        z: Vec4,
+        w: Vec4,
+    }
+}
+
+macro_rules! packed_from_mint {
+    ( $( $name:ident mint::$mint_ty:ident<$prim:ident> { $($field:ident),+ }, )* ) => {
+        $(
+            impl From<mint::$mint_ty<$prim>> for $name {
+                fn from(value: mint::$mint_ty<$prim>) -> Self {
+                    Self {
+                        $($field: value.$field,)+
+                    }
+                }
+            }
+
+            impl From<$name> for mint::$mint_ty<$prim> {
+                fn from(value: $name) -> Self {
+                    Self {
+                        $($field: value.$field,)+
+                    }
+                }
+            }
+        )*
+    };
+}
+
+packed_from_mint! {
+    PackedVec2 mint::Vector2<f32> { x, y },
+    PackedVec3 mint::Vector3<f32> { x, y, z },
+    PackedVec4 mint::Vector4<f32> { x, y, z, w },
+
+    PackedIVec2 mint::Vector2<i32> { x, y },
+    PackedIVec3 mint::Vector3<i32> { x, y, z },
+    PackedIVec4 mint::Vector4<i32> { x, y, z, w },
+
+    PackedUVec2 mint::Vector2<u32> { x, y },
+    PackedUVec3 mint::Vector3<u32> { x, y, z },
+    PackedUVec4 mint::Vector4<u32> { x, y, z, w },
+}
+
+array_elements!(layout::Msl {
+    f32,
+    i32,
+    u32,
+    Bool,
+    Vec2,
+    Vec3,
+    Vec4,
+    IVec2,
+    IVec3,
+    IVec4,
+    UVec2,
+    UVec3,
+    UVec4,
+    BVec2,
+    BVec3,
+    BVec4,
+    PackedVec2,
+    PackedVec3,
+    PackedVec4,
+    PackedIVec2,
+    PackedIVec3,
+    PackedIVec4,
+    PackedUVec2,
+    PackedUVec3,
+    PackedUVec4,
+    Mat2,
+    Mat3,
+    Mat4,
+});
+
+/// Corresponds to an MSL array.
+///
+/// Every MSL type's size is a multiple of its alignment, so elements are
+/// stored without padding between them. See [`layout::Array`].
+pub type Array<T, const N: usize> = layout::Array<layout::Msl, T, N>;
+
+unsafe impl<T: ArrayElement<layout::Msl>, const N: usize> Msl for Array<T, N> {
+    const ALIGNMENT: usize =
+        array_alignment::<layout::Msl>(<T as LayoutValue<layout::Msl>>::ALIGNMENT);
+}
+
+impl<T: AsMsl, const N: usize> AsMsl for [T; N]
+where
+    T::Output: ArrayElement<layout::Msl>,
+{
+    type Output = Array<T::Output, N>;
+
+    fn as_msl(&self) -> Self::Output {
+        Array::new(self.each_ref().map(T::as_msl))
+    }
+
+    fn from_msl(value: Self::Output) -> Self {
+        value.to_array().map(T::from_msl)
+    }
+}
@@ -0,0 +1,201 @@
+use core::mem::size_of;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use bytemuck::{bytes_of, Pod, Zeroable};
+
+#[cfg(feature = "std")]
+use crate::layout::WriteLayout;
+use crate::layout::{self, AsLayout, LayoutValue};
+#[cfg(feature = "std")]
+use crate::msl::Writer;
+
+/// Trait implemented for all MSL primitives. Generally should not be
+/// implemented outside this crate.
+///
+/// # Safety
+/// Implementors must not contain any uninitialized padding bytes, and their
+/// size and `ALIGNMENT` must match the MSL layout of the type they represent.
+pub unsafe trait Msl: Copy + Zeroable + Pod {
+    /// The required alignment of the type. Must be a power of two.
+    ///
+    /// This is distinct from the value returned by `std::mem::align_of` because
+    /// `AsMsl` structs do not use Rust's alignment. This enables them to
+    /// control and zero their padding bytes, making converting them to and from
+    /// slices safe.
+    const ALIGNMENT: usize;
+
+    /// Casts the type to a byte array. Implementors should not override this
+    /// method.
+    ///
+    /// # Safety
+    /// This is always safe due to the requirements of [`bytemuck::Pod`] being a
+    /// prerequisite for this trait.
+    fn as_bytes(&self) -> &[u8] {
+        bytes_of(self)
+    }
+}
+
+/**
+Trait implemented for all types that can be turned into MSL values.
+
+This trait can often be `#[derive]`'d instead of manually implementing it. Any
+struct which contains only fields that also implement `AsMsl` can derive
+`AsMsl`.
+
+Types from the mint crate implement `AsMsl`, making them convenient for use
+in buffer types. Most Rust geometry crates, like cgmath, nalgebra, and
+ultraviolet support mint.
+
+## Example
+
+```metal
+struct Camera {
+    float4x4 view;
+    float4x4 projection;
+};
+```
+
+```no_run
+use crevice::msl::AsMsl;
+
+#[derive(AsMsl)]
+struct CameraUniform {
+    view: mint::ColumnMatrix4<f32>,
+    projection: mint::ColumnMatrix4<f32>,
+}
+
+let view: mint::ColumnMatrix4<f32> = todo!("your math code here");
+let projection: mint::ColumnMatrix4<f32> = todo!("your math code here");
+
+let camera = CameraUniform {
+    view,
+    projection,
+};
+
+# fn write_to_gpu_buffer(bytes: &[u8]) {}
+let camera_msl = camera.as_msl();
+write_to_gpu_buffer(camera_msl.as_bytes());
+```
+*/
+pub trait AsMsl {
+    /// The MSL version of this value.
+    type Output: Msl;
+
+    /// Convert this value into the MSL version of itself.
+    fn as_msl(&self) -> Self::Output;
+
+    /// Returns the size of the MSL version of this type. Useful for
+    /// pre-sizing buffers.
+    fn msl_size_static() -> usize {
+        size_of::<Self::Output>()
+    }
+
+    /// Converts from MSL version of self to self.
+    fn from_msl(value: Self::Output) -> Self;
+}
+
+impl<T> AsMsl for T
+where
+    T: Msl,
+{
+    type Output = Self;
+
+    fn as_msl(&self) -> Self {
+        *self
+    }
+
+    fn from_msl(value: Self) -> Self {
+        value
+    }
+}
+
+/// Trait implemented for all types that can be written into a buffer as
+/// MSL bytes. This type is more general than [`AsMsl`]: all `AsMsl`
+/// types implement `WriteMsl`, but not the other way around.
+///
+/// While `AsMsl` requires implementers to return a type that implements the
+/// `Msl` trait, `WriteMsl` directly writes bytes using a [`Writer`]. This
+/// makes `WriteMsl` usable for writing slices or other DSTs that could not
+/// implement `AsMsl` without allocating new memory on the heap.
+///
+/// Every type implementing `WriteMsl` also implements
+/// [`WriteLayout<layout::Msl>`][WriteLayout], so it can be written with
+/// [`Writer::write`].
+#[cfg(feature = "std")]
+pub trait WriteMsl {
+    /// Writes this value into the given [`Writer`] using MSL layout rules.
+    ///
+    /// Should return the offset of the first byte of this type, as returned by
+    /// the first call to [`Writer::write`].
+    fn write_msl<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize>;
+
+    /// The space required to write this value using MSL layout rules. This
+    /// does not include alignment padding that may be needed before or after
+    /// this type when written as part of a larger buffer.
+    fn msl_size(&self) -> usize {
+        let mut writer = Writer::new(io::sink());
+        self.write_msl(&mut writer).unwrap();
+        writer.len()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteMsl for T
+where
+    T: AsMsl,
+{
+    fn write_msl<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        writer.write_value(&self.as_msl())
+    }
+
+    fn msl_size(&self) -> usize {
+        size_of::<<Self as AsMsl>::Output>()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteMsl for [T]
+where
+    T: WriteMsl,
+{
+    fn write_msl<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        self.write_layout(writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteLayout<layout::Msl> for T
+where
+    T: WriteMsl,
+{
+    fn write_layout<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        self.write_msl(writer)
+    }
+
+    fn layout_size(&self) -> usize {
+        self.msl_size()
+    }
+}
+
+unsafe impl<T> LayoutValue<layout::Msl> for T
+where
+    T: Msl,
+{
+    const ALIGNMENT: usize = <T as Msl>::ALIGNMENT;
+}
+
+impl<T> AsLayout<layout::Msl> for T
+where
+    T: AsMsl,
+{
+    type Output = <T as AsMsl>::Output;
+
+    fn as_layout(&self) -> Self::Output {
+        self.as_msl()
+    }
+
+    fn from_layout(value: Self::Output) -> Self {
+        <T as AsMsl>::from_msl(value)
+    }
+}
@@ -0,0 +1,317 @@
+/*!
+Checks Crevice's layouts against [naga](https://github.com/gfx-rs/wgpu/tree/trunk/naga),
+without needing a GPU.
+
+The generated GLSL or WGSL for a type is parsed with naga, which lays it out
+using its own implementation of the std140, std430 and WGSL rules. The offsets,
+sizes and strides it computes are then compared with Crevice's, giving a
+[`LayoutDiff`] listing any differences. This makes it possible to check layouts
+in CI on any machine, along with the hand-written shaders an application
+ships with.
+
+Members are matched to fields by name, so GLSL fields renamed with
+`#[crevice(rename(...))]` show up as missing.
+
+## Example
+
+```rust
+use crevice::glsl::GlslStruct;
+use crevice::layout::{Std140, Std430};
+use crevice::std140::AsStd140;
+use crevice::std430::AsStd430;
+use crevice::wgsl::WgslStruct;
+
+#[derive(AsStd140, AsStd430, GlslStruct, WgslStruct)]
+struct PointLight {
+    position: mint::Vector3<f32>,
+    radius: f32,
+    color: mint::Vector3<f32>,
+}
+
+for diff in [
+    crevice::naga::verify_glsl::<PointLight, Std140>().unwrap(),
+    crevice::naga::verify_glsl::<PointLight, Std430>().unwrap(),
+    crevice::naga::verify_wgsl::<PointLight, Std140>().unwrap(),
+] {
+    assert!(diff.is_match(), "{}", diff);
+}
+```
+*/
+
+use std::fmt;
+
+use ::naga::proc::Layouter;
+use ::naga::{ArraySize, Handle, Module, Scalar, ScalarKind, Type, TypeInner};
+
+use crate::glsl::{
+    array_type_name, compare_layouts, BlockLayout, GlslBlock, GlslStruct, LayoutDiff, MemberLayout,
+};
+use crate::layout::{AsLayout, Layout};
+use crate::reflect::{LayoutInfo, Reflect};
+use crate::wgsl::WgslStruct;
+
+/// Generates a storage buffer block for `T` in `L` layout, lays it out with
+/// naga and compares the result with Crevice's layout of `T`.
+///
+/// # Panics
+/// Panics if `L` isn't a layout GLSL blocks can use.
+pub fn verify_glsl<T, L>() -> Result<LayoutDiff, NagaError>
+where
+    L: Layout,
+    T: GlslStruct + AsLayout<L>,
+    <T as AsLayout<L>>::Output: Reflect,
+{
+    let layout = match L::NAME {
+        "std140" => BlockLayout::Std140,
+        "std430" => BlockLayout::Std430,
+        name => panic!("GLSL blocks can't use the {} layout", name),
+    };
+
+    // naga requires every block to have a binding.
+    let block = GlslBlock::buffer::<T>().layout(layout).set(0).binding(0);
+    let source = format!(
+        "#version 450\n\n{}\n\nvoid main() {{}}\n",
+        block.glsl_definition_with_dependencies()
+    );
+
+    validate_glsl_block(&source, T::NAME, <T as AsLayout<L>>::Output::LAYOUT_INFO)
+}
+
+/// Generates the WGSL struct for `T` in `L` layout, lays it out with naga and
+/// compares the result with Crevice's layout of `T`.
+pub fn verify_wgsl<T, L>() -> Result<LayoutDiff, NagaError>
+where
+    L: Layout,
+    T: WgslStruct + AsLayout<L>,
+    <T as AsLayout<L>>::Output: Reflect,
+{
+    let source = T::wgsl_definition_with_dependencies::<L>();
+    validate_wgsl_struct(
+        &source,
+        T::DEFINITION.name,
+        <T as AsLayout<L>>::Output::LAYOUT_INFO,
+    )
+}
+
+/// Parses GLSL source with naga and compares the layout of the uniform or
+/// storage buffer block named `block_name` with `info`.
+///
+/// The source must be a complete shader, including a `main` function. naga
+/// computes offsets according to the block's layout qualifier.
+pub fn validate_glsl_block(
+    source: &str,
+    block_name: &str,
+    info: &LayoutInfo,
+) -> Result<LayoutDiff, NagaError> {
+    let options = ::naga::front::glsl::Options::from(::naga::ShaderStage::Compute);
+    let module = ::naga::front::glsl::Frontend::default()
+        .parse(&options, source)
+        .map_err(|err| NagaError::Parse {
+            message: err.emit_to_string(source),
+        })?;
+
+    // Blocks are the types of global variables. Structs used by a block are
+    // copied with the block's layout, so a struct of the same name may appear
+    // more than once.
+    let ty = module
+        .global_variables
+        .iter()
+        .map(|(_, variable)| variable.ty)
+        .find(|&ty| module.types[ty].name.as_deref() == Some(block_name));
+
+    validate(&module, ty, block_name, info)
+}
+
+/// Parses WGSL source with naga and compares the layout of the struct named
+/// `struct_name` with `info`.
+pub fn validate_wgsl_struct(
+    source: &str,
+    struct_name: &str,
+    info: &LayoutInfo,
+) -> Result<LayoutDiff, NagaError> {
+    let module = ::naga::front::wgsl::parse_str(source).map_err(|err| NagaError::Parse {
+        message: err.emit_to_string(source),
+    })?;
+
+    let ty = module.types.iter().find_map(|(handle, ty)| {
+        let is_struct = matches!(ty.inner, TypeInner::Struct { .. });
+        (is_struct && ty.name.as_deref() == Some(struct_name)).then_some(handle)
+    });
+
+    validate(&module, ty, struct_name, info)
+}
+
+fn validate(
+    module: &Module,
+    ty: Option<Handle<Type>>,
+    name: &str,
+    info: &LayoutInfo,
+) -> Result<LayoutDiff, NagaError> {
+    let ty = ty.ok_or_else(|| NagaError::NotFound {
+        name: name.to_owned(),
+    })?;
+
+    let mut layouter = Layouter::default();
+    layouter
+        .update(module.to_ctx())
+        .map_err(|err| NagaError::Layout {
+            message: err.to_string(),
+        })?;
+
+    let members = struct_members(module, &layouter, ty);
+    let mut differences = Vec::new();
+    compare_layouts(&members, info, "", 0, &mut differences);
+
+    Ok(LayoutDiff {
+        block: name.to_owned(),
+        differences,
+    })
+}
+
+/// An error that prevented naga from laying out a block or struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NagaError {
+    /// naga couldn't parse the source.
+    Parse {
+        /// naga's description of the errors.
+        message: String,
+    },
+
+    /// naga couldn't lay out the types in the source.
+    Layout {
+        /// naga's description of the error.
+        message: String,
+    },
+
+    /// The source doesn't declare a block or struct with the given name.
+    NotFound {
+        /// The name of the block or struct.
+        name: String,
+    },
+}
+
+impl fmt::Display for NagaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { message } => write!(f, "naga couldn't parse the source:\n{}", message),
+            Self::Layout { message } => write!(f, "naga couldn't lay out the source: {}", message),
+            Self::NotFound { name } => write!(f, "no block or struct named `{}` was found", name),
+        }
+    }
+}
+
+impl std::error::Error for NagaError {}
+
+/// Reads the layout naga computed for every member of the struct type `ty`.
+fn struct_members(module: &Module, layouter: &Layouter, ty: Handle<Type>) -> Vec<MemberLayout> {
+    let members = match &module.types[ty].inner {
+        TypeInner::Struct { members, .. } => members,
+        _ => return Vec::new(),
+    };
+
+    let mut layouts = Vec::new();
+    for member in members {
+        // Arrays are described by their element type, with the length and
+        // stride added on.
+        let (element, array) = match module.types[member.ty].inner {
+            TypeInner::Array {
+                base,
+                size: ArraySize::Constant(len),
+                stride,
+            } => (base, Some((len.get() as usize, stride as usize))),
+            // Runtime-sized arrays have no place in a Rust struct.
+            TypeInner::Array {
+                size: ArraySize::Dynamic,
+                ..
+            } => continue,
+            _ => (member.ty, None),
+        };
+
+        let element_name = type_name(module, element);
+        let (ty, array_stride) = match array {
+            Some((len, stride)) => (array_type_name(&element_name, len), Some(stride)),
+            None => (element_name, None),
+        };
+
+        let matrix_stride = match module.types[element].inner {
+            TypeInner::Matrix { columns, .. } => {
+                Some(layouter[element].size as usize / columns as usize)
+            }
+            _ => None,
+        };
+
+        layouts.push(MemberLayout {
+            name: member.name.clone().unwrap_or_default(),
+            ty,
+            offset: member.offset as usize,
+            size: Some(layouter[member.ty].size as usize),
+            array_stride,
+            matrix_stride,
+            fields: struct_members(module, layouter, element),
+        });
+    }
+
+    layouts
+}
+
+/// The GLSL name of a naga type, like `vec3` or `mat4x3`.
+fn type_name(module: &Module, ty: Handle<Type>) -> String {
+    match module.types[ty].inner {
+        TypeInner::Scalar(scalar) => scalar_name(scalar),
+        TypeInner::Vector { size, scalar } => {
+            format!("{}vec{}", vector_prefix(scalar), size as u8)
+        }
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } => {
+            if columns == rows {
+                format!("{}mat{}", vector_prefix(scalar), columns as u8)
+            } else {
+                format!(
+                    "{}mat{}x{}",
+                    vector_prefix(scalar),
+                    columns as u8,
+                    rows as u8
+                )
+            }
+        }
+        TypeInner::Array {
+            base,
+            size: ArraySize::Constant(len),
+            ..
+        } => array_type_name(&type_name(module, base), len.get() as usize),
+        _ => module.types[ty].name.clone().unwrap_or_default(),
+    }
+}
+
+fn scalar_name(scalar: Scalar) -> String {
+    match (scalar.kind, scalar.width) {
+        (ScalarKind::Float, 4) => "float".to_owned(),
+        (ScalarKind::Float, 8) => "double".to_owned(),
+        (ScalarKind::Sint, 4) => "int".to_owned(),
+        (ScalarKind::Uint, 4) => "uint".to_owned(),
+        (ScalarKind::Bool, _) => "bool".to_owned(),
+        (ScalarKind::Float, width) => format!("float{}_t", width * 8),
+        (ScalarKind::Sint, width) => format!("int{}_t", width * 8),
+        (ScalarKind::Uint, width) => format!("uint{}_t", width * 8),
+        (ScalarKind::AbstractInt | ScalarKind::AbstractFloat, _) => "abstract".to_owned(),
+    }
+}
+
+/// The prefix of GLSL vector and matrix types with the given component type,
+/// like `d` for `dvec3`.
+fn vector_prefix(scalar: Scalar) -> String {
+    match (scalar.kind, scalar.width) {
+        (ScalarKind::Float, 4) => String::new(),
+        (ScalarKind::Float, 8) => "d".to_owned(),
+        (ScalarKind::Sint, 4) => "i".to_owned(),
+        (ScalarKind::Uint, 4) => "u".to_owned(),
+        (ScalarKind::Bool, _) => "b".to_owned(),
+        (ScalarKind::Float, width) => format!("f{}", width * 8),
+        (ScalarKind::Sint, width) => format!("i{}", width * 8),
+        (ScalarKind::Uint, width) => format!("u{}", width * 8),
+        (ScalarKind::AbstractInt | ScalarKind::AbstractFloat, _) => "abstract".to_owned(),
+    }
+}
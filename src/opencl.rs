@@ -0,0 +1,56 @@
+/*!
+Defines traits and types for working with data laid out as in OpenCL C
+kernels.
+
+OpenCL C follows C alignment rules: structs are aligned to their most aligned
+member and padded out to a multiple of that alignment. Three-component vectors
+like `float3` have the size and alignment of their four-component
+counterparts. CUDA's `float3` is only 12 bytes with 4-byte alignment, so this
+layout doesn't match CUDA kernels. OpenCL C has no matrix types and does not
+allow `bool` in kernel arguments, so neither are supported.
+
+Types deriving [`AsOpenCl`] can also generate the matching `typedef struct`
+declaration in OpenCL C through [`OpenClStruct`].
+
+## Examples
+Given this struct:
+*/
+#![cfg_attr(
+    feature = "std",
+    doc = r##"
+```rust
+use crevice::opencl::{AsOpenCl, OpenClStruct};
+
+#[derive(AsOpenCl)]
+struct Particle {
+    position: mint::Vector3<f32>,
+    mass: f32,
+}
+
+println!("{}", <Particle as AsOpenCl>::Output::opencl_definition());
+```
+"##
+)]
+/*!
+The output will be:
+```c
+typedef struct {
+    float3 position;
+    float mass;
+} Particle;
+```
+*/
+
+mod primitives;
+mod sizer;
+mod traits;
+#[cfg(feature = "std")]
+mod writer;
+
+pub use self::primitives::*;
+pub use self::sizer::*;
+pub use self::traits::*;
+#[cfg(feature = "std")]
+pub use self::writer::*;
+
+pub use crevice_derive::AsOpenCl;
@@ -0,0 +1,144 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::glsl::Glsl;
+use crate::internal::array_alignment;
+use crate::layout::{self, ArrayElement, LayoutValue};
+use crate::opencl::{AsOpenCl, OpenCl, OpenClArray, OpenClType};
+use crate::reflect::{LayoutInfo, Reflect};
+
+macro_rules! scalars {
+    ( $( $name:ident $opencl_name:ident align($align:literal), )+ ) => {
+        $(
+            unsafe impl OpenCl for $name {
+                const ALIGNMENT: usize = $align;
+            }
+
+            unsafe impl OpenClType for $name {
+                const NAME: &'static str = stringify!($opencl_name);
+            }
+        )+
+    };
+}
+
+scalars! {
+    f32 float align(4),
+    f64 double align(8),
+    i32 int align(4),
+    u32 uint align(4),
+}
+
+macro_rules! vectors {
+    (
+        $(
+            #[$doc:meta] align($align:literal) $opencl_name:ident $glsl_name:ident $name:ident <$prim:ident> ($($field:ident),+) $(pad $pad_ty:ty)?
+        )+
+    ) => {
+        $(
+            #[$doc]
+            #[allow(missing_docs)]
+            #[derive(Debug, Clone, Copy, PartialEq)]
+            #[repr(C)]
+            pub struct $name {
+                $(pub $field: $prim,)+
+                $(pub _pad: $pad_ty,)?
+            }
+
+            unsafe impl Zeroable for $name {}
+            unsafe impl Pod for $name {}
+
+            unsafe impl OpenCl for $name {
+                const ALIGNMENT: usize = $align;
+            }
+
+            unsafe impl OpenClType for $name {
+                const NAME: &'static str = stringify!($opencl_name);
+            }
+
+            unsafe impl Glsl for $name {
+                const NAME: &'static str = stringify!($glsl_name);
+            }
+
+            unsafe impl Reflect for $name {
+                const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo::vector(
+                    stringify!($glsl_name),
+                    core::mem::size_of::<$name>(),
+                    $align,
+                    [$(stringify!($field)),+].len() * core::mem::size_of::<$prim>(),
+                );
+            }
+        )+
+    };
+}
+
+vectors! {
+    #[doc = "Corresponds to an OpenCL C `float2`."] align(8) float2 vec2 Vec2<f32>(x, y)
+    #[doc = "Corresponds to an OpenCL C `float3`, which is padded out to 16 bytes."] align(16) float3 vec3 Vec3<f32>(x, y, z) pad f32
+    #[doc = "Corresponds to an OpenCL C `float4`."] align(16) float4 vec4 Vec4<f32>(x, y, z, w)
+
+    #[doc = "Corresponds to an OpenCL C `int2`."] align(8) int2 ivec2 IVec2<i32>(x, y)
+    #[doc = "Corresponds to an OpenCL C `int3`, which is padded out to 16 bytes."] align(16) int3 ivec3 IVec3<i32>(x, y, z) pad i32
+    #[doc = "Corresponds to an OpenCL C `int4`."] align(16) int4 ivec4 IVec4<i32>(x, y, z, w)
+
+    #[doc = "Corresponds to an OpenCL C `uint2`."] align(8) uint2 uvec2 UVec2<u32>(x, y)
+    #[doc = "Corresponds to an OpenCL C `uint3`, which is padded out to 16 bytes."] align(16) uint3 uvec3 UVec3<u32>(x, y, z) pad u32
+    #[doc = "Corresponds to an OpenCL C `uint4`."] align(16) uint4 uvec4 UVec4<u32>(x, y, z, w)
+
+    #[doc = "Corresponds to an OpenCL C `double2`."] align(16) double2 dvec2 DVec2<f64>(x, y)
+    #[doc = "Corresponds to an OpenCL C `double3`, which is padded out to 32 bytes."] align(32) double3 dvec3 DVec3<f64>(x, y, z) pad f64
+    #[doc = "Corresponds to an OpenCL C `double4`."] align(32) double4 dvec4 DVec4<f64>(x, y, z, w)
+}
+
+array_elements!(layout::OpenCl {
+    f32,
+    f64,
+    i32,
+    u32,
+    Vec2,
+    Vec3,
+    Vec4,
+    IVec2,
+    IVec3,
+    IVec4,
+    UVec2,
+    UVec3,
+    UVec4,
+    DVec2,
+    DVec3,
+    DVec4,
+});
+
+/// Corresponds to an OpenCL C array.
+///
+/// Every OpenCL C type's size is a multiple of its alignment, so elements are
+/// stored without padding between them. See [`layout::Array`].
+pub type Array<T, const N: usize> = layout::Array<layout::OpenCl, T, N>;
+
+unsafe impl<T: ArrayElement<layout::OpenCl>, const N: usize> OpenCl for Array<T, N> {
+    const ALIGNMENT: usize =
+        array_alignment::<layout::OpenCl>(<T as LayoutValue<layout::OpenCl>>::ALIGNMENT);
+}
+
+unsafe impl<T: ArrayElement<layout::OpenCl> + OpenClType, const N: usize> OpenClType
+    for Array<T, N>
+{
+    const NAME: &'static str = T::NAME;
+    const ARRAY: Option<&'static OpenClArray> = Some(&OpenClArray {
+        len: N,
+        element: T::ARRAY,
+    });
+}
+
+impl<T: AsOpenCl, const N: usize> AsOpenCl for [T; N]
+where
+    T::Output: ArrayElement<layout::OpenCl>,
+{
+    type Output = Array<T::Output, N>;
+
+    fn as_opencl(&self) -> Self::Output {
+        Array::new(self.each_ref().map(T::as_opencl))
+    }
+
+    fn from_opencl(value: Self::Output) -> Self {
+        value.to_array().map(T::from_opencl)
+    }
+}
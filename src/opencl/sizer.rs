@@ -0,0 +1,43 @@
+use crate::layout;
+
+/**
+Type that computes the buffer size needed by a series of OpenCL C types laid
+out.
+
+This type works well well when paired with `Writer`, precomputing a buffer's
+size to alleviate the need to dynamically re-allocate buffers.
+
+`Sizer` is an alias of the generic [`layout::Sizer`] using the
+[`layout::OpenCl`] rules.
+
+## Example
+
+```c
+typedef struct {
+    float3 size;
+    float frobiness;
+} Frob;
+```
+
+```
+use crevice::opencl::{self, AsOpenCl};
+
+#[derive(AsOpenCl)]
+struct Frob {
+    size: mint::Vector3<f32>,
+    frobiness: f32,
+}
+
+// Many APIs require that buffers contain at least enough space for all
+// fixed-size bindiongs to a buffer as well as one element of any arrays, if
+// there are any.
+let mut sizer = opencl::Sizer::new();
+sizer.add::<u32>();
+sizer.add::<Frob>();
+
+# fn create_buffer_with_size(size: usize) {}
+let buffer = create_buffer_with_size(sizer.len());
+# assert_eq!(sizer.len(), 48);
+```
+*/
+pub type Sizer = layout::Sizer<layout::OpenCl>;
@@ -0,0 +1,293 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+use core::mem::size_of;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use bytemuck::{bytes_of, Pod, Zeroable};
+
+#[cfg(feature = "std")]
+use crate::layout::WriteLayout;
+use crate::layout::{self, AsLayout, LayoutValue};
+#[cfg(feature = "std")]
+use crate::opencl::Writer;
+
+/// Trait implemented for all OpenCL C primitives. Generally should not be
+/// implemented outside this crate.
+///
+/// # Safety
+/// Implementors must not contain any uninitialized padding bytes, and their
+/// size and `ALIGNMENT` must match the OpenCL C layout of the type they represent.
+pub unsafe trait OpenCl: Copy + Zeroable + Pod {
+    /// The required alignment of the type. Must be a power of two.
+    ///
+    /// This is distinct from the value returned by `std::mem::align_of` because
+    /// `AsOpenCl` structs do not use Rust's alignment. This enables them to
+    /// control and zero their padding bytes, making converting them to and from
+    /// slices safe.
+    const ALIGNMENT: usize;
+
+    /// Casts the type to a byte array. Implementors should not override this
+    /// method.
+    ///
+    /// # Safety
+    /// This is always safe due to the requirements of [`bytemuck::Pod`] being a
+    /// prerequisite for this trait.
+    fn as_bytes(&self) -> &[u8] {
+        bytes_of(self)
+    }
+}
+
+/**
+Trait implemented for all types that can be turned into OpenCL C values.
+
+This trait can often be `#[derive]`'d instead of manually implementing it. Any
+struct which contains only fields that also implement `AsOpenCl` can derive
+`AsOpenCl`.
+
+Types from the mint crate implement `AsOpenCl`, making them convenient for use
+in kernel arguments. Most Rust geometry crates, like cgmath, nalgebra, and
+ultraviolet support mint.
+
+## Example
+
+```c
+typedef struct {
+    float4 origin;
+    float4 direction;
+} Ray;
+```
+
+```no_run
+use crevice::opencl::AsOpenCl;
+
+#[derive(AsOpenCl)]
+struct Ray {
+    origin: mint::Vector4<f32>,
+    direction: mint::Vector4<f32>,
+}
+
+let origin: mint::Vector4<f32> = todo!("your math code here");
+let direction: mint::Vector4<f32> = todo!("your math code here");
+
+let ray = Ray {
+    origin,
+    direction,
+};
+
+# fn write_to_gpu_buffer(bytes: &[u8]) {}
+let ray_opencl = ray.as_opencl();
+write_to_gpu_buffer(ray_opencl.as_bytes());
+```
+*/
+pub trait AsOpenCl {
+    /// The OpenCL C version of this value.
+    type Output: OpenCl;
+
+    /// Convert this value into the OpenCL C version of itself.
+    fn as_opencl(&self) -> Self::Output;
+
+    /// Returns the size of the OpenCL C version of this type. Useful for
+    /// pre-sizing buffers.
+    fn opencl_size_static() -> usize {
+        size_of::<Self::Output>()
+    }
+
+    /// Converts from OpenCL C version of self to self.
+    fn from_opencl(value: Self::Output) -> Self;
+}
+
+impl<T> AsOpenCl for T
+where
+    T: OpenCl,
+{
+    type Output = Self;
+
+    fn as_opencl(&self) -> Self {
+        *self
+    }
+
+    fn from_opencl(value: Self) -> Self {
+        value
+    }
+}
+
+/// Trait implemented for all types that can be written into a buffer as
+/// OpenCL C bytes. This type is more general than [`AsOpenCl`]: all `AsOpenCl`
+/// types implement `WriteOpenCl`, but not the other way around.
+///
+/// While `AsOpenCl` requires implementers to return a type that implements the
+/// `OpenCl` trait, `WriteOpenCl` directly writes bytes using a [`Writer`]. This
+/// makes `WriteOpenCl` usable for writing slices or other DSTs that could not
+/// implement `AsOpenCl` without allocating new memory on the heap.
+///
+/// Every type implementing `WriteOpenCl` also implements
+/// [`WriteLayout<layout::OpenCl>`][WriteLayout], so it can be written with
+/// [`Writer::write`].
+#[cfg(feature = "std")]
+pub trait WriteOpenCl {
+    /// Writes this value into the given [`Writer`] using OpenCL C layout rules.
+    ///
+    /// Should return the offset of the first byte of this type, as returned by
+    /// the first call to [`Writer::write`].
+    fn write_opencl<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize>;
+
+    /// The space required to write this value using OpenCL C layout rules. This
+    /// does not include alignment padding that may be needed before or after
+    /// this type when written as part of a larger buffer.
+    fn opencl_size(&self) -> usize {
+        let mut writer = Writer::new(io::sink());
+        self.write_opencl(&mut writer).unwrap();
+        writer.len()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteOpenCl for T
+where
+    T: AsOpenCl,
+{
+    fn write_opencl<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        writer.write_value(&self.as_opencl())
+    }
+
+    fn opencl_size(&self) -> usize {
+        size_of::<<Self as AsOpenCl>::Output>()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteOpenCl for [T]
+where
+    T: WriteOpenCl,
+{
+    fn write_opencl<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        self.write_layout(writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteLayout<layout::OpenCl> for T
+where
+    T: WriteOpenCl,
+{
+    fn write_layout<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        self.write_opencl(writer)
+    }
+
+    fn layout_size(&self) -> usize {
+        self.opencl_size()
+    }
+}
+
+unsafe impl<T> LayoutValue<layout::OpenCl> for T
+where
+    T: OpenCl,
+{
+    const ALIGNMENT: usize = <T as OpenCl>::ALIGNMENT;
+}
+
+impl<T> AsLayout<layout::OpenCl> for T
+where
+    T: AsOpenCl,
+{
+    type Output = <T as AsOpenCl>::Output;
+
+    fn as_layout(&self) -> Self::Output {
+        self.as_opencl()
+    }
+
+    fn from_layout(value: Self::Output) -> Self {
+        <T as AsOpenCl>::from_opencl(value)
+    }
+}
+
+/// Trait for types that have an OpenCL C equivalent. Useful for generating
+/// OpenCL C code from Rust structs.
+///
+/// # Safety
+/// `NAME` must name an OpenCL C type with the same memory representation as the
+/// implementing type, or as its elements if `ARRAY` is present.
+pub unsafe trait OpenClType: OpenCl {
+    /// The name of this type in OpenCL C, like `float3` or `uint`.
+    const NAME: &'static str;
+
+    /// The dimensions of this type if it is an array, which is `None`
+    /// otherwise. Arrays are named after their element type, so
+    /// `opencl::Array<f32, 4>` has the `NAME` `float` and is declared as
+    /// `float name[4]`.
+    const ARRAY: Option<&'static OpenClArray> = None;
+}
+
+/// The dimensions of an OpenCL C array, like the `[4]` in `float weights[4]`.
+#[derive(Debug, Clone, Copy)]
+pub struct OpenClArray {
+    /// The number of elements in the array.
+    pub len: usize,
+
+    /// The dimensions of the array's elements if they are arrays themselves.
+    pub element: Option<&'static OpenClArray>,
+}
+
+impl OpenClArray {
+    /// Writes the array's dimensions as they follow a declaration's name, like
+    /// `[4][2]`.
+    pub fn write_suffix(array: Option<&OpenClArray>, output: &mut dyn fmt::Write) -> fmt::Result {
+        let mut array = array;
+        while let Some(dimension) = array {
+            write!(output, "[{}]", dimension.len)?;
+            array = dimension.element;
+        }
+        Ok(())
+    }
+}
+
+/// A field contained within an OpenCL C struct definition.
+#[derive(Debug, Clone, Copy)]
+pub struct OpenClField {
+    /// The type of the field, like `float2` or `int`.
+    pub ty: &'static str,
+
+    /// The field's name. This must be a valid OpenCL C identifier.
+    pub name: &'static str,
+
+    /// The dimensions of the field if it is an array.
+    pub array: Option<&'static OpenClArray>,
+}
+
+/// Trait for the OpenCL C versions of structs deriving [`AsOpenCl`], which can
+/// be declared as a `typedef struct` in OpenCL C.
+///
+/// This trait should not be implemented by hand; it is implemented by
+/// `#[derive(AsOpenCl)]`.
+///
+/// # Safety
+/// `FIELDS` must list every non-padding field of the struct, in the order they
+/// are laid out in memory.
+pub unsafe trait OpenClStruct: OpenClType {
+    /// The fields contained in this struct.
+    const FIELDS: &'static [OpenClField];
+
+    /// Generates an OpenCL C `typedef struct` declaration that represents this
+    /// struct and its fields.
+    #[cfg(feature = "alloc")]
+    fn opencl_definition() -> String {
+        let mut output = String::new();
+        output.push_str("typedef struct {\n");
+
+        for field in Self::FIELDS {
+            output.push('\t');
+            output.push_str(field.ty);
+            output.push(' ');
+            output.push_str(field.name);
+            OpenClArray::write_suffix(field.array, &mut output).unwrap();
+            output.push_str(";\n");
+        }
+
+        output.push_str("} ");
+        output.push_str(Self::NAME);
+        output.push(';');
+        output
+    }
+}
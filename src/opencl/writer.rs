@@ -0,0 +1,85 @@
+use std::io::{self, Write};
+
+use crate::layout;
+use crate::opencl::OpenCl;
+
+/**
+Type that enables writing correctly aligned OpenCL C values to a buffer.
+
+`Writer` is useful when many values need to be laid out in a row that cannot be
+represented by a struct alone, like dynamically sized arrays or dynamically
+laid-out values.
+
+`Writer` is an alias of the generic [`layout::Writer`] using the
+[`layout::OpenCl`] rules.
+
+## Example
+In this example, we'll write a length-prefixed list of lights to a buffer.
+`opencl::Writer` helps align correctly, even across multiple structs, which can
+be tricky and error-prone otherwise.
+
+```c
+typedef struct {
+    float3 position;
+    float3 color;
+    float brightness;
+} PointLight;
+
+__kernel void shade(__global const uint* len, __global const PointLight* lights);
+```
+
+```
+use crevice::opencl::{self, AsOpenCl};
+
+#[derive(AsOpenCl)]
+struct PointLight {
+    position: mint::Point3<f32>,
+    color: mint::Vector3<f32>,
+    brightness: f32,
+}
+
+let lights = vec![
+    PointLight {
+        position: [0.0, 1.0, 0.0].into(),
+        color: [1.0, 0.0, 0.0].into(),
+        brightness: 0.6,
+    },
+    PointLight {
+        position: [0.0, 4.0, 3.0].into(),
+        color: [1.0, 1.0, 1.0].into(),
+        brightness: 1.0,
+    },
+];
+
+# fn map_gpu_buffer_for_write() -> &'static mut [u8] {
+#     Box::leak(vec![0; 1024].into_boxed_slice())
+# }
+let target_buffer = map_gpu_buffer_for_write();
+let mut writer = opencl::Writer::new(target_buffer);
+
+let light_count = lights.len() as u32;
+writer.write(&light_count)?;
+
+// Crevice will automatically insert the required padding to align the
+// PointLight structure correctly. In this case, there will be 12 bytes of
+// padding between the length field and the light list.
+
+writer.write(lights.as_slice())?;
+
+# fn unmap_gpu_buffer() {}
+unmap_gpu_buffer();
+
+# Ok::<(), std::io::Error>(())
+```
+*/
+pub type Writer<W> = layout::Writer<layout::OpenCl, W>;
+
+impl<W: Write> Writer<W> {
+    /// Write an OpenCL C type to the underlying buffer.
+    pub fn write_opencl<T>(&mut self, value: &T) -> io::Result<usize>
+    where
+        T: OpenCl,
+    {
+        self.write_value(value)
+    }
+}
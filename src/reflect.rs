@@ -0,0 +1,470 @@
+/*!
+Defines types for inspecting the layout of types at compile time.
+
+Every type produced by Crevice's layout derives, like
+`<T as AsStd140>::Output`, implements [`Reflect`], as do all layout primitives.
+[`Reflect::LAYOUT_INFO`] describes the size and alignment of the type as well as
+the offset, size, and type of each of its fields. All of this information is
+available in `const` contexts and without `std`.
+
+## Example
+
+```rust
+use crevice::reflect::Reflect;
+use crevice::std140::AsStd140;
+
+#[derive(AsStd140)]
+struct Light {
+    position: mint::Vector3<f32>,
+    intensity: f32,
+    color: mint::Vector3<f32>,
+}
+
+const INFO: &crevice::reflect::LayoutInfo = <Light as AsStd140>::Output::LAYOUT_INFO;
+
+assert_eq!(INFO.name, "Light");
+assert_eq!(INFO.size, 32);
+assert_eq!(INFO.alignment, 16);
+
+let color = &INFO.fields[2];
+assert_eq!(color.name, "color");
+assert_eq!(color.offset, 16);
+assert_eq!(color.size, 12);
+assert_eq!(color.glsl_type, "vec3");
+```
+*/
+
+use crate::bool::Bool;
+
+/// Describes the layout of a type under a specific set of layout rules.
+#[derive(Debug)]
+pub struct LayoutInfo {
+    /// The name of the type. For primitives, this is the GLSL name of the type,
+    /// like `vec3`. For structs, this is the name of the Rust struct. For
+    /// arrays, this is the name of the element type.
+    pub name: &'static str,
+
+    /// The size of the type in bytes, including any padding.
+    pub size: usize,
+
+    /// The required alignment of the type in bytes.
+    pub alignment: usize,
+
+    /// The fields contained in this type, in declaration order. This is empty
+    /// for primitives and arrays.
+    pub fields: &'static [FieldInfo],
+
+    /// If this type is an array, describes its elements.
+    pub array: Option<ArrayInfo>,
+
+    /// If this type is a matrix, or a vector padded out past its components,
+    /// describes its columns. A padded vector has a single column. This is
+    /// `None` for other primitives, whose bytes are all used.
+    pub columns: Option<ColumnInfo>,
+}
+
+impl LayoutInfo {
+    /// Creates a `LayoutInfo` for a type without any fields.
+    pub const fn primitive(name: &'static str, size: usize, alignment: usize) -> Self {
+        Self {
+            name,
+            size,
+            alignment,
+            fields: &[],
+            array: None,
+            columns: None,
+        }
+    }
+
+    /// Creates a `LayoutInfo` for a vector whose components take up `used` of
+    /// its `size` bytes, with the rest being padding.
+    pub const fn vector(name: &'static str, size: usize, alignment: usize, used: usize) -> Self {
+        let columns = if used < size {
+            Some(ColumnInfo {
+                count: 1,
+                size: used,
+                stride: size,
+            })
+        } else {
+            None
+        };
+
+        Self {
+            columns,
+            ..Self::primitive(name, size, alignment)
+        }
+    }
+
+    /// Creates a `LayoutInfo` for a matrix made of columns of type `column`,
+    /// each starting `stride` bytes after the last. The last column may be left
+    /// unpadded.
+    pub const fn matrix(
+        name: &'static str,
+        size: usize,
+        alignment: usize,
+        column: &LayoutInfo,
+        stride: usize,
+    ) -> Self {
+        let column_size = match &column.columns {
+            Some(columns) => columns.size,
+            None => column.size,
+        };
+
+        Self {
+            columns: Some(ColumnInfo {
+                count: size.div_ceil(stride),
+                size: column_size,
+                stride,
+            }),
+            ..Self::primitive(name, size, alignment)
+        }
+    }
+
+    /// Finds the member of this type with the given path, like `lights[3].color`.
+    ///
+    /// Paths are made up of field names separated by `.` and array indices in
+    /// square brackets. Returns `None` if the path does not name a member of
+    /// this type or an array index is out of bounds.
+    pub const fn find(&'static self, path: &str) -> Option<MemberInfo> {
+        let path = path.as_bytes();
+
+        let mut layout = self;
+        let mut offset = 0;
+        let mut size = self.size;
+
+        let mut i = skip_whitespace(path, 0);
+        if i == path.len() {
+            return None;
+        }
+
+        let mut first = true;
+        while i < path.len() {
+            if path[i] == b'[' {
+                let array = match layout.array {
+                    Some(array) => array,
+                    None => return None,
+                };
+
+                let (index, end) = match parse_index(path, skip_whitespace(path, i + 1)) {
+                    Some(parsed) => parsed,
+                    None => return None,
+                };
+
+                i = skip_whitespace(path, end);
+                if i == path.len() || path[i] != b']' || index >= array.len {
+                    return None;
+                }
+                i += 1;
+
+                offset += index * array.stride;
+                size = array.element.size;
+                layout = array.element;
+            } else {
+                if !first {
+                    if path[i] != b'.' {
+                        return None;
+                    }
+                    i = skip_whitespace(path, i + 1);
+                }
+
+                let start = i;
+                while i < path.len() && is_identifier_byte(path[i]) {
+                    i += 1;
+                }
+
+                let field = match find_field(layout.fields, path, start, i) {
+                    Some(field) => field,
+                    None => return None,
+                };
+
+                offset += field.offset;
+                size = field.size;
+                layout = field.layout;
+            }
+
+            first = false;
+            i = skip_whitespace(path, i);
+        }
+
+        Some(MemberInfo {
+            offset,
+            size,
+            layout,
+        })
+    }
+}
+
+/// Describes the elements of an array.
+#[derive(Debug, Clone, Copy)]
+pub struct ArrayInfo {
+    /// The number of elements in the array.
+    pub len: usize,
+
+    /// The distance between the start of consecutive elements, in bytes.
+    pub stride: usize,
+
+    /// The layout of each element.
+    pub element: &'static LayoutInfo,
+}
+
+/// Describes the columns of a matrix or padded vector. Bytes after each
+/// column's components, up to the start of the next column, are padding.
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnInfo {
+    /// The number of columns.
+    pub count: usize,
+
+    /// The size of each column's components in bytes, not counting padding.
+    pub size: usize,
+
+    /// The distance between the start of consecutive columns, in bytes.
+    pub stride: usize,
+}
+
+/// Describes a member of a type found by [`LayoutInfo::find`] or
+/// [`offset_of_path`].
+#[derive(Debug, Clone, Copy)]
+pub struct MemberInfo {
+    /// The offset of the member from the start of the outermost type, in bytes.
+    pub offset: usize,
+
+    /// The size of the member in bytes.
+    pub size: usize,
+
+    /// The layout of the member's type.
+    pub layout: &'static LayoutInfo,
+}
+
+/// Finds the offset and size of a nested member of `T` from a path like
+/// `lights[3].color`. See [`LayoutInfo::find`] for details.
+///
+/// `T` is a type produced by one of Crevice's layout derives, like
+/// `<Scene as AsStd140>::Output`. The [`std140_offset_of`][crate::std140_offset_of]
+/// and [`std430_offset_of`][crate::std430_offset_of] macros check paths at compile time
+/// instead.
+///
+/// ```rust
+/// use crevice::reflect::offset_of_path;
+/// use crevice::std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Light {
+///     position: mint::Vector3<f32>,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// #[derive(AsStd140)]
+/// struct Scene {
+///     ambient: f32,
+///     lights: [Light; 4],
+/// }
+///
+/// type SceneStd140 = <Scene as AsStd140>::Output;
+///
+/// let color = offset_of_path::<SceneStd140>("lights[3].color").unwrap();
+/// assert_eq!(color.offset, 16 + 3 * 32 + 16);
+/// assert_eq!(color.size, 12);
+///
+/// assert!(offset_of_path::<SceneStd140>("lights[4].color").is_none());
+/// assert!(offset_of_path::<SceneStd140>("ambient.x").is_none());
+/// ```
+pub fn offset_of_path<T: Reflect + ?Sized>(path: &str) -> Option<MemberInfo> {
+    T::LAYOUT_INFO.find(path)
+}
+
+/// Finds the offset and size of a nested member of a type's `std140` version,
+/// like `std140_offset_of!(Scene, lights[3].color)`.
+///
+/// Evaluates to a [`MemberInfo`][crate::reflect::MemberInfo] in a `const`
+/// context. Invalid paths fail to compile.
+///
+/// ```rust
+/// use crevice::std140::AsStd140;
+///
+/// #[derive(AsStd140)]
+/// struct Light {
+///     position: mint::Vector3<f32>,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// #[derive(AsStd140)]
+/// struct Scene {
+///     ambient: f32,
+///     lights: [Light; 4],
+/// }
+///
+/// const COLOR_OFFSET: usize = crevice::std140_offset_of!(Scene, lights[3].color).offset;
+/// assert_eq!(COLOR_OFFSET, 128);
+/// ```
+///
+/// ```compile_fail
+/// # use crevice::std140::AsStd140;
+/// # #[derive(AsStd140)]
+/// # struct Scene {
+/// #     ambient: f32,
+/// # }
+/// let offset = crevice::std140_offset_of!(Scene, missing).offset;
+/// ```
+#[macro_export]
+macro_rules! std140_offset_of {
+    ($ty:ty, $($path:tt)+) => {
+        $crate::__offset_of_path!(
+            <<$ty as $crate::std140::AsStd140>::Output as $crate::reflect::Reflect>::LAYOUT_INFO,
+            $ty,
+            $($path)+
+        )
+    };
+}
+
+/// Finds the offset and size of a nested member of a type's `std430` version,
+/// like `std430_offset_of!(Scene, lights[3].color)`.
+///
+/// Evaluates to a [`MemberInfo`][crate::reflect::MemberInfo] in a `const`
+/// context. Invalid paths fail to compile.
+#[macro_export]
+macro_rules! std430_offset_of {
+    ($ty:ty, $($path:tt)+) => {
+        $crate::__offset_of_path!(
+            <<$ty as $crate::std430::AsStd430>::Output as $crate::reflect::Reflect>::LAYOUT_INFO,
+            $ty,
+            $($path)+
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __offset_of_path {
+    ($layout:expr, $ty:ty, $($path:tt)+) => {{
+        const MEMBER: $crate::reflect::MemberInfo = match $layout.find(stringify!($($path)+)) {
+            Some(member) => member,
+            None => panic!(concat!(
+                "`",
+                stringify!($($path)+),
+                "` is not a member of `",
+                stringify!($ty),
+                "`"
+            )),
+        };
+
+        MEMBER
+    }};
+}
+
+const fn skip_whitespace(path: &[u8], mut i: usize) -> usize {
+    while i < path.len() && path[i].is_ascii_whitespace() {
+        i += 1;
+    }
+
+    i
+}
+
+const fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+const fn parse_index(path: &[u8], mut i: usize) -> Option<(usize, usize)> {
+    let start = i;
+    let mut index = 0usize;
+
+    while i < path.len() && path[i].is_ascii_digit() {
+        index = match index.checked_mul(10) {
+            Some(index) => index,
+            None => return None,
+        };
+        index = match index.checked_add((path[i] - b'0') as usize) {
+            Some(index) => index,
+            None => return None,
+        };
+        i += 1;
+    }
+
+    if i == start {
+        None
+    } else {
+        Some((index, i))
+    }
+}
+
+const fn find_field(
+    fields: &'static [FieldInfo],
+    path: &[u8],
+    start: usize,
+    end: usize,
+) -> Option<&'static FieldInfo> {
+    let mut index = 0;
+
+    while index < fields.len() {
+        let name = fields[index].name.as_bytes();
+
+        if name.len() == end - start {
+            let mut i = 0;
+            while i < name.len() && name[i] == path[start + i] {
+                i += 1;
+            }
+
+            if i == name.len() {
+                return Some(&fields[index]);
+            }
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+/// Describes a single field of a struct.
+#[derive(Debug)]
+pub struct FieldInfo {
+    /// The field's name.
+    pub name: &'static str,
+
+    /// The offset of the field from the start of the struct, in bytes.
+    pub offset: usize,
+
+    /// The size of the field in bytes.
+    pub size: usize,
+
+    /// The GLSL type of the field, like `vec2` or the name of a struct.
+    pub glsl_type: &'static str,
+
+    /// The layout of the field's type.
+    pub layout: &'static LayoutInfo,
+}
+
+/// Trait for types whose layout can be inspected at compile time.
+///
+/// This trait is implemented for all layout primitives and is implemented by
+/// Crevice's layout derives for the types they generate.
+///
+/// # Safety
+/// `LAYOUT_INFO` must accurately describe the size, alignment, and field
+/// offsets of the implementing type.
+pub unsafe trait Reflect {
+    /// The layout of this type.
+    const LAYOUT_INFO: &'static LayoutInfo;
+}
+
+macro_rules! scalars {
+    ( $( $ty:ty => $glsl_name:literal align($align:literal), )* ) => {
+        $(
+            unsafe impl Reflect for $ty {
+                const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo::primitive(
+                    $glsl_name,
+                    core::mem::size_of::<$ty>(),
+                    $align,
+                );
+            }
+        )*
+    };
+}
+
+scalars! {
+    f32 => "float" align(4),
+    f64 => "double" align(8),
+    i32 => "int" align(4),
+    u32 => "uint" align(4),
+    Bool => "bool" align(4),
+    crate::msl::Bool => "bool" align(1),
+}
@@ -0,0 +1,436 @@
+/*!
+Defines human-readable reports describing the layout of a type, including
+every run of padding bytes.
+
+Reports can be rendered as a plain text table, an ASCII byte map, or a
+Markdown table for design documents. They're built from a type's
+[`LayoutInfo`], so any type implementing [`Reflect`] can be reported on,
+including every type produced by Crevice's layout derives.
+
+## Example
+
+```rust
+use crevice::report::LayoutReport;
+use crevice::std140::AsStd140;
+
+#[derive(AsStd140)]
+struct PointLight {
+    position: mint::Vector3<f32>,
+    color: mint::Vector3<f32>,
+    brightness: f32,
+}
+
+let report = LayoutReport::std140::<PointLight>();
+assert_eq!(report.wasted_bytes(), 4);
+
+println!("{}", report.to_table());
+println!("{}", report.to_byte_map());
+```
+
+The table and byte map look like this:
+
+```text
+PointLight: size 32, alignment 16
+offset  size  align  field
+     0    12     16  position: vec3
+    12     4         (padding)
+    16    12     16  color: vec3
+    28     4      4  brightness: float
+wasted: 4 of 32 bytes
+
+     0  AAAAAAAAAAAA....
+    16  BBBBBBBBBBBBCCCC
+A = position, B = color, C = brightness, . = padding
+```
+*/
+
+use alloc::{borrow::ToOwned, format, string::String, vec, vec::Vec};
+use core::fmt::{self, Write};
+
+use crate::reflect::{LayoutInfo, Reflect};
+use crate::std140::AsStd140;
+use crate::std430::AsStd430;
+
+/// The number of bytes shown on each row of a byte map.
+const BYTES_PER_ROW: usize = 16;
+
+/// The symbols used to mark each field in a byte map, in order.
+const FIELD_SYMBOLS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// A report describing the layout of a type's fields and padding.
+#[derive(Debug, Clone)]
+pub struct LayoutReport {
+    info: &'static LayoutInfo,
+    entries: Vec<ReportEntry>,
+    wasted_bytes: usize,
+}
+
+/// A run of bytes within a [`LayoutReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportEntry {
+    /// A field of the type.
+    Field {
+        /// The name of the field.
+        name: &'static str,
+
+        /// The shader type of the field, like `vec3` or `Light[4]`.
+        ty: String,
+
+        /// The offset of the field in bytes.
+        offset: usize,
+
+        /// The size of the field in bytes.
+        size: usize,
+
+        /// The required alignment of the field in bytes.
+        alignment: usize,
+    },
+
+    /// Padding inserted between fields or at the end of the type.
+    Padding {
+        /// The offset of the padding in bytes.
+        offset: usize,
+
+        /// The number of padding bytes.
+        size: usize,
+    },
+}
+
+impl ReportEntry {
+    /// The offset of this entry in bytes.
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::Field { offset, .. } | Self::Padding { offset, .. } => *offset,
+        }
+    }
+
+    /// The size of this entry in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Field { size, .. } | Self::Padding { size, .. } => *size,
+        }
+    }
+}
+
+impl LayoutReport {
+    /// Creates a report from the given layout information.
+    pub fn new(info: &'static LayoutInfo) -> Self {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+
+        // Fields of reordered structs aren't laid out in declaration order.
+        let mut fields: Vec<_> = info.fields.iter().collect();
+        fields.sort_by_key(|field| field.offset);
+
+        for field in fields {
+            if field.offset > offset {
+                entries.push(ReportEntry::Padding {
+                    offset,
+                    size: field.offset - offset,
+                });
+            }
+
+            entries.push(ReportEntry::Field {
+                name: field.name,
+                ty: type_name(field.layout),
+                offset: field.offset,
+                size: field.size,
+                alignment: field.layout.alignment,
+            });
+
+            offset = field.offset + field.size;
+        }
+
+        if !info.fields.is_empty() && info.size > offset {
+            entries.push(ReportEntry::Padding {
+                offset,
+                size: info.size - offset,
+            });
+        }
+
+        Self {
+            info,
+            entries,
+            wasted_bytes: wasted_bytes(info),
+        }
+    }
+
+    /// Creates a report for the given type.
+    pub fn of<T: Reflect + ?Sized>() -> Self {
+        Self::new(T::LAYOUT_INFO)
+    }
+
+    /// Creates a report for the `std140` version of the given type.
+    pub fn std140<T: AsStd140>() -> Self
+    where
+        T::Output: Reflect,
+    {
+        Self::of::<T::Output>()
+    }
+
+    /// Creates a report for the `std430` version of the given type.
+    pub fn std430<T: AsStd430>() -> Self
+    where
+        T::Output: Reflect,
+    {
+        Self::of::<T::Output>()
+    }
+
+    /// The name of the reported type.
+    pub fn name(&self) -> &'static str {
+        self.info.name
+    }
+
+    /// The size of the reported type in bytes.
+    pub fn size(&self) -> usize {
+        self.info.size
+    }
+
+    /// The alignment of the reported type in bytes.
+    pub fn alignment(&self) -> usize {
+        self.info.alignment
+    }
+
+    /// The fields and padding runs of the reported type, in order.
+    pub fn entries(&self) -> &[ReportEntry] {
+        &self.entries
+    }
+
+    /// The padding runs of the reported type, in order.
+    pub fn padding(&self) -> impl Iterator<Item = &ReportEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry, ReportEntry::Padding { .. }))
+    }
+
+    /// The total number of padding bytes in the type, including padding
+    /// within nested structs, arrays and the columns of matrices.
+    pub fn wasted_bytes(&self) -> usize {
+        self.wasted_bytes
+    }
+
+    /// Suggests an order for the type's fields that needs less padding, using
+    /// the same search as `#[crevice(reorder)]`.
+    ///
+    /// Offsets are computed with `std140` and `std430` rules, where each field
+    /// is aligned to its own alignment. The smallest order is found for types
+    /// with up to seven fields; larger types get a greedy order that may not
+    /// be the smallest.
+    pub fn suggest_order(&self) -> FieldOrder {
+        let fields = self.info.fields;
+        let sizes: Vec<_> = fields.iter().map(|field| field.size).collect();
+        let alignments: Vec<_> = fields.iter().map(|field| field.layout.alignment).collect();
+
+        let mut used = vec![false; fields.len()];
+        let mut order = vec![0; fields.len()];
+        let mut candidate = vec![0; fields.len()];
+        crate::internal::field_order_into(
+            &sizes,
+            &alignments,
+            self.info.alignment,
+            false,
+            &mut used,
+            &mut order,
+            &mut candidate,
+        );
+
+        let mut size = 0;
+        for &index in &order {
+            size += crate::internal::align_offset(size, alignments[index]) + sizes[index];
+        }
+        size += crate::internal::align_offset(size, self.info.alignment);
+
+        FieldOrder {
+            fields: order.iter().map(|&index| fields[index].name).collect(),
+            size,
+            bytes_saved: self.info.size.saturating_sub(size),
+        }
+    }
+
+    /// Renders the report as a plain text table.
+    pub fn to_table(&self) -> String {
+        let mut output = String::new();
+
+        writeln!(
+            output,
+            "{}: size {}, alignment {}",
+            self.info.name, self.info.size, self.info.alignment
+        )
+        .unwrap();
+        writeln!(output, "offset  size  align  field").unwrap();
+
+        for entry in &self.entries {
+            match entry {
+                ReportEntry::Field {
+                    name,
+                    ty,
+                    offset,
+                    size,
+                    alignment,
+                } => writeln!(
+                    output,
+                    "{:>6}  {:>4}  {:>5}  {}: {}",
+                    offset, size, alignment, name, ty
+                ),
+                ReportEntry::Padding { offset, size } => {
+                    writeln!(output, "{:>6}  {:>4}         (padding)", offset, size)
+                }
+            }
+            .unwrap();
+        }
+
+        write!(
+            output,
+            "wasted: {} of {} bytes",
+            self.wasted_bytes, self.info.size
+        )
+        .unwrap();
+        output
+    }
+
+    /// Renders the report as an ASCII map with one character per byte,
+    /// followed by a legend naming each field's symbol. Padding bytes,
+    /// including those within nested structs and matrices, are shown as `.`.
+    pub fn to_byte_map(&self) -> String {
+        let mut bytes = vec![b'.'; self.info.size];
+        let mut legend = Vec::new();
+
+        for (index, field) in self.info.fields.iter().enumerate() {
+            let symbol = FIELD_SYMBOLS[index % FIELD_SYMBOLS.len()];
+            fill_bytes(&mut bytes, field.layout, field.offset, symbol);
+            legend.push(format!("{} = {}", symbol as char, field.name));
+        }
+        legend.push(". = padding".to_owned());
+
+        let mut output = String::new();
+        for (row, chunk) in bytes.chunks(BYTES_PER_ROW).enumerate() {
+            writeln!(
+                output,
+                "{:>6}  {}",
+                row * BYTES_PER_ROW,
+                String::from_utf8_lossy(chunk)
+            )
+            .unwrap();
+        }
+
+        output.push_str(&legend.join(", "));
+        output
+    }
+
+    /// Renders the report as a Markdown table.
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::new();
+
+        writeln!(
+            output,
+            "**`{}`**: size {}, alignment {}\n",
+            self.info.name, self.info.size, self.info.alignment
+        )
+        .unwrap();
+        writeln!(output, "| Offset | Size | Align | Field | Type |").unwrap();
+        writeln!(output, "| -----: | ---: | ----: | :---- | :--- |").unwrap();
+
+        for entry in &self.entries {
+            match entry {
+                ReportEntry::Field {
+                    name,
+                    ty,
+                    offset,
+                    size,
+                    alignment,
+                } => writeln!(
+                    output,
+                    "| {} | {} | {} | `{}` | `{}` |",
+                    offset, size, alignment, name, ty
+                ),
+                ReportEntry::Padding { offset, size } => {
+                    writeln!(output, "| {} | {} | | *padding* | |", offset, size)
+                }
+            }
+            .unwrap();
+        }
+
+        write!(
+            output,
+            "\n**Wasted:** {} of {} bytes",
+            self.wasted_bytes, self.info.size
+        )
+        .unwrap();
+        output
+    }
+}
+
+/// A suggested order for the fields of a type, returned by
+/// [`LayoutReport::suggest_order`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldOrder {
+    /// The names of the fields in their suggested order.
+    pub fields: Vec<&'static str>,
+
+    /// The size of the type with its fields in this order.
+    pub size: usize,
+
+    /// The number of bytes saved compared to the current order.
+    pub bytes_saved: usize,
+}
+
+impl fmt::Display for LayoutReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.to_table())
+    }
+}
+
+fn type_name(info: &LayoutInfo) -> String {
+    match &info.array {
+        Some(array) => format!("{}[{}]", type_name(array.element), array.len),
+        None => info.name.to_owned(),
+    }
+}
+
+fn wasted_bytes(info: &LayoutInfo) -> usize {
+    // Padding between elements isn't always repeated after the last one, as
+    // in HLSL constant buffers, so it's whatever the elements don't use.
+    if let Some(array) = &info.array {
+        let element = array.element;
+        return info.size - array.len * (element.size - wasted_bytes(element));
+    }
+
+    // Matrices and padded vectors are padded after each column.
+    if let Some(columns) = &info.columns {
+        return info.size - columns.count * columns.size;
+    }
+
+    if info.fields.is_empty() {
+        return 0;
+    }
+
+    let used: usize = info
+        .fields
+        .iter()
+        .map(|field| field.size - wasted_bytes(field.layout))
+        .sum();
+
+    info.size - used
+}
+
+/// Marks the bytes of the given type that aren't padding with `symbol`.
+fn fill_bytes(bytes: &mut [u8], info: &LayoutInfo, offset: usize, symbol: u8) {
+    if let Some(array) = &info.array {
+        for index in 0..array.len {
+            fill_bytes(bytes, array.element, offset + index * array.stride, symbol);
+        }
+    } else if let Some(columns) = &info.columns {
+        for index in 0..columns.count {
+            let start = offset + index * columns.stride;
+            let end = (start + columns.size).min(bytes.len());
+            bytes[start..end].fill(symbol);
+        }
+    } else if info.fields.is_empty() {
+        let end = (offset + info.size).min(bytes.len());
+        bytes[offset..end].fill(symbol);
+    } else {
+        for field in info.fields {
+            fill_bytes(bytes, field.layout, offset + field.offset, symbol);
+        }
+    }
+}
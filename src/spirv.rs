@@ -0,0 +1,621 @@
+/*!
+Reads the layout of blocks from compiled SPIR-V and compares it with Crevice's
+layout of a Rust type.
+
+SPIR-V modules record the layout of every block in their `Offset`,
+`ArrayStride` and `MatrixStride` decorations, so checking a type against the
+shaders that are actually shipped needs no GPU or driver. Member names come
+from the module's debug information, which most compilers include unless it's
+stripped.
+
+## Example
+
+```rust
+use crevice::layout::Std140;
+use crevice::std140::AsStd140;
+
+#[derive(AsStd140)]
+struct Camera {
+    view: mint::ColumnMatrix4<f32>,
+    projection: mint::ColumnMatrix4<f32>,
+    normal_matrix: mint::ColumnMatrix3<f32>,
+    position: mint::Vector3<f32>,
+    exposure: f32,
+    frustum: [mint::Vector4<f32>; 6],
+}
+
+let spirv = include_bytes!("../tests/shaders/camera.spv");
+let diff = crevice::spirv::validate_block::<Camera, Std140>(spirv, "Camera").unwrap();
+assert!(diff.is_match(), "{}", diff);
+```
+*/
+
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::fmt;
+
+use crate::glsl::{array_type_name, compare_layouts, LayoutDiff, MemberLayout};
+use crate::layout::{AsLayout, Layout};
+use crate::reflect::{LayoutInfo, Reflect};
+
+/// The first word of every SPIR-V module.
+const MAGIC: u32 = 0x0723_0203;
+
+const OP_NAME: u32 = 5;
+const OP_MEMBER_NAME: u32 = 6;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_CONSTANT: u32 = 43;
+const OP_SPEC_CONSTANT: u32 = 50;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+
+const DECORATION_ROW_MAJOR: u32 = 4;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_OFFSET: u32 = 35;
+
+/// Compares the layout of a block in a SPIR-V module with the layout of a Rust
+/// type, giving every difference between them.
+///
+/// `block_name` is the name of the block's struct type, which is the name the
+/// block was declared with in GLSL. Members are matched to fields of `T` by
+/// name, and members of nested structs are compared too. A runtime-sized array
+/// at the end of a storage buffer is ignored, since it has no place in a Rust
+/// struct.
+pub fn validate_block<T, L>(spirv: &[u8], block_name: &str) -> Result<LayoutDiff, SpirvError>
+where
+    L: Layout,
+    T: AsLayout<L>,
+    <T as AsLayout<L>>::Output: Reflect,
+{
+    validate_block_layout(spirv, block_name, <T as AsLayout<L>>::Output::LAYOUT_INFO)
+}
+
+/// Like [`validate_block`], but compares against an already known layout.
+pub fn validate_block_layout(
+    spirv: &[u8],
+    block_name: &str,
+    info: &LayoutInfo,
+) -> Result<LayoutDiff, SpirvError> {
+    let block = reflect_block(spirv, block_name)?;
+    let members: Vec<MemberLayout> = block.members.iter().map(member_layout).collect();
+
+    let mut differences = Vec::new();
+    compare_layouts(&members, info, "", 0, &mut differences);
+
+    Ok(LayoutDiff {
+        block: block.name,
+        differences,
+    })
+}
+
+/// Reads the layout of the block named `block_name` from a SPIR-V module.
+pub fn reflect_block(spirv: &[u8], block_name: &str) -> Result<SpirvBlock, SpirvError> {
+    let module = Module::parse(spirv)?;
+
+    let id = module
+        .names
+        .iter()
+        .find(|(id, name)| name == block_name && matches!(module.ty(*id), Some(Type::Struct(_))))
+        .map(|(id, _)| *id)
+        .ok_or_else(|| SpirvError::BlockNotFound {
+            name: block_name.to_owned(),
+        })?;
+
+    Ok(SpirvBlock {
+        name: block_name.to_owned(),
+        members: module.struct_members(id)?,
+    })
+}
+
+/// The layout of a block read from SPIR-V by [`reflect_block`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpirvBlock {
+    /// The name of the block.
+    pub name: String,
+
+    /// The members of the block, in order. A trailing runtime-sized array is
+    /// left out.
+    pub members: Vec<SpirvMember>,
+}
+
+/// The layout of a member of a block or struct read from SPIR-V.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpirvMember {
+    /// The name of the member.
+    pub name: String,
+
+    /// The member's type, written as a GLSL type like `vec3`, `mat4` or
+    /// `Light[4]`. Row-major matrices are prefixed with `row_major`.
+    pub ty: String,
+
+    /// The `Offset` decoration of the member, relative to the start of its
+    /// struct or block.
+    pub offset: usize,
+
+    /// The size of the member, if it's known. SPIR-V doesn't record the size
+    /// of structs, so this is `None` for members that are structs.
+    pub size: Option<usize>,
+
+    /// The `ArrayStride` decoration of the member's type, if it's an array.
+    pub array_stride: Option<usize>,
+
+    /// The `MatrixStride` decoration of the member, if it's a matrix or an
+    /// array of matrices.
+    pub matrix_stride: Option<usize>,
+
+    /// The members of the member's struct type, or of its element type if
+    /// it's an array of structs.
+    pub members: Vec<SpirvMember>,
+}
+
+fn member_layout(member: &SpirvMember) -> MemberLayout {
+    MemberLayout {
+        name: member.name.clone(),
+        ty: member.ty.clone(),
+        offset: member.offset,
+        size: member.size,
+        array_stride: member.array_stride,
+        matrix_stride: member.matrix_stride,
+        fields: member.members.iter().map(member_layout).collect(),
+    }
+}
+
+/// An error that prevented a block from being read from SPIR-V.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpirvError {
+    /// The data doesn't start with the SPIR-V magic number, or isn't a whole
+    /// number of words.
+    InvalidHeader,
+
+    /// The instruction at the given word couldn't be read.
+    Malformed {
+        /// The index of the word the instruction starts at.
+        word: usize,
+
+        /// What went wrong.
+        message: String,
+    },
+
+    /// The module doesn't contain a struct type with the given name.
+    BlockNotFound {
+        /// The name of the block.
+        name: String,
+    },
+
+    /// A member of the struct type with the given name has no name or no
+    /// `Offset` decoration. Names are missing if debug information was
+    /// stripped from the module.
+    MissingDecoration {
+        /// The name of the struct type.
+        name: String,
+
+        /// The index of the member.
+        member: usize,
+
+        /// What the member is missing, like `Offset`.
+        missing: &'static str,
+    },
+
+    /// A type can't be laid out, like an opaque image or a pointer.
+    UnsupportedType {
+        /// The name of the struct type containing it.
+        name: String,
+
+        /// The index of the member using it.
+        member: usize,
+    },
+}
+
+impl fmt::Display for SpirvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHeader => f.write_str("the data isn't a SPIR-V module"),
+            Self::Malformed { word, message } => write!(f, "word {}: {}", word, message),
+            Self::BlockNotFound { name } => write!(f, "no block named `{}` was found", name),
+            Self::MissingDecoration {
+                name,
+                member,
+                missing,
+            } => write!(f, "member {} of `{}` has no {}", member, name, missing),
+            Self::UnsupportedType { name, member } => write!(
+                f,
+                "member {} of `{}` has a type that can't be laid out",
+                member, name
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SpirvError {}
+
+/// A type declared in a SPIR-V module, with IDs referring to other types.
+#[derive(Debug, Clone)]
+enum Type {
+    Bool,
+    Int { width: u32, signed: bool },
+    Float { width: u32 },
+    Vector { component: u32, count: u32 },
+    Matrix { column: u32, count: u32 },
+    Array { element: u32, length: u32 },
+    RuntimeArray,
+    Struct(Vec<u32>),
+}
+
+/// The parts of a SPIR-V module needed to lay out blocks.
+#[derive(Default)]
+struct Module {
+    names: Vec<(u32, String)>,
+
+    /// Member names, keyed by struct ID and member index.
+    member_names: Vec<((u32, u32), String)>,
+    types: Vec<(u32, Type)>,
+    constants: Vec<(u32, u32)>,
+    array_strides: Vec<(u32, u32)>,
+
+    /// Member decorations, keyed by struct ID and member index.
+    offsets: Vec<((u32, u32), u32)>,
+    matrix_strides: Vec<((u32, u32), u32)>,
+    row_major: Vec<(u32, u32)>,
+}
+
+fn lookup<K: PartialEq, V: Copy>(entries: &[(K, V)], key: K) -> Option<V> {
+    entries
+        .iter()
+        .find(|(entry, _)| *entry == key)
+        .map(|(_, value)| *value)
+}
+
+/// Decodes a nul-terminated string packed into words.
+fn literal_string(words: &[u32]) -> String {
+    let bytes: Vec<u8> = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .take_while(|&byte| byte != 0)
+        .collect();
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+impl Module {
+    fn parse(spirv: &[u8]) -> Result<Self, SpirvError> {
+        if spirv.len() % 4 != 0 || spirv.len() < 20 {
+            return Err(SpirvError::InvalidHeader);
+        }
+
+        // Modules may be stored in either byte order, which the magic number
+        // reveals.
+        let little_endian = match spirv[..4].try_into().ok().map(u32::from_le_bytes) {
+            Some(MAGIC) => true,
+            Some(magic) if magic.swap_bytes() == MAGIC => false,
+            _ => return Err(SpirvError::InvalidHeader),
+        };
+
+        let words: Vec<u32> = spirv
+            .chunks_exact(4)
+            .map(|chunk| {
+                let bytes = [chunk[0], chunk[1], chunk[2], chunk[3]];
+                if little_endian {
+                    u32::from_le_bytes(bytes)
+                } else {
+                    u32::from_be_bytes(bytes)
+                }
+            })
+            .collect();
+
+        let mut module = Module::default();
+        let mut position = 5;
+
+        while position < words.len() {
+            let count = (words[position] >> 16) as usize;
+            let opcode = words[position] & 0xffff;
+
+            if count == 0 || position + count > words.len() {
+                return Err(SpirvError::Malformed {
+                    word: position,
+                    message: format!("instruction has an invalid length of {}", count),
+                });
+            }
+
+            let operands = &words[position + 1..position + count];
+            module
+                .add_instruction(opcode, operands)
+                .map_err(|message| SpirvError::Malformed {
+                    word: position,
+                    message: message.to_owned(),
+                })?;
+
+            position += count;
+        }
+
+        Ok(module)
+    }
+
+    fn add_instruction(&mut self, opcode: u32, operands: &[u32]) -> Result<(), &'static str> {
+        // Every instruction used here has at least two operands, except
+        // `OpTypeBool` and `OpTypeStruct`.
+        let needs = |len: usize| {
+            if operands.len() < len {
+                Err("instruction has too few operands")
+            } else {
+                Ok(())
+            }
+        };
+
+        match opcode {
+            OP_NAME => {
+                needs(2)?;
+                self.names
+                    .push((operands[0], literal_string(&operands[1..])));
+            }
+            OP_MEMBER_NAME => {
+                needs(3)?;
+                self.member_names
+                    .push(((operands[0], operands[1]), literal_string(&operands[2..])));
+            }
+            OP_TYPE_BOOL => {
+                needs(1)?;
+                self.types.push((operands[0], Type::Bool));
+            }
+            OP_TYPE_INT => {
+                needs(3)?;
+                self.types.push((
+                    operands[0],
+                    Type::Int {
+                        width: operands[1],
+                        signed: operands[2] != 0,
+                    },
+                ));
+            }
+            OP_TYPE_FLOAT => {
+                needs(2)?;
+                self.types
+                    .push((operands[0], Type::Float { width: operands[1] }));
+            }
+            OP_TYPE_VECTOR => {
+                needs(3)?;
+                self.types.push((
+                    operands[0],
+                    Type::Vector {
+                        component: operands[1],
+                        count: operands[2],
+                    },
+                ));
+            }
+            OP_TYPE_MATRIX => {
+                needs(3)?;
+                self.types.push((
+                    operands[0],
+                    Type::Matrix {
+                        column: operands[1],
+                        count: operands[2],
+                    },
+                ));
+            }
+            OP_TYPE_ARRAY => {
+                needs(3)?;
+                self.types.push((
+                    operands[0],
+                    Type::Array {
+                        element: operands[1],
+                        length: operands[2],
+                    },
+                ));
+            }
+            OP_TYPE_RUNTIME_ARRAY => {
+                needs(2)?;
+                self.types.push((operands[0], Type::RuntimeArray));
+            }
+            OP_TYPE_STRUCT => {
+                needs(1)?;
+                self.types
+                    .push((operands[0], Type::Struct(operands[1..].to_vec())));
+            }
+            OP_CONSTANT | OP_SPEC_CONSTANT => {
+                // Only the low word matters for array lengths.
+                needs(3)?;
+                self.constants.push((operands[1], operands[2]));
+            }
+            OP_DECORATE => {
+                needs(2)?;
+                if operands[1] == DECORATION_ARRAY_STRIDE {
+                    needs(3)?;
+                    self.array_strides.push((operands[0], operands[2]));
+                }
+            }
+            OP_MEMBER_DECORATE => {
+                needs(3)?;
+                let member = (operands[0], operands[1]);
+                match operands[2] {
+                    DECORATION_OFFSET => {
+                        needs(4)?;
+                        self.offsets.push((member, operands[3]));
+                    }
+                    DECORATION_MATRIX_STRIDE => {
+                        needs(4)?;
+                        self.matrix_strides.push((member, operands[3]));
+                    }
+                    DECORATION_ROW_MAJOR => self.row_major.push(member),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn ty(&self, id: u32) -> Option<&Type> {
+        self.types
+            .iter()
+            .find(|(type_id, _)| *type_id == id)
+            .map(|(_, ty)| ty)
+    }
+
+    fn name(&self, id: u32) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(name_id, _)| *name_id == id)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Reads the layout of every member of the struct type `id`.
+    fn struct_members(&self, id: u32) -> Result<Vec<SpirvMember>, SpirvError> {
+        let struct_name = self.name(id).unwrap_or_default();
+        let member_types = match self.ty(id) {
+            Some(Type::Struct(members)) => members,
+            _ => unreachable!("struct_members is only called with struct types"),
+        };
+
+        let mut members = Vec::new();
+        for (index, &member_type) in member_types.iter().enumerate() {
+            let key = (id, index as u32);
+            let missing = |missing| SpirvError::MissingDecoration {
+                name: struct_name.to_owned(),
+                member: index,
+                missing,
+            };
+            let unsupported = || SpirvError::UnsupportedType {
+                name: struct_name.to_owned(),
+                member: index,
+            };
+
+            if let Some(Type::RuntimeArray) = self.ty(member_type) {
+                continue;
+            }
+
+            let name = self
+                .member_names
+                .iter()
+                .find(|(member, _)| *member == key)
+                .map(|(_, name)| name.clone())
+                .ok_or_else(|| missing("name"))?;
+            let offset = lookup(&self.offsets, key).ok_or_else(|| missing("Offset"))?;
+            let matrix_stride = lookup(&self.matrix_strides, key).map(|stride| stride as usize);
+
+            // Arrays are described by their element type, with the length and
+            // stride added on.
+            let (element, array) = match self.ty(member_type) {
+                Some(Type::Array { element, length }) => {
+                    let len = lookup(&self.constants, *length).ok_or_else(unsupported)?;
+                    let stride = lookup(&self.array_strides, member_type)
+                        .ok_or_else(|| missing("ArrayStride"))?;
+                    (*element, Some((len as usize, stride as usize)))
+                }
+                _ => (member_type, None),
+            };
+
+            let mut ty = self.type_name(element).ok_or_else(unsupported)?;
+            let element_size = match self.ty(element) {
+                Some(Type::Matrix { count, .. }) => {
+                    let stride = matrix_stride.ok_or_else(|| missing("MatrixStride"))?;
+                    if self.row_major.contains(&key) {
+                        ty = format!("row_major {}", ty);
+                    }
+                    Some(stride * *count as usize)
+                }
+                Some(Type::Struct(_)) => None,
+                _ => Some(self.scalar_size(element).ok_or_else(unsupported)?),
+            };
+
+            let fields = match self.ty(element) {
+                Some(Type::Struct(_)) => self.struct_members(element)?,
+                _ => Vec::new(),
+            };
+
+            let (ty, size, array_stride) = match array {
+                Some((len, stride)) => {
+                    (array_type_name(&ty, len), Some(stride * len), Some(stride))
+                }
+                None => (ty, element_size, None),
+            };
+
+            members.push(SpirvMember {
+                name,
+                ty,
+                offset: offset as usize,
+                size,
+                array_stride,
+                matrix_stride,
+                members: fields,
+            });
+        }
+
+        Ok(members)
+    }
+
+    /// The size of a scalar or vector type.
+    fn scalar_size(&self, id: u32) -> Option<usize> {
+        match self.ty(id)? {
+            Type::Bool => Some(4),
+            Type::Int { width, .. } | Type::Float { width } => Some(*width as usize / 8),
+            Type::Vector { component, count } => {
+                Some(self.scalar_size(*component)? * *count as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// The GLSL name of a type, like `vec3` or `mat4x3`.
+    fn type_name(&self, id: u32) -> Option<String> {
+        let name = match self.ty(id)? {
+            Type::Bool => "bool".to_owned(),
+            Type::Int { width: 32, signed } => if *signed { "int" } else { "uint" }.to_owned(),
+            Type::Int { width, signed } => {
+                format!("{}int{}_t", if *signed { "" } else { "u" }, width)
+            }
+            Type::Float { width: 32 } => "float".to_owned(),
+            Type::Float { width: 64 } => "double".to_owned(),
+            Type::Float { width } => format!("float{}_t", width),
+            Type::Vector { component, count } => {
+                format!("{}vec{}", self.prefix(*component)?, count)
+            }
+            Type::Matrix { column, count } => {
+                let (component, rows) = match self.ty(*column)? {
+                    Type::Vector { component, count } => (*component, *count),
+                    _ => return None,
+                };
+
+                let prefix = self.prefix(component)?;
+                if rows == *count {
+                    format!("{}mat{}", prefix, count)
+                } else {
+                    format!("{}mat{}x{}", prefix, count, rows)
+                }
+            }
+            Type::Array { element, length } => {
+                let len = lookup(&self.constants, *length)?;
+                array_type_name(&self.type_name(*element)?, len as usize)
+            }
+            Type::Struct(_) => self.name(id).unwrap_or_default().to_owned(),
+            Type::RuntimeArray => return None,
+        };
+
+        Some(name)
+    }
+
+    /// The prefix of GLSL vector and matrix types with the given component
+    /// type, like `d` for `dvec3`.
+    fn prefix(&self, component: u32) -> Option<String> {
+        let prefix = match self.ty(component)? {
+            Type::Bool => "b".to_owned(),
+            Type::Float { width: 32 } => String::new(),
+            Type::Float { width: 64 } => "d".to_owned(),
+            Type::Float { width } => format!("f{}", width),
+            Type::Int { width: 32, signed } => if *signed { "i" } else { "u" }.to_owned(),
+            Type::Int { width, signed } => {
+                format!("{}{}", if *signed { "i" } else { "u" }, width)
+            }
+            _ => return None,
+        };
+
+        Some(prefix)
+    }
+}
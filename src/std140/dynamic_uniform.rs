@@ -1,6 +1,7 @@
 use bytemuck::{Pod, Zeroable};
 
 use crate::internal::max;
+use crate::reflect::{LayoutInfo, Reflect};
 use crate::std140::{AsStd140, Std140};
 
 /// Wrapper type that aligns the inner type to at least 256 bytes.
@@ -30,6 +31,17 @@ unsafe impl<T: Std140> Std140 for DynamicUniformStd140<T> {
     const ALIGNMENT: usize = max(256, T::ALIGNMENT);
 }
 
+unsafe impl<T: Std140 + Reflect> Reflect for DynamicUniformStd140<T> {
+    const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo {
+        name: T::LAYOUT_INFO.name,
+        size: T::LAYOUT_INFO.size,
+        alignment: <Self as Std140>::ALIGNMENT,
+        fields: T::LAYOUT_INFO.fields,
+        array: T::LAYOUT_INFO.array,
+        columns: T::LAYOUT_INFO.columns,
+    };
+}
+
 unsafe impl<T: Zeroable> Zeroable for DynamicUniformStd140<T> {}
 unsafe impl<T: Pod> Pod for DynamicUniformStd140<T> {}
 
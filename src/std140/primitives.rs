@@ -2,6 +2,9 @@ use bytemuck::{Pod, Zeroable};
 
 use crate::bool::Bool;
 use crate::glsl::Glsl;
+use crate::internal::array_alignment;
+use crate::layout::{self, ArrayElement, LayoutValue};
+use crate::reflect::{LayoutInfo, Reflect};
 use crate::std140::{AsStd140, Std140};
 
 unsafe impl Std140 for f32 {
@@ -61,6 +64,14 @@ macro_rules! vectors {
             unsafe impl Glsl for $name {
                 const NAME: &'static str = stringify!($glsl_name);
             }
+
+            unsafe impl Reflect for $name {
+                const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo::primitive(
+                    stringify!($glsl_name),
+                    core::mem::size_of::<$name>(),
+                    $align,
+                );
+            }
         )+
     };
 }
@@ -93,6 +104,7 @@ macro_rules! matrices {
             #[$doc:meta]
             align($align:literal)
             $glsl_name:ident $name:ident {
+                $column:ident: $column_ty:ty,
                 $($field:ident: $field_ty:ty,)+
             }
         )+
@@ -103,6 +115,7 @@ macro_rules! matrices {
             #[derive(Debug, Clone, Copy)]
             #[repr(C)]
             pub struct $name {
+                pub $column: $column_ty,
                 $(pub $field: $field_ty,)+
             }
 
@@ -116,6 +129,16 @@ macro_rules! matrices {
             unsafe impl Glsl for $name {
                 const NAME: &'static str = stringify!($glsl_name);
             }
+
+            unsafe impl Reflect for $name {
+                const LAYOUT_INFO: &'static LayoutInfo = &LayoutInfo::matrix(
+                    stringify!($glsl_name),
+                    core::mem::size_of::<$name>(),
+                    $align,
+                    <$column_ty as Reflect>::LAYOUT_INFO,
+                    core::mem::offset_of!($name, y),
+                );
+            }
         )+
     };
 }
@@ -177,3 +200,59 @@ matrices! {
         w: DVec4,
     }
 }
+
+array_elements!(layout::Std140 {
+    f32,
+    f64,
+    i32,
+    u32,
+    Bool,
+    Vec2,
+    Vec3,
+    Vec4,
+    IVec2,
+    IVec3,
+    IVec4,
+    UVec2,
+    UVec3,
+    UVec4,
+    BVec2,
+    BVec3,
+    BVec4,
+    DVec2,
+    DVec3,
+    DVec4,
+    Mat2,
+    Mat3,
+    Mat4,
+    DMat2,
+    DMat3,
+    DMat4,
+});
+
+/// Corresponds to a GLSL array in std140 layout.
+///
+/// std140 starts every array element at a multiple of 16 bytes, so elements
+/// like `float` or `vec2` are each followed by padding up to the next element.
+/// See [`layout::Array`].
+pub type Array<T, const N: usize> = layout::Array<layout::Std140, T, N>;
+
+unsafe impl<T: ArrayElement<layout::Std140>, const N: usize> Std140 for Array<T, N> {
+    const ALIGNMENT: usize =
+        array_alignment::<layout::Std140>(<T as LayoutValue<layout::Std140>>::ALIGNMENT);
+}
+
+impl<T: AsStd140, const N: usize> AsStd140 for [T; N]
+where
+    T::Output: ArrayElement<layout::Std140>,
+{
+    type Output = Array<T::Output, N>;
+
+    fn as_std140(&self) -> Self::Output {
+        Array::new(self.each_ref().map(T::as_std140))
+    }
+
+    fn from_std140(value: Self::Output) -> Self {
+        value.to_array().map(T::from_std140)
+    }
+}
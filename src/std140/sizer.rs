@@ -1,7 +1,4 @@
-use core::mem::size_of;
-
-use crate::internal::align_offset;
-use crate::std140::{AsStd140, Std140};
+use crate::layout;
 
 /**
 Type that computes the buffer size needed by a series of `std140` types laid
@@ -10,6 +7,9 @@ out.
 This type works well well when paired with `Writer`, precomputing a buffer's
 size to alleviate the need to dynamically re-allocate buffers.
 
+`Sizer` is an alias of the generic [`layout::Sizer`] using the
+[`layout::Std140`] rules.
+
 ## Example
 
 ```glsl
@@ -45,37 +45,4 @@ let buffer = create_buffer_with_size(sizer.len());
 # assert_eq!(sizer.len(), 32);
 ```
 */
-pub struct Sizer {
-    offset: usize,
-}
-
-impl Sizer {
-    /// Create a new `Sizer`.
-    pub fn new() -> Self {
-        Self { offset: 0 }
-    }
-
-    /// Add a type's necessary padding and size to the `Sizer`. Returns the
-    /// offset into the buffer where that type would be written.
-    pub fn add<T>(&mut self) -> usize
-    where
-        T: AsStd140,
-    {
-        let size = size_of::<<T as AsStd140>::Output>();
-        let alignment = <T as AsStd140>::Output::ALIGNMENT;
-        let padding = align_offset(self.offset, alignment);
-
-        self.offset += padding;
-        let write_here = self.offset;
-
-        self.offset += size;
-
-        write_here
-    }
-
-    /// Returns the number of bytes required to contain all the types added to
-    /// the `Sizer`.
-    pub fn len(&self) -> usize {
-        self.offset
-    }
-}
+pub type Sizer = layout::Sizer<layout::Std140>;
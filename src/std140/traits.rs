@@ -4,11 +4,18 @@ use std::io::{self, Write};
 
 use bytemuck::{bytes_of, Pod, Zeroable};
 
+#[cfg(feature = "std")]
+use crate::layout::WriteLayout;
+use crate::layout::{self, AsLayout, LayoutValue};
 #[cfg(feature = "std")]
 use crate::std140::Writer;
 
 /// Trait implemented for all `std140` primitives. Generally should not be
 /// implemented outside this crate.
+///
+/// # Safety
+/// Implementors must not contain any uninitialized padding bytes, and their
+/// size and `ALIGNMENT` must match the `std140` layout of the type they represent.
 pub unsafe trait Std140: Copy + Zeroable + Pod {
     /// The required alignment of the type. Must be a power of two.
     ///
@@ -111,6 +118,10 @@ where
 /// `Std140` trait, `WriteStd140` directly writes bytes using a [`Writer`]. This
 /// makes `WriteStd140` usable for writing slices or other DSTs that could not
 /// implement `AsStd140` without allocating new memory on the heap.
+///
+/// Every type implementing `WriteStd140` also implements
+/// [`WriteLayout<layout::Std140>`][WriteLayout], so it can be written with
+/// [`Writer::write`].
 #[cfg(feature = "std")]
 pub trait WriteStd140 {
     /// Writes this value into the given [`Writer`] using `std140` layout rules.
@@ -135,7 +146,7 @@ where
     T: AsStd140,
 {
     fn write_std140<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
-        writer.write_std140(&self.as_std140())
+        writer.write_value(&self.as_std140())
     }
 
     fn std140_size(&self) -> usize {
@@ -149,25 +160,42 @@ where
     T: WriteStd140,
 {
     fn write_std140<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
-        // if no items are written, offset is current position of the writer
-        let mut offset = writer.len();
+        self.write_layout(writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteLayout<layout::Std140> for T
+where
+    T: WriteStd140,
+{
+    fn write_layout<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        self.write_std140(writer)
+    }
 
-        let mut iter = self.iter();
+    fn layout_size(&self) -> usize {
+        self.std140_size()
+    }
+}
 
-        if let Some(item) = iter.next() {
-            offset = item.write_std140(writer)?;
-        }
+unsafe impl<T> LayoutValue<layout::Std140> for T
+where
+    T: Std140,
+{
+    const ALIGNMENT: usize = <T as Std140>::ALIGNMENT;
+}
 
-        for item in iter {
-            item.write_std140(writer)?;
-        }
+impl<T> AsLayout<layout::Std140> for T
+where
+    T: AsStd140,
+{
+    type Output = <T as AsStd140>::Output;
 
-        Ok(offset)
+    fn as_layout(&self) -> Self::Output {
+        self.as_std140()
     }
 
-    fn std140_size(&self) -> usize {
-        let mut writer = Writer::new(io::sink());
-        self.write_std140(&mut writer).unwrap();
-        writer.len()
+    fn from_layout(value: Self::Output) -> Self {
+        <T as AsStd140>::from_std140(value)
     }
 }
@@ -1,10 +1,7 @@
 use std::io::{self, Write};
-use std::mem::size_of;
 
-use bytemuck::bytes_of;
-
-use crate::internal::align_offset;
-use crate::std140::{AsStd140, Std140, WriteStd140};
+use crate::layout;
+use crate::std140::{AsStd140, Std140};
 
 /**
 Type that enables writing correctly aligned `std140` values to a buffer.
@@ -13,6 +10,9 @@ Type that enables writing correctly aligned `std140` values to a buffer.
 represented by a struct alone, like dynamically sized arrays or dynamically
 laid-out values.
 
+`Writer` is an alias of the generic [`layout::Writer`] using the
+[`layout::Std140`] rules.
+
 ## Example
 In this example, we'll write a length-prefixed list of lights to a buffer.
 `std140::Writer` helps align correctly, even across multiple structs, which can
@@ -75,72 +75,15 @@ unmap_gpu_buffer();
 # Ok::<(), std::io::Error>(())
 ```
 */
-pub struct Writer<W> {
-    writer: W,
-    offset: usize,
-}
+pub type Writer<W> = layout::Writer<layout::Std140, W>;
 
 impl<W: Write> Writer<W> {
-    /// Create a new `Writer`, wrapping a buffer, file, or other type that
-    /// implements [`std::io::Write`].
-    pub fn new(writer: W) -> Self {
-        Self { writer, offset: 0 }
-    }
-
-    /// Write a new value to the underlying buffer, writing zeroed padding where
-    /// necessary.
-    ///
-    /// Returns the offset into the buffer that the value was written to.
-    pub fn write<T>(&mut self, value: &T) -> io::Result<usize>
-    where
-        T: WriteStd140 + ?Sized,
-    {
-        value.write_std140(self)
-    }
-
-    /// Write an iterator of values to the underlying buffer.
-    ///
-    /// Returns the offset into the buffer that the first value was written to.
-    /// If no values were written, returns the `len()`.
-    pub fn write_iter<I, T>(&mut self, iter: I) -> io::Result<usize>
-    where
-        I: IntoIterator<Item = T>,
-        T: WriteStd140,
-    {
-        let mut offset = self.offset;
-
-        let mut iter = iter.into_iter();
-
-        if let Some(item) = iter.next() {
-            offset = item.write_std140(self)?;
-        }
-
-        for item in iter {
-            item.write_std140(self)?;
-        }
-
-        Ok(offset)
-    }
-
     /// Write an `Std140` type to the underlying buffer.
     pub fn write_std140<T>(&mut self, value: &T) -> io::Result<usize>
     where
         T: Std140,
     {
-        let padding = align_offset(self.offset, T::ALIGNMENT);
-
-        for _ in 0..padding {
-            self.writer.write_all(&[0])?;
-        }
-        self.offset += padding;
-
-        let value = value.as_std140();
-        self.writer.write_all(bytes_of(&value))?;
-
-        let write_here = self.offset;
-        self.offset += size_of::<T>();
-
-        Ok(write_here)
+        self.write_value(value)
     }
 
     /// Write a slice of values to the underlying buffer.
@@ -154,9 +97,4 @@ impl<W: Write> Writer<W> {
     {
         self.write(slice)
     }
-
-    /// Returns the amount of data written by this `Writer`.
-    pub fn len(&self) -> usize {
-        self.offset
-    }
 }
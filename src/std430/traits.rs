@@ -4,11 +4,18 @@ use std::io::{self, Write};
 
 use bytemuck::{bytes_of, Pod, Zeroable};
 
+#[cfg(feature = "std")]
+use crate::layout::WriteLayout;
+use crate::layout::{self, AsLayout, LayoutValue};
 #[cfg(feature = "std")]
 use crate::std430::Writer;
 
 /// Trait implemented for all `std430` primitives. Generally should not be
 /// implemented outside this crate.
+///
+/// # Safety
+/// Implementors must not contain any uninitialized padding bytes, and their
+/// size and `ALIGNMENT` must match the `std430` layout of the type they represent.
 pub unsafe trait Std430: Copy + Zeroable + Pod {
     /// The required alignment of the type. Must be a power of two.
     ///
@@ -111,6 +118,10 @@ where
 /// `Std430` trait, `WriteStd430` directly writes bytes using a [`Writer`]. This
 /// makes `WriteStd430` usable for writing slices or other DSTs that could not
 /// implement `AsStd430` without allocating new memory on the heap.
+///
+/// Every type implementing `WriteStd430` also implements
+/// [`WriteLayout<layout::Std430>`][WriteLayout], so it can be written with
+/// [`Writer::write`].
 #[cfg(feature = "std")]
 pub trait WriteStd430 {
     /// Writes this value into the given [`Writer`] using `std430` layout rules.
@@ -135,7 +146,7 @@ where
     T: AsStd430,
 {
     fn write_std430<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
-        writer.write_std430(&self.as_std430())
+        writer.write_value(&self.as_std430())
     }
 
     fn std430_size(&self) -> usize {
@@ -149,24 +160,42 @@ where
     T: WriteStd430,
 {
     fn write_std430<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
-        let mut offset = writer.len();
+        self.write_layout(writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> WriteLayout<layout::Std430> for T
+where
+    T: WriteStd430,
+{
+    fn write_layout<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<usize> {
+        self.write_std430(writer)
+    }
 
-        let mut iter = self.iter();
+    fn layout_size(&self) -> usize {
+        self.std430_size()
+    }
+}
 
-        if let Some(item) = iter.next() {
-            offset = item.write_std430(writer)?;
-        }
+unsafe impl<T> LayoutValue<layout::Std430> for T
+where
+    T: Std430,
+{
+    const ALIGNMENT: usize = <T as Std430>::ALIGNMENT;
+}
 
-        for item in iter {
-            item.write_std430(writer)?;
-        }
+impl<T> AsLayout<layout::Std430> for T
+where
+    T: AsStd430,
+{
+    type Output = <T as AsStd430>::Output;
 
-        Ok(offset)
+    fn as_layout(&self) -> Self::Output {
+        self.as_std430()
     }
 
-    fn std430_size(&self) -> usize {
-        let mut writer = Writer::new(io::sink());
-        self.write_std430(&mut writer).unwrap();
-        writer.len()
+    fn from_layout(value: Self::Output) -> Self {
+        <T as AsStd430>::from_std430(value)
     }
 }
@@ -1,8 +1,140 @@
 #![allow(unused_macros)]
 
+/// Implements [`ArrayElement`][crate::layout::ArrayElement] for primitives of
+/// a layout, padding each one out to its array stride.
+macro_rules! array_elements {
+    ( $layout:ty { $( $ty:ty, )* } ) => {
+        $(
+            unsafe impl crate::layout::ArrayElement<$layout> for $ty {
+                type Padded = crate::internal::Padded<
+                    $ty,
+                    {
+                        crate::internal::array_padding::<$layout>(
+                            core::mem::size_of::<$ty>(),
+                            <$ty as crate::layout::LayoutValue<$layout>>::ALIGNMENT,
+                        )
+                    },
+                >;
+            }
+        )*
+    };
+}
+
 macro_rules! easy_impl {
+    // MSL has no 64-bit floating point types.
+    (@msl DVec2 $($rest:tt)*) => {};
+    (@msl DVec3 $($rest:tt)*) => {};
+    (@msl DVec4 $($rest:tt)*) => {};
+    (@msl DMat2 $($rest:tt)*) => {};
+    (@msl DMat3 $($rest:tt)*) => {};
+    (@msl DMat4 $($rest:tt)*) => {};
+
+    (@msl $std_name:ident $imp_ty:ty { $($field:ident),* }) => {
+        #[allow(clippy::needless_update)]
+        impl crate::msl::AsMsl for $imp_ty {
+            type Output = crate::msl::$std_name;
+
+            #[inline]
+            fn as_msl(&self) -> Self::Output {
+                crate::msl::$std_name {
+                    $(
+                        $field: self.$field.as_msl(),
+                    )*
+                    ..bytemuck::Zeroable::zeroed()
+                }
+            }
+
+            #[inline]
+            fn from_msl(value: Self::Output) -> Self {
+                Self {
+                    $(
+                        $field: <_ as crate::msl::AsMsl>::from_msl(value.$field),
+                    )*
+                }
+            }
+        }
+    };
+
+    // OpenCL C has no matrix types and does not allow bools in kernel arguments.
+    (@opencl BVec2 $($rest:tt)*) => {};
+    (@opencl BVec3 $($rest:tt)*) => {};
+    (@opencl BVec4 $($rest:tt)*) => {};
+    (@opencl Mat2 $($rest:tt)*) => {};
+    (@opencl Mat3 $($rest:tt)*) => {};
+    (@opencl Mat4 $($rest:tt)*) => {};
+    (@opencl DMat2 $($rest:tt)*) => {};
+    (@opencl DMat3 $($rest:tt)*) => {};
+    (@opencl DMat4 $($rest:tt)*) => {};
+
+    (@opencl $std_name:ident $imp_ty:ty { $($field:ident),* }) => {
+        #[allow(clippy::needless_update)]
+        impl crate::opencl::AsOpenCl for $imp_ty {
+            type Output = crate::opencl::$std_name;
+
+            #[inline]
+            fn as_opencl(&self) -> Self::Output {
+                crate::opencl::$std_name {
+                    $(
+                        $field: self.$field.as_opencl(),
+                    )*
+                    ..bytemuck::Zeroable::zeroed()
+                }
+            }
+
+            #[inline]
+            fn from_opencl(value: Self::Output) -> Self {
+                Self {
+                    $(
+                        $field: <_ as crate::opencl::AsOpenCl>::from_opencl(value.$field),
+                    )*
+                }
+            }
+        }
+    };
+
+    // WGSL has no 64-bit floating point types, and bools can't be stored in
+    // buffers.
+    (@wgsl BVec2 $($rest:tt)*) => {};
+    (@wgsl BVec3 $($rest:tt)*) => {};
+    (@wgsl BVec4 $($rest:tt)*) => {};
+    (@wgsl DVec2 $($rest:tt)*) => {};
+    (@wgsl DVec3 $($rest:tt)*) => {};
+    (@wgsl DVec4 $($rest:tt)*) => {};
+    (@wgsl DMat2 $($rest:tt)*) => {};
+    (@wgsl DMat3 $($rest:tt)*) => {};
+    (@wgsl DMat4 $($rest:tt)*) => {};
+
+    (@wgsl $std_name:ident $imp_ty:ty) => {
+        unsafe impl crate::wgsl::Wgsl for $imp_ty {
+            const NAME: &'static str = <crate::std140::$std_name as crate::wgsl::Wgsl>::NAME;
+            const TYPE: crate::wgsl::WgslType = <crate::std140::$std_name as crate::wgsl::Wgsl>::TYPE;
+        }
+    };
+
+    (@glsl_value $std_name:ident $imp_ty:ty { $($field:ident),* }) => {
+        impl crate::glsl::GlslValue for $imp_ty {
+            fn write_glsl_value(&self, output: &mut dyn core::fmt::Write) -> core::fmt::Result {
+                output.write_str(<crate::std140::$std_name as crate::glsl::Glsl>::NAME)?;
+                crate::glsl::write_arguments(output, &[$(&self.$field),*])
+            }
+        }
+    };
+
+    (@hlsl $std_name:ident $imp_ty:ty) => {
+        unsafe impl crate::hlsl::Hlsl for $imp_ty {
+            const NAME: &'static str = <crate::hlsl_cbuffer::$std_name as crate::hlsl::Hlsl>::NAME;
+            const TYPE: crate::hlsl::HlslType = <crate::hlsl_cbuffer::$std_name as crate::hlsl::Hlsl>::TYPE;
+        }
+    };
+
     ( $( $std_name:ident $imp_ty:ty { $($field:ident),* }, )* ) => {
         $(
+            easy_impl!(@msl $std_name $imp_ty { $($field),* });
+            easy_impl!(@opencl $std_name $imp_ty { $($field),* });
+            easy_impl!(@wgsl $std_name $imp_ty);
+            easy_impl!(@hlsl $std_name $imp_ty);
+            easy_impl!(@glsl_value $std_name $imp_ty { $($field),* });
+
             #[allow(clippy::needless_update)]
             impl crate::std140::AsStd140 for $imp_ty {
                 type Output = crate::std140::$std_name;
@@ -51,6 +183,30 @@ macro_rules! easy_impl {
                 }
             }
 
+            #[allow(clippy::needless_update)]
+            impl crate::hlsl_cbuffer::AsHlslCbuffer for $imp_ty {
+                type Output = crate::hlsl_cbuffer::$std_name;
+
+                #[inline]
+                fn as_hlsl_cbuffer(&self) -> Self::Output {
+                    crate::hlsl_cbuffer::$std_name {
+                        $(
+                            $field: self.$field.as_hlsl_cbuffer(),
+                        )*
+                        ..bytemuck::Zeroable::zeroed()
+                    }
+                }
+
+                #[inline]
+                fn from_hlsl_cbuffer(value: Self::Output) -> Self {
+                    Self {
+                        $(
+                            $field: <_ as crate::hlsl_cbuffer::AsHlslCbuffer>::from_hlsl_cbuffer(value.$field),
+                        )*
+                    }
+                }
+            }
+
             unsafe impl crate::glsl::Glsl for $imp_ty {
                 const NAME: &'static str = crate::std140::$std_name::NAME;
             }
@@ -59,40 +215,132 @@ macro_rules! easy_impl {
 }
 
 macro_rules! minty_impl {
-    ( $( $mint_ty:ty => $imp_ty:ty, )* ) => {
+    // MSL has no 64-bit floating point types.
+    (@msl $mint_ty:ty => $imp_ty:ty, f64) => {};
+
+    (@msl $mint_ty:ty => $imp_ty:ty, $prim:ident) => {
+        impl crate::msl::AsMsl for $imp_ty {
+            type Output = <$mint_ty as crate::msl::AsMsl>::Output;
+
+            #[inline]
+            fn as_msl(&self) -> Self::Output {
+                let mint: $mint_ty = (*self).into();
+                mint.as_msl()
+            }
+
+            #[inline]
+            fn from_msl(value: Self::Output) -> Self {
+                <$mint_ty>::from_msl(value).into()
+            }
+        }
+    };
+
+    // OpenCL C has no matrix types and does not allow bools in kernel arguments.
+    (@opencl $mint_ty:ty => $imp_ty:ty, bool, $mint_kind:ident) => {};
+    (@opencl $mint_ty:ty => $imp_ty:ty, $prim:ident, ColumnMatrix2) => {};
+    (@opencl $mint_ty:ty => $imp_ty:ty, $prim:ident, ColumnMatrix3) => {};
+    (@opencl $mint_ty:ty => $imp_ty:ty, $prim:ident, ColumnMatrix4) => {};
+
+    (@opencl $mint_ty:ty => $imp_ty:ty, $prim:ident, $mint_kind:ident) => {
+        impl crate::opencl::AsOpenCl for $imp_ty {
+            type Output = <$mint_ty as crate::opencl::AsOpenCl>::Output;
+
+            #[inline]
+            fn as_opencl(&self) -> Self::Output {
+                let mint: $mint_ty = (*self).into();
+                mint.as_opencl()
+            }
+
+            #[inline]
+            fn from_opencl(value: Self::Output) -> Self {
+                <$mint_ty>::from_opencl(value).into()
+            }
+        }
+    };
+
+    // WGSL has no 64-bit floating point types, and bools can't be stored in
+    // buffers.
+    (@wgsl $mint_ty:ty => $imp_ty:ty, f64) => {};
+    (@wgsl $mint_ty:ty => $imp_ty:ty, bool) => {};
+
+    (@wgsl $mint_ty:ty => $imp_ty:ty, $prim:ident) => {
+        unsafe impl crate::wgsl::Wgsl for $imp_ty {
+            const NAME: &'static str = <$mint_ty as crate::wgsl::Wgsl>::NAME;
+            const TYPE: crate::wgsl::WgslType = <$mint_ty as crate::wgsl::Wgsl>::TYPE;
+        }
+    };
+
+    (@glsl_value $mint_ty:ty => $imp_ty:ty) => {
+        impl crate::glsl::GlslValue for $imp_ty {
+            fn write_glsl_value(&self, output: &mut dyn core::fmt::Write) -> core::fmt::Result {
+                let mint: $mint_ty = (*self).into();
+                crate::glsl::GlslValue::write_glsl_value(&mint, output)
+            }
+        }
+    };
+
+    (@hlsl $mint_ty:ty => $imp_ty:ty) => {
+        unsafe impl crate::hlsl::Hlsl for $imp_ty {
+            const NAME: &'static str = <$mint_ty as crate::hlsl::Hlsl>::NAME;
+            const TYPE: crate::hlsl::HlslType = <$mint_ty as crate::hlsl::Hlsl>::TYPE;
+        }
+    };
+
+    ( $( mint::$mint_kind:ident<$prim:ident> => $imp_ty:ty, )* ) => {
         $(
+            minty_impl!(@msl mint::$mint_kind<$prim> => $imp_ty, $prim);
+            minty_impl!(@opencl mint::$mint_kind<$prim> => $imp_ty, $prim, $mint_kind);
+            minty_impl!(@wgsl mint::$mint_kind<$prim> => $imp_ty, $prim);
+            minty_impl!(@hlsl mint::$mint_kind<$prim> => $imp_ty);
+            minty_impl!(@glsl_value mint::$mint_kind<$prim> => $imp_ty);
+
             impl crate::std140::AsStd140 for $imp_ty {
-                type Output = <$mint_ty as crate::std140::AsStd140>::Output;
+                type Output = <mint::$mint_kind<$prim> as crate::std140::AsStd140>::Output;
 
                 #[inline]
                 fn as_std140(&self) -> Self::Output {
-                    let mint: $mint_ty = (*self).into();
+                    let mint: mint::$mint_kind<$prim> = (*self).into();
                     mint.as_std140()
                 }
 
                 #[inline]
                 fn from_std140(value: Self::Output) -> Self {
-                    <$mint_ty>::from_std140(value).into()
+                    <mint::$mint_kind<$prim>>::from_std140(value).into()
                 }
             }
 
             impl crate::std430::AsStd430 for $imp_ty {
-                type Output = <$mint_ty as crate::std430::AsStd430>::Output;
+                type Output = <mint::$mint_kind<$prim> as crate::std430::AsStd430>::Output;
 
                 #[inline]
                 fn as_std430(&self) -> Self::Output {
-                    let mint: $mint_ty = (*self).into();
+                    let mint: mint::$mint_kind<$prim> = (*self).into();
                     mint.as_std430()
                 }
 
                 #[inline]
                 fn from_std430(value: Self::Output) -> Self {
-                    <$mint_ty>::from_std430(value).into()
+                    <mint::$mint_kind<$prim>>::from_std430(value).into()
+                }
+            }
+
+            impl crate::hlsl_cbuffer::AsHlslCbuffer for $imp_ty {
+                type Output = <mint::$mint_kind<$prim> as crate::hlsl_cbuffer::AsHlslCbuffer>::Output;
+
+                #[inline]
+                fn as_hlsl_cbuffer(&self) -> Self::Output {
+                    let mint: mint::$mint_kind<$prim> = (*self).into();
+                    mint.as_hlsl_cbuffer()
+                }
+
+                #[inline]
+                fn from_hlsl_cbuffer(value: Self::Output) -> Self {
+                    <mint::$mint_kind<$prim>>::from_hlsl_cbuffer(value).into()
                 }
             }
 
             unsafe impl crate::glsl::Glsl for $imp_ty {
-                const NAME: &'static str = <$mint_ty>::NAME;
+                const NAME: &'static str = <mint::$mint_kind<$prim>>::NAME;
             }
         )*
     };
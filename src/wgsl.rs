@@ -0,0 +1,484 @@
+/*!
+Defines traits and types for generating WGSL code from Rust definitions.
+
+All WGSL-compatible primitives, like `i32` or `vec3<f32>`, implement the
+[`Wgsl`] trait. Structs should implement [`WgslStruct`], which can be derived.
+
+WGSL lays out structs according to its own rules, so generated definitions are
+tied to one of Crevice's layouts. `@align` and `@size` attributes are added
+wherever that layout differs from WGSL's natural layout, so that the WGSL struct
+matches the bytes Crevice produces.
+
+## Examples
+Given this struct:
+*/
+#![cfg_attr(
+    feature = "std",
+    doc = r##"
+```rust
+use crevice::layout::Std140;
+use crevice::std140::AsStd140;
+use crevice::wgsl::WgslStruct;
+
+#[derive(AsStd140, WgslStruct)]
+struct Material {
+    roughness: f32,
+    metallic: f32,
+}
+
+#[derive(AsStd140, WgslStruct)]
+struct Object {
+    id: u32,
+    material: Material,
+}
+
+println!("{}", Object::wgsl_definition_with_dependencies::<Std140>());
+```
+"##
+)]
+/*!
+The output will be:
+```wgsl
+struct Material {
+    roughness: f32,
+    @size(12) metallic: f32,
+};
+
+struct Object {
+    id: u32,
+    @align(16) material: Material,
+};
+```
+*/
+
+#[cfg(feature = "alloc")]
+mod binding;
+
+#[cfg(feature = "alloc")]
+pub use self::binding::*;
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, vec::Vec};
+
+pub use crevice_derive::WgslStruct;
+
+#[cfg(feature = "alloc")]
+use crate::internal::align_offset;
+#[cfg(feature = "alloc")]
+use crate::layout::{AsLayout, Layout};
+#[cfg(feature = "alloc")]
+use crate::reflect::{FieldInfo, LayoutInfo, Reflect};
+
+/// Trait for types that have a WGSL equivalent. Useful for generating WGSL code
+/// from Rust structs.
+///
+/// # Safety
+/// `NAME` must name a WGSL type with the same memory representation as the
+/// implementing type, or as its elements if it is an array, and `TYPE` must
+/// describe it accurately.
+pub unsafe trait Wgsl {
+    /// The name of this type in WGSL, like `vec2<f32>` or `mat4x4<f32>`.
+    const NAME: &'static str;
+
+    /// Describes the natural layout of a primitive, or the definition of a
+    /// struct.
+    const TYPE: WgslType;
+}
+
+/// Describes a WGSL type.
+#[derive(Debug, Clone, Copy)]
+pub enum WgslType {
+    /// A primitive, like `f32` or `vec3<f32>`.
+    Primitive {
+        /// The natural alignment of the type in WGSL.
+        alignment: usize,
+
+        /// The size of the type in WGSL.
+        size: usize,
+    },
+
+    /// A struct with the given definition.
+    Struct(&'static WgslStructDefinition),
+
+    /// A fixed-size array, like `array<f32, 4>`. Arrays are named after their
+    /// element type, so `[f32; 4]` has the `NAME` `f32`.
+    Array {
+        /// The number of elements in the array.
+        len: usize,
+
+        /// Describes the array's element type.
+        element: &'static WgslType,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl WgslType {
+    /// The struct this type is, or is an array of, if any.
+    fn definition(&self) -> Option<&'static WgslStructDefinition> {
+        match *self {
+            WgslType::Primitive { .. } => None,
+            WgslType::Struct(definition) => Some(definition),
+            WgslType::Array { element, .. } => element.definition(),
+        }
+    }
+
+    /// The full name of a type described by this, given the name of its
+    /// innermost element type, like `array<f32, 4>` for `f32`.
+    fn name(&self, element_name: &str) -> String {
+        match self {
+            WgslType::Primitive { .. } | WgslType::Struct(_) => String::from(element_name),
+            WgslType::Array { len, element } => {
+                format!("array<{}, {}>", element.name(element_name), len)
+            }
+        }
+    }
+
+    /// Gives the natural alignment and size in WGSL of a value of this type,
+    /// given the Crevice layout it must match.
+    fn natural_layout(&self, layout: &LayoutInfo) -> (usize, usize) {
+        match *self {
+            WgslType::Primitive { alignment, size } => (alignment, size),
+            WgslType::Struct(definition) => {
+                (StructLayout::new(definition, layout).alignment, layout.size)
+            }
+            WgslType::Array { len, element } => {
+                let array = layout
+                    .array
+                    .unwrap_or_else(|| panic!("layout of `{}` is not an array", layout.name));
+                let (alignment, size) = element.natural_layout(array.element);
+
+                // WGSL has no way to change an array's stride.
+                (alignment, len * (size + align_offset(size, alignment)))
+            }
+        }
+    }
+}
+
+/// A field contained within a WGSL struct definition.
+#[derive(Debug, Clone, Copy)]
+pub struct WgslField {
+    /// The type of the field, like `vec2<f32>` or `mat3x3<f32>`.
+    pub ty: &'static str,
+
+    /// The field's name. This must be a valid WGSL identifier.
+    pub name: &'static str,
+
+    /// Describes the field's type.
+    pub wgsl_type: WgslType,
+}
+
+/// The definition of a WGSL struct, which links to the definitions of any
+/// structs used by its fields.
+#[derive(Debug)]
+pub struct WgslStructDefinition {
+    /// The name of the struct.
+    pub name: &'static str,
+
+    /// The fields contained in the struct, in declaration order.
+    pub fields: &'static [WgslField],
+}
+
+#[cfg(feature = "alloc")]
+impl WgslStructDefinition {
+    /// Generates WGSL code that defines this struct, matching the given layout
+    /// of its Rust equivalent, like `<T as AsStd140>::Output::LAYOUT_INFO`.
+    ///
+    /// # Panics
+    /// Panics if the layout can't be expressed in WGSL, like `std140`'s
+    /// 32-byte `mat2`, or doesn't describe this struct.
+    pub fn wgsl_definition(&self, layout: &LayoutInfo) -> String {
+        let wgsl_layout = StructLayout::new(self, layout);
+
+        let mut output = String::new();
+        output.push_str("struct ");
+        output.push_str(self.name);
+        output.push_str(" {\n");
+
+        for member in &wgsl_layout.members {
+            output.push_str("    ");
+            if let Some(align) = member.align {
+                output.push_str(&format!("@align({}) ", align));
+            }
+            if let Some(size) = member.size {
+                output.push_str(&format!("@size({}) ", size));
+            }
+            output.push_str(member.field.name);
+            output.push_str(": ");
+            output.push_str(&member.field.wgsl_type.name(member.field.ty));
+            output.push_str(",\n");
+        }
+
+        output.push_str("};");
+        output
+    }
+
+    /// Generates WGSL code that defines this struct and every struct it
+    /// depends on, in an order WGSL accepts. See
+    /// [`WgslStructDefinition::wgsl_definition`].
+    pub fn wgsl_definition_with_dependencies(&self, layout: &LayoutInfo) -> String {
+        wgsl_definitions(&[(self, layout)])
+    }
+}
+
+/// Generates WGSL code defining all of the given structs, each paired with the
+/// layout of its Rust equivalent, and every struct they depend on.
+///
+/// Definitions are de-duplicated by name and ordered so that each struct is
+/// defined after the structs it uses.
+///
+/// # Panics
+/// Panics if a layout can't be expressed in WGSL or doesn't describe its
+/// struct.
+#[cfg(feature = "alloc")]
+pub fn wgsl_definitions(structs: &[(&WgslStructDefinition, &LayoutInfo)]) -> String {
+    let mut definitions = Vec::new();
+    for (definition, layout) in structs {
+        visit_definition(definition, layout, &mut definitions);
+    }
+
+    definitions
+        .iter()
+        .map(|(definition, layout)| definition.wgsl_definition(layout))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Trait for types that can be represented as a struct in WGSL.
+///
+/// This trait should not generally be implemented by hand, but can be derived.
+///
+/// # Safety
+/// `TYPE` must be [`WgslType::Struct`] with a definition listing every field
+/// of the struct, in declaration order.
+pub unsafe trait WgslStruct: Wgsl {
+    /// The definition of this struct.
+    const DEFINITION: &'static WgslStructDefinition = match Self::TYPE {
+        WgslType::Struct(definition) => definition,
+        WgslType::Primitive { .. } | WgslType::Array { .. } => {
+            panic!("WgslStruct types must be structs")
+        }
+    };
+
+    /// Generates WGSL code that represents this struct laid out according to
+    /// `L`, like [`Std140`][crate::layout::Std140].
+    ///
+    /// # Panics
+    /// Panics if the layout can't be expressed in WGSL.
+    #[cfg(feature = "alloc")]
+    fn wgsl_definition<L: Layout>() -> String
+    where
+        Self: AsLayout<L>,
+        <Self as AsLayout<L>>::Output: Reflect,
+    {
+        Self::DEFINITION.wgsl_definition(<Self as AsLayout<L>>::Output::LAYOUT_INFO)
+    }
+
+    /// Generates WGSL code that defines this struct and every struct it
+    /// depends on laid out according to `L`, in an order WGSL accepts.
+    ///
+    /// # Panics
+    /// Panics if the layout can't be expressed in WGSL.
+    #[cfg(feature = "alloc")]
+    fn wgsl_definition_with_dependencies<L: Layout>() -> String
+    where
+        Self: AsLayout<L>,
+        <Self as AsLayout<L>>::Output: Reflect,
+    {
+        Self::DEFINITION
+            .wgsl_definition_with_dependencies(<Self as AsLayout<L>>::Output::LAYOUT_INFO)
+    }
+}
+
+/// Adds `definition` to `output` after all of its dependencies, skipping any
+/// structs that are already present.
+#[cfg(feature = "alloc")]
+fn visit_definition<'a>(
+    definition: &'a WgslStructDefinition,
+    layout: &'a LayoutInfo,
+    output: &mut Vec<(&'a WgslStructDefinition, &'a LayoutInfo)>,
+) {
+    if output
+        .iter()
+        .any(|(existing, _)| existing.name == definition.name)
+    {
+        return;
+    }
+
+    for (field, field_layout) in matching_fields(definition, layout) {
+        if let Some(field_definition) = field.wgsl_type.definition() {
+            let mut field_layout = field_layout.layout;
+            while let Some(array) = field_layout.array {
+                field_layout = array.element;
+            }
+
+            visit_definition(field_definition, field_layout, output);
+        }
+    }
+
+    output.push((definition, layout));
+}
+
+/// Pairs each field of `definition` with its layout, in declaration order.
+#[cfg(feature = "alloc")]
+fn matching_fields<'a>(
+    definition: &'a WgslStructDefinition,
+    layout: &'a LayoutInfo,
+) -> impl Iterator<Item = (&'a WgslField, &'a FieldInfo)> {
+    assert_eq!(
+        definition.fields.len(),
+        layout.fields.len(),
+        "layout of `{}` doesn't match its WGSL definition",
+        definition.name
+    );
+
+    definition.fields.iter().map(move |field| {
+        let info = layout
+            .fields
+            .iter()
+            .find(|info| info.name == field.name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "layout of `{}` has no field `{}`",
+                    definition.name, field.name
+                )
+            });
+
+        (field, info)
+    })
+}
+
+/// A member of a WGSL struct along with the attributes it needs.
+#[cfg(feature = "alloc")]
+struct Member<'a> {
+    field: &'a WgslField,
+    offset: usize,
+    align: Option<usize>,
+    size: Option<usize>,
+}
+
+/// The members of a WGSL struct in memory order, with the attributes needed to
+/// match a Crevice layout.
+#[cfg(feature = "alloc")]
+struct StructLayout<'a> {
+    members: Vec<Member<'a>>,
+    alignment: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> StructLayout<'a> {
+    fn new(definition: &'a WgslStructDefinition, layout: &'a LayoutInfo) -> Self {
+        let mut fields: Vec<_> = matching_fields(definition, layout).collect();
+        fields.sort_by_key(|(_, info)| info.offset);
+
+        let mut members: Vec<Member<'a>> = Vec::new();
+        let mut struct_alignment = 1;
+        let mut offset = 0;
+
+        for (field, info) in fields {
+            let (mut alignment, size) = field.wgsl_type.natural_layout(info.layout);
+
+            assert_eq!(
+                size, info.size,
+                "field `{}` of `{}` has a different size in WGSL than in `{}`'s layout",
+                field.name, definition.name, layout.name
+            );
+
+            let mut member = Member {
+                field,
+                offset: info.offset,
+                align: None,
+                size: None,
+            };
+
+            if offset + align_offset(offset, alignment) != info.offset {
+                let layout_alignment = info.layout.alignment;
+
+                if layout_alignment > alignment
+                    && offset + align_offset(offset, layout_alignment) == info.offset
+                {
+                    // Raising the alignment is enough to place the field.
+                    member.align = Some(layout_alignment);
+                    alignment = layout_alignment;
+                } else if info.offset % alignment == 0 && !members.is_empty() {
+                    // Otherwise, grow the previous field to fill the gap.
+                    let previous = members.last_mut().unwrap();
+                    previous.size = Some(info.offset - previous.offset);
+                } else {
+                    panic!(
+                        "field `{}` of `{}` can't be placed at offset {} in WGSL",
+                        field.name, definition.name, info.offset
+                    );
+                }
+            }
+
+            struct_alignment = struct_alignment.max(alignment);
+            offset = info.offset + info.size;
+            members.push(member);
+        }
+
+        // Grow the last field if the layout has more padding at the end than
+        // WGSL would add.
+        if offset + align_offset(offset, struct_alignment) < layout.size {
+            if let Some(last) = members.last_mut() {
+                last.size = Some(layout.size - last.offset);
+                offset = layout.size;
+            }
+        }
+
+        assert_eq!(
+            offset + align_offset(offset, struct_alignment),
+            layout.size,
+            "`{}` has a different size in WGSL than in its layout",
+            definition.name
+        );
+
+        Self {
+            members,
+            alignment: struct_alignment,
+        }
+    }
+}
+
+macro_rules! primitives {
+    ( $( $ty:ty => $name:literal align($align:literal) size($size:literal), )* ) => {
+        $(
+            unsafe impl Wgsl for $ty {
+                const NAME: &'static str = $name;
+                const TYPE: WgslType = WgslType::Primitive {
+                    alignment: $align,
+                    size: $size,
+                };
+            }
+        )*
+    };
+}
+
+primitives! {
+    f32 => "f32" align(4) size(4),
+    i32 => "i32" align(4) size(4),
+    u32 => "u32" align(4) size(4),
+
+    crate::std140::Vec2 => "vec2<f32>" align(8) size(8),
+    crate::std140::Vec3 => "vec3<f32>" align(16) size(12),
+    crate::std140::Vec4 => "vec4<f32>" align(16) size(16),
+
+    crate::std140::IVec2 => "vec2<i32>" align(8) size(8),
+    crate::std140::IVec3 => "vec3<i32>" align(16) size(12),
+    crate::std140::IVec4 => "vec4<i32>" align(16) size(16),
+
+    crate::std140::UVec2 => "vec2<u32>" align(8) size(8),
+    crate::std140::UVec3 => "vec3<u32>" align(16) size(12),
+    crate::std140::UVec4 => "vec4<u32>" align(16) size(16),
+
+    crate::std140::Mat2 => "mat2x2<f32>" align(8) size(16),
+    crate::std140::Mat3 => "mat3x3<f32>" align(16) size(48),
+    crate::std140::Mat4 => "mat4x4<f32>" align(16) size(64),
+}
+
+unsafe impl<T: Wgsl, const N: usize> Wgsl for [T; N] {
+    const NAME: &'static str = T::NAME;
+    const TYPE: WgslType = WgslType::Array {
+        len: N,
+        element: &T::TYPE,
+    };
+}
@@ -0,0 +1,133 @@
+use alloc::{borrow::ToOwned, format, string::String};
+
+use crate::wgsl::Wgsl;
+
+/// The access mode of a storage buffer binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageAccess {
+    /// `read`: the shader may only read from the buffer.
+    Read,
+
+    /// `read_write`: the shader may read from and write to the buffer.
+    ReadWrite,
+}
+
+/// The address space of a binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddressSpace {
+    Uniform,
+    Storage(StorageAccess),
+}
+
+/// Generates the declaration of a WGSL resource binding, like
+/// `@group(0) @binding(1) var<uniform> camera: Camera;`.
+///
+/// ```rust
+/// use crevice::wgsl::{WgslBinding, WgslStruct};
+///
+/// #[derive(WgslStruct)]
+/// struct PointLight {
+///     position: mint::Vector3<f32>,
+///     color: mint::Vector3<f32>,
+/// }
+///
+/// let binding = WgslBinding::storage_array::<PointLight>("lights")
+///     .group(0)
+///     .binding(2);
+///
+/// assert_eq!(
+///     binding.wgsl_definition(),
+///     "@group(0) @binding(2) var<storage, read> lights: array<PointLight>;"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct WgslBinding {
+    space: AddressSpace,
+    group: Option<u32>,
+    binding: Option<u32>,
+    name: String,
+    ty: String,
+}
+
+impl WgslBinding {
+    /// Creates a uniform buffer binding of `T` with the given variable name.
+    pub fn uniform<T: Wgsl>(name: &str) -> Self {
+        Self {
+            space: AddressSpace::Uniform,
+            group: None,
+            binding: None,
+            name: name.to_owned(),
+            ty: T::NAME.to_owned(),
+        }
+    }
+
+    /// Creates a read-only storage buffer binding of `T` with the given
+    /// variable name.
+    pub fn storage<T: Wgsl>(name: &str) -> Self {
+        Self {
+            space: AddressSpace::Storage(StorageAccess::Read),
+            ..Self::uniform::<T>(name)
+        }
+    }
+
+    /// Creates a read-only storage buffer binding of a runtime-sized array of
+    /// `T` with the given variable name.
+    pub fn storage_array<T: Wgsl>(name: &str) -> Self {
+        Self {
+            ty: format!("array<{}>", T::NAME),
+            ..Self::storage::<T>(name)
+        }
+    }
+
+    /// Sets the bind group of the binding.
+    pub fn group(mut self, group: u32) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    /// Sets the binding number of the binding.
+    pub fn binding(mut self, binding: u32) -> Self {
+        self.binding = Some(binding);
+        self
+    }
+
+    /// Sets the access mode of a storage buffer binding.
+    ///
+    /// # Panics
+    /// Panics if this isn't a storage buffer binding.
+    pub fn access(mut self, access: StorageAccess) -> Self {
+        assert!(
+            matches!(self.space, AddressSpace::Storage(_)),
+            "only storage buffer bindings have an access mode"
+        );
+
+        self.space = AddressSpace::Storage(access);
+        self
+    }
+
+    /// Makes a storage buffer binding writable. See [`WgslBinding::access`].
+    pub fn read_write(self) -> Self {
+        self.access(StorageAccess::ReadWrite)
+    }
+
+    /// Generates WGSL code declaring this binding.
+    pub fn wgsl_definition(&self) -> String {
+        let mut output = String::new();
+
+        if let Some(group) = self.group {
+            output.push_str(&format!("@group({}) ", group));
+        }
+        if let Some(binding) = self.binding {
+            output.push_str(&format!("@binding({}) ", binding));
+        }
+
+        let space = match self.space {
+            AddressSpace::Uniform => "uniform",
+            AddressSpace::Storage(StorageAccess::Read) => "storage, read",
+            AddressSpace::Storage(StorageAccess::ReadWrite) => "storage, read_write",
+        };
+
+        output.push_str(&format!("var<{}> {}: {};", space, self.name, self.ty));
+        output
+    }
+}
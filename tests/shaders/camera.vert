@@ -0,0 +1,20 @@
+#version 450
+
+// Compiled to camera.spv with debug names kept, which the SPIR-V tests compare
+// against `Camera`.
+
+layout(std140, set = 0, binding = 0) uniform Camera {
+    mat4 view;
+    mat4 projection;
+    mat3 normal_matrix;
+    vec3 position;
+    float exposure;
+    vec4 frustum[6];
+} camera;
+
+layout(location = 0) in vec3 in_position;
+
+void main() {
+    vec4 world = vec4(camera.normal_matrix * in_position + camera.position, camera.exposure);
+    gl_Position = camera.projection * camera.view * world + camera.frustum[0];
+}
@@ -0,0 +1,43 @@
+#version 450
+#define MAX_LIGHTS 16
+
+/* Shared lighting types. */
+
+// A light that shines in every direction.
+struct PointLight {
+    // World space position.
+    vec3 position;
+    float radius;
+    highp vec3 color;
+    uint type;
+};
+
+// Per-frame camera data.
+layout(std140, set = 0, binding = 0) uniform Camera {
+    mat4 view;
+    mat4 projection;
+    vec3 position, forward;
+    bool orthographic;
+} camera;
+
+layout(std430, set = 0, binding = 1) readonly buffer Lights {
+    PointLight main;
+    uint count;
+    PointLight lights[MAX_LIGHTS];
+};
+
+layout(location = 0) in vec3 v_position;
+layout(location = 0) out vec4 out_color;
+
+uniform sampler2D albedo;
+
+vec3 shade(PointLight light) {
+    if (light.radius > 0.0) {
+        return light.color;
+    }
+    return vec3(0.0);
+}
+
+void main() {
+    out_color = vec4(shade(main), 1.0);
+}
@@ -36,6 +36,61 @@ fn there_and_back_again() {
     assert_eq!(<ThereAndBackAgain as AsStd140>::from_std140(x_as), x);
 }
 
+#[test]
+#[cfg(feature = "std")]
+fn write_manual_write_std140() {
+    use std::io::{self, Write};
+
+    use crevice::std140::{self, WriteStd140};
+
+    /// A length-prefixed list, which can't implement `AsStd140`.
+    struct Weights(Vec<f32>);
+
+    impl WriteStd140 for Weights {
+        fn write_std140<W: Write>(&self, writer: &mut std140::Writer<W>) -> io::Result<usize> {
+            let offset = writer.write(&(self.0.len() as u32))?;
+            writer.write(self.0.as_slice())?;
+            Ok(offset)
+        }
+    }
+
+    let weights = Weights(vec![0.25, 0.5, 0.25]);
+
+    let mut output = Vec::new();
+    let mut writer = std140::Writer::new(&mut output);
+    writer.write(&1.0f32).unwrap();
+    assert_eq!(writer.write(&weights).unwrap(), 4);
+    assert_eq!(writer.write(&[weights][..]).unwrap(), 64);
+
+    // Every array element starts on a new 16-byte boundary.
+    assert_eq!(output.len(), 116);
+    assert_eq!(Weights(vec![1.0]).std140_size(), 20);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn write_std140_slices() {
+    use crevice::std140;
+
+    fn floats(bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks(4)
+            .map(|chunk| f32::from_ne_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+
+    let mut output = Vec::new();
+    let mut writer = std140::Writer::new(&mut output);
+    writer.write(&[1.0f32, 2.0][..]).unwrap();
+    assert_eq!(floats(&output), [1.0, 0.0, 0.0, 0.0, 2.0]);
+
+    let vectors: [mint::Vector2<f32>; 2] = [[1.0, 2.0].into(), [3.0, 4.0].into()];
+    let mut output = Vec::new();
+    let mut writer = std140::Writer::new(&mut output);
+    writer.write(&vectors[..]).unwrap();
+    assert_eq!(floats(&output), [1.0, 2.0, 0.0, 0.0, 3.0, 4.0]);
+}
+
 #[test]
 #[cfg(feature = "std")]
 fn generate_struct_glsl() {
@@ -48,5 +103,986 @@ fn generate_struct_glsl() {
         bar: mint::ColumnMatrix2<f32>,
     }
 
-    insta::assert_display_snapshot!(TestGlsl::glsl_definition());
+    insta::assert_snapshot!(TestGlsl::glsl_definition());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_struct_opencl() {
+    use crevice::opencl::{AsOpenCl, OpenClStruct};
+
+    #[allow(dead_code)]
+    #[derive(AsOpenCl)]
+    struct TestOpenCl {
+        foo: mint::Vector3<f32>,
+        bar: mint::Vector2<i32>,
+        baz: f64,
+    }
+
+    insta::assert_snapshot!(<TestOpenCl as AsOpenCl>::Output::opencl_definition());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_opencl_array_fields() {
+    use crevice::opencl::{AsOpenCl, OpenClStruct};
+
+    #[allow(dead_code)]
+    #[derive(AsOpenCl)]
+    struct Light {
+        color: mint::Vector4<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsOpenCl)]
+    struct Scene {
+        weights: [f32; 4],
+        points: [mint::Vector3<f32>; 2],
+        grid: [[i32; 2]; 3],
+        lights: [Light; 2],
+    }
+
+    insta::assert_snapshot!(<Scene as AsOpenCl>::Output::opencl_definition());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn layout_report() {
+    use crevice::hlsl_cbuffer::AsHlslCbuffer;
+    use crevice::report::LayoutReport;
+    use crevice::std430::AsStd430;
+
+    #[allow(dead_code)]
+    #[derive(AsStd140)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        intensity: f32,
+        color: mint::Vector2<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsStd140)]
+    struct Scene {
+        ambient: f32,
+        lights: [Light; 2],
+        exposure: f32,
+    }
+
+    let report = LayoutReport::std140::<Scene>();
+    assert_eq!(report.padding().count(), 2);
+    assert_eq!(report.wasted_bytes(), 12 + 2 * 8 + 12);
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}\n\n{}",
+        report.to_table(),
+        report.to_byte_map(),
+        report.to_markdown()
+    ));
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, AsStd430)]
+    struct Transform {
+        rotation: mint::ColumnMatrix3<f32>,
+        scale: mint::ColumnMatrix2<f32>,
+    }
+
+    // The columns of matrices are padded inside the matrix.
+    let report = LayoutReport::std140::<Transform>();
+    assert_eq!(report.padding().count(), 0);
+    assert_eq!(report.wasted_bytes(), 12 + 16);
+    assert_eq!(
+        report.to_byte_map(),
+        [
+            "     0  AAAAAAAAAAAA....",
+            "    16  AAAAAAAAAAAA....",
+            "    32  AAAAAAAAAAAA....",
+            "    48  BBBBBBBB........",
+            "    64  BBBBBBBB........",
+            "A = rotation, B = scale, . = padding",
+        ]
+        .join("\n")
+    );
+    assert_eq!(LayoutReport::std430::<Transform>().wasted_bytes(), 12);
+
+    #[allow(dead_code)]
+    #[derive(AsHlslCbuffer)]
+    struct Weights {
+        weights: [f32; 2],
+        scale: f32,
+    }
+
+    // Constant buffer arrays have no padding after their last element.
+    let report = LayoutReport::of::<<Weights as AsHlslCbuffer>::Output>();
+    assert_eq!(report.size(), 24);
+    assert_eq!(report.wasted_bytes(), 12);
+    assert_eq!(
+        report.to_byte_map(),
+        [
+            "     0  AAAA............",
+            "    16  AAAABBBB",
+            "A = weights, B = scale, . = padding",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_reordered_struct_glsl() {
+    use crevice::glsl::GlslStruct;
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, GlslStruct)]
+    #[crevice(reorder)]
+    struct TestReordered {
+        scale: f32,
+        position: mint::Vector3<f32>,
+        offset: mint::Vector2<f32>,
+    }
+
+    insta::assert_snapshot!(TestReordered::glsl_definition());
+}
+
+#[test]
+fn glsl_definition_const() {
+    use crevice::glsl::GlslStruct;
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Particle {
+        position: mint::Vector3<f32>,
+        lifetime: f32,
+        transform: mint::ColumnMatrix4<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, GlslStruct)]
+    #[crevice(reorder)]
+    struct Reordered {
+        scale: f32,
+        position: mint::Vector3<f32>,
+        offset: mint::Vector2<f32>,
+    }
+
+    const PARTICLE: &str = Particle::GLSL_DEFINITION;
+
+    assert_eq!(
+        PARTICLE,
+        "struct Particle {\n\
+        \tvec3 position;\n\
+        \tfloat lifetime;\n\
+        \tmat4 transform;\n\
+        };"
+    );
+
+    #[cfg(feature = "std")]
+    {
+        assert_eq!(Particle::GLSL_DEFINITION, Particle::glsl_definition());
+        assert_eq!(Reordered::GLSL_DEFINITION, Reordered::glsl_definition());
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_struct_glsl_with_dependencies() {
+    use crevice::glsl::{Glsl, GlslStruct};
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        color: mint::Vector3<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Material {
+        albedo: mint::Vector4<f32>,
+        emission: Light,
+    }
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Scene {
+        sun: Light,
+        material: Material,
+        lamp: Light,
+    }
+
+    let definitions = Scene::DEFINITION.unwrap().with_dependencies();
+    let names: Vec<_> = definitions
+        .iter()
+        .map(|definition| definition.name)
+        .collect();
+    assert_eq!(names, ["Light", "Material", "Scene"]);
+
+    insta::assert_snapshot!(Scene::glsl_definition_with_dependencies());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_glsl_blocks() {
+    use crevice::glsl::{BlockLayout, GlslBlock, GlslStruct};
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        color: mint::Vector3<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Camera {
+        view: mint::ColumnMatrix4<f32>,
+        projection: mint::ColumnMatrix4<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Lights {
+        ambient: Light,
+        count: u32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Constants {
+        time: f32,
+    }
+
+    let uniform = GlslBlock::uniform::<Camera>()
+        .set(0)
+        .binding(1)
+        .instance_name("camera");
+
+    let buffer = GlslBlock::buffer::<Lights>()
+        .layout(BlockLayout::Std140)
+        .binding(3)
+        .readonly()
+        .coherent()
+        .runtime_array::<Light>("lights");
+
+    let push_constant = GlslBlock::push_constant::<Constants>().instance_name("constants");
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}\n\n{}",
+        uniform.glsl_definition(),
+        buffer.glsl_definition_with_dependencies(),
+        push_constant.glsl_definition(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_glsl_blocks_with_offsets() {
+    use crevice::glsl::{GlslBlock, GlslStruct};
+    use crevice::layout::{Std140, Std430};
+    use crevice::std430::AsStd430;
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, AsStd430, GlslStruct)]
+    struct Material {
+        albedo: mint::Vector3<f32>,
+        roughness: f32,
+        emissive: mint::Vector3<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, AsStd430, GlslStruct)]
+    struct Object {
+        id: u32,
+        material: Material,
+        uv_scale: mint::Vector2<f32>,
+        transform: mint::ColumnMatrix3<f32>,
+        layer: i32,
+    }
+
+    let uniform = GlslBlock::uniform::<Object>()
+        .binding(0)
+        .explicit_offsets::<Object, Std140>();
+
+    let buffer = GlslBlock::buffer::<Object>()
+        .binding(1)
+        .explicit_offsets::<Object, Std430>()
+        .instance_name("object");
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}",
+        uniform.glsl_definition(),
+        buffer.glsl_definition(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_struct_wgsl() {
+    use crevice::layout::{Std140, Std430};
+    use crevice::std430::AsStd430;
+    use crevice::wgsl::{WgslBinding, WgslStruct};
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, AsStd430, WgslStruct)]
+    struct Material {
+        roughness: f32,
+        metallic: f32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, AsStd430, WgslStruct)]
+    struct Object {
+        id: u32,
+        material: Material,
+        transform: mint::ColumnMatrix4<f32>,
+        offset: mint::Vector2<f32>,
+    }
+
+    let uniform = WgslBinding::uniform::<Object>("object").group(0).binding(1);
+    let storage = WgslBinding::storage_array::<Object>("objects")
+        .group(1)
+        .binding(0)
+        .read_write();
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}\n\n{}\n\n{}",
+        Object::wgsl_definition_with_dependencies::<Std140>(),
+        uniform.wgsl_definition(),
+        Object::wgsl_definition_with_dependencies::<Std430>(),
+        storage.wgsl_definition(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_array_fields() {
+    use crevice::glsl::GlslStruct;
+    use crevice::hlsl::HlslStruct;
+    use crevice::layout::Std430;
+    use crevice::std430::AsStd430;
+    use crevice::wgsl::WgslStruct;
+
+    #[allow(dead_code)]
+    #[derive(AsStd430, GlslStruct, WgslStruct, HlslStruct)]
+    struct Light {
+        color: mint::Vector4<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsStd430, GlslStruct, WgslStruct, HlslStruct)]
+    struct Scene {
+        weights: [f32; 4],
+        uv: [mint::Vector2<f32>; 2],
+        lights: [Light; 2],
+        transforms: [mint::ColumnMatrix3<f32>; 2],
+    }
+
+    assert_eq!(Scene::GLSL_DEFINITION, Scene::glsl_definition());
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}\n\n{}\n\n{}",
+        Scene::glsl_definition_with_dependencies(),
+        Scene::msl_definition(),
+        Scene::wgsl_definition_with_dependencies::<Std430>(),
+        Scene::hlsl_definition_with_dependencies(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+#[should_panic(expected = "has a different size in WGSL")]
+fn generate_std140_scalar_array_wgsl() {
+    use crevice::layout::Std140;
+    use crevice::wgsl::WgslStruct;
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, WgslStruct)]
+    struct Weights {
+        weights: [f32; 4],
+    }
+
+    // WGSL arrays of `f32` have a stride of 4 bytes, which can't be changed to
+    // std140's 16.
+    Weights::wgsl_definition::<Std140>();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_hlsl_cbuffer() {
+    use crevice::hlsl::{HlslConstantBuffer, HlslStruct};
+    use crevice::hlsl_cbuffer::AsHlslCbuffer;
+
+    #[allow(dead_code)]
+    #[derive(AsHlslCbuffer, HlslStruct)]
+    struct Light {
+        direction: mint::Vector3<f32>,
+        intensity: f32,
+        rotation: mint::ColumnMatrix3<f32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsHlslCbuffer, HlslStruct)]
+    struct Frame {
+        time: f32,
+        resolution: mint::Vector2<f32>,
+        light: Light,
+        frame_index: u32,
+        view_projection: mint::ColumnMatrix4<f32>,
+        flags: mint::Vector2<u32>,
+    }
+
+    let cbuffer = HlslConstantBuffer::new::<Frame>().register(2).space(1);
+    let row_major = HlslConstantBuffer::new::<Frame>()
+        .name("FrameConstants")
+        .register(0)
+        .row_major();
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}",
+        cbuffer.hlsl_definition_with_dependencies(),
+        row_major.hlsl_definition(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_struct_msl() {
+    use crevice::glsl::GlslStruct;
+    use crevice::msl::{AsMsl, PackedVec3};
+
+    #[allow(dead_code)]
+    #[derive(AsMsl, GlslStruct)]
+    struct Light {
+        position: PackedVec3,
+        intensity: f32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsMsl, GlslStruct)]
+    struct Scene {
+        light: Light,
+        transform: mint::ColumnMatrix4<f32>,
+        tint: mint::Vector3<f32>,
+        flags: u32,
+    }
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}",
+        Scene::msl_definition_with_dependencies(),
+        Scene::msl_argument_buffer_definition(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_glsl_values() {
+    use crevice::glsl::{glsl_constant, glsl_constant_array, GlslStruct, GlslValue};
+
+    #[derive(GlslStruct, GlslValue)]
+    struct Material {
+        albedo: mint::Vector4<f32>,
+        layers: mint::Vector2<u32>,
+        double_sided: mint::Vector2<bool>,
+    }
+
+    #[derive(AsStd140, GlslStruct, GlslValue)]
+    #[crevice(reorder)]
+    struct Light {
+        intensity: f32,
+        direction: mint::Vector3<f32>,
+        offset: mint::Vector2<i32>,
+    }
+
+    let material = Material {
+        albedo: [1.0, 0.25, 1e-7, -0.0].into(),
+        layers: [3, 0].into(),
+        double_sided: [true, false].into(),
+    };
+
+    let lights = [
+        Light {
+            intensity: 0.5,
+            direction: [0.0, 1.0, 0.0].into(),
+            offset: [-4, 2].into(),
+        },
+        Light {
+            intensity: f32::INFINITY,
+            direction: [1.0e10, 0.0, f32::NAN].into(),
+            offset: [i32::MIN, 0].into(),
+        },
+    ];
+
+    let transform: mint::ColumnMatrix2<f64> = [[1.0, 0.0], [0.5, 2.0]].into();
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}\n{}\n{}\n{}",
+        Light::glsl_definition(),
+        glsl_constant("DEFAULT_MATERIAL", &material),
+        glsl_constant_array("DEFAULT_LIGHTS", &lights),
+        glsl_constant("TRANSFORM", &transform),
+        glsl_constant_array("WEIGHTS", &[0.25f32, 0.75]),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_renamed_glsl() {
+    use crevice::glsl::GlslStruct;
+
+    #[allow(dead_code, non_snake_case)]
+    #[derive(GlslStruct)]
+    #[crevice(rename(suffix = "_"))]
+    struct Filter {
+        input: u32,
+        sample: f32,
+        r#in: f32,
+        weight__scale: f32,
+        strength: f32,
+    }
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(GlslStruct)]
+    #[crevice(rename(prefix = "u_"))]
+    struct active {
+        gl_position: mint::Vector4<f32>,
+        output: mint::Vector3<f32>,
+    }
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}",
+        Filter::glsl_definition(),
+        active::glsl_definition(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_glsl_for_targets() {
+    use crevice::glsl::{GlslBlock, GlslStruct, GlslTarget};
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Light {
+        position: mint::Vector3<f32>,
+        tile: mint::Vector2<i32>,
+        index: u32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Scene {
+        view: mint::ColumnMatrix4<f32>,
+        light: Light,
+        time: f32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Counters {
+        origin: mint::Vector3<f64>,
+        visited: u64,
+    }
+
+    let webgl = GlslBlock::uniform::<Scene>()
+        .set(0)
+        .binding(1)
+        .instance_name("scene")
+        .target(GlslTarget::WEBGL2);
+
+    let es = GlslBlock::uniform::<Scene>()
+        .set(0)
+        .binding(1)
+        .instance_name("scene")
+        .target(GlslTarget::OpenGlEs(310));
+
+    let gl = GlslBlock::buffer::<Counters>()
+        .set(0)
+        .binding(2)
+        .target(GlslTarget::OpenGl(430));
+
+    let vulkan = GlslBlock::buffer::<Counters>()
+        .set(0)
+        .binding(2)
+        .target(GlslTarget::Vulkan);
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}\n\n{}\n\n{}\n\n{}",
+        webgl.glsl_definition_with_dependencies(),
+        es.glsl_definition(),
+        gl.glsl_definition_with_dependencies(),
+        vulkan.glsl_definition_with_dependencies(),
+        Counters::glsl_definition_with_dependencies_for(GlslTarget::OpenGl(330)),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_documented_glsl() {
+    use crevice::glsl::{GlslBlock, GlslStruct};
+
+    /// A light that shines in every direction.
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct PointLight {
+        /// World space position.
+        position: mint::Vector3<f32>,
+
+        /// Distance at which the light has no effect.
+        ///
+        /// Lights are culled beyond this radius.
+        radius: f32,
+
+        color: mint::Vector3<f32>,
+    }
+
+    /**
+     * Every light in the scene.
+     */
+    #[allow(dead_code)]
+    #[derive(GlslStruct)]
+    struct Lighting {
+        /// The brightest light.
+        main: PointLight,
+
+        /** Number of lights in use,
+         * up to 16. */
+        count: u32,
+    }
+
+    assert_eq!(Lighting::GLSL_DEFINITION, Lighting::glsl_definition());
+    assert_eq!(PointLight::GLSL_DEFINITION, PointLight::glsl_definition());
+
+    let block = GlslBlock::uniform::<Lighting>().binding(0);
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}",
+        block.glsl_definition_with_dependencies(),
+        Lighting::msl_definition_with_dependencies(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generate_shader_module() {
+    use crevice::glsl::{GlslBlock, GlslStruct, GlslValue};
+    use crevice::hlsl::{HlslConstantBuffer, HlslStruct};
+    use crevice::hlsl_cbuffer::AsHlslCbuffer;
+    use crevice::layout::Std140;
+    use crevice::module::ShaderModuleBuilder;
+    use crevice::wgsl::{WgslBinding, WgslStruct};
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, AsHlslCbuffer, GlslStruct, GlslValue, HlslStruct, WgslStruct)]
+    struct Material {
+        albedo: mint::Vector3<f32>,
+        roughness: f32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(AsStd140, AsHlslCbuffer, GlslStruct, HlslStruct, WgslStruct)]
+    struct Scene {
+        material: Material,
+        light_count: u32,
+    }
+
+    let default_material = Material {
+        albedo: [1.0, 1.0, 1.0].into(),
+        roughness: 0.5,
+    };
+
+    let module = ShaderModuleBuilder::new("shared-types")
+        .constant("MAX_LIGHTS", 16u32)
+        .constant("LIGHT_KIND_SPOT", 2i32)
+        .constant("EPSILON", 0.001f32)
+        .constant("MAX_LIGHTS", 16u32)
+        .glsl_struct::<Material>()
+        .glsl_constant("DEFAULT_MATERIAL", &default_material)
+        .glsl_block(GlslBlock::uniform::<Scene>().binding(0))
+        .glsl_block(
+            GlslBlock::buffer::<Material>()
+                .binding(1)
+                .block_name("MaterialBuffer")
+                .instance_name("material_buffer"),
+        )
+        .wgsl_struct::<Scene, Std140>()
+        .wgsl_binding(WgslBinding::uniform::<Scene>("scene").group(0).binding(0))
+        .hlsl_struct::<Material>()
+        .hlsl_cbuffer(HlslConstantBuffer::new::<Scene>().register(0));
+
+    insta::assert_snapshot!(format!(
+        "{}\n{}\n{}",
+        module.glsl_source(),
+        module.wgsl_source(),
+        module.hlsl_source(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn write_shader_module_only_when_changed() {
+    use crevice::module::ShaderModuleBuilder;
+
+    let dir = std::env::temp_dir().join(format!("crevice-module-{}", std::process::id()));
+    let path = dir.join("generated").join("constants.glsl");
+
+    let module = ShaderModuleBuilder::new("constants").constant("COUNT", 1u32);
+    assert!(module.write_glsl(&path).unwrap());
+    assert!(!module.write_glsl(&path).unwrap());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        module.glsl_source()
+    );
+
+    let module = module.constant("OTHER", 2u32);
+    assert!(module.write_glsl(&path).unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn include_glsl_round_trip() {
+    use crevice::glsl::GlslBlock;
+    use crevice::std430::AsStd430;
+
+    crevice::include_glsl!("tests/shaders/lighting.glsl");
+
+    assert_eq!(Camera::std140_size_static(), 160);
+    assert_eq!(Lights::std430_size_static(), 560);
+
+    let camera = GlslBlock::uniform::<Camera>()
+        .set(0)
+        .binding(0)
+        .instance_name("camera");
+    let lights = GlslBlock::buffer::<Lights>().set(0).binding(1).readonly();
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}",
+        camera.glsl_definition(),
+        lights.glsl_definition_with_dependencies(),
+    ));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn validate_glsl_blocks() {
+    use crevice::glsl::{validate_block, GlslSourceError};
+    use crevice::layout::{Std140, Std430};
+    use crevice::std430::AsStd430;
+    use mint::{ColumnMatrix3, ColumnMatrix4, Vector3, Vector4};
+
+    let lighting = include_str!("shaders/lighting.glsl");
+
+    #[derive(AsStd140, AsStd430)]
+    struct PointLight {
+        position: Vector3<f32>,
+        radius: f32,
+        color: Vector3<f32>,
+        kind: u32,
+    }
+
+    #[derive(AsStd430)]
+    struct Lights {
+        main: PointLight,
+        count: u32,
+    }
+
+    #[derive(AsStd140)]
+    struct Camera {
+        view: ColumnMatrix4<f32>,
+        projection: ColumnMatrix4<f32>,
+        position: Vector4<f32>,
+        forward: Vector3<f32>,
+        near: f32,
+    }
+
+    #[derive(AsStd140)]
+    struct Scene {
+        lights: [PointLight; 4],
+        weights: [Vector4<f32>; 3],
+        exposure: f32,
+    }
+
+    let scene = "
+        #define LIGHT_COUNT 4
+        const uint WEIGHT_COUNT = 3u;
+
+        struct PointLight {
+            vec3 position;
+            float radius;
+            vec4 color;
+            uint kind;
+        };
+
+        uniform Scene {
+            PointLight lights[LIGHT_COUNT];
+            vec4[WEIGHT_COUNT] weights;
+            layout(offset = 304) float exposure;
+        };
+    ";
+
+    let lights = validate_block::<Lights, Std430>(lighting, "Lights").unwrap();
+    let camera = validate_block::<Camera, Std140>(lighting, "Camera").unwrap();
+    let scene = validate_block::<Scene, Std140>(scene, "Scene").unwrap();
+    let wrong_layout = validate_block::<PointLight, Std140>(lighting, "Lights").unwrap();
+
+    insta::assert_snapshot!(format!(
+        "{}\n\n{}\n\n{}\n\n{}",
+        lights, camera, scene, wrong_layout
+    ));
+
+    let matching = "
+        layout(std430) buffer Lights {
+            PointLight main;
+            uint count;
+        };
+    ";
+    let matching = format!(
+        "struct PointLight {{ vec3 position; float radius; vec3 color; uint kind; }};\n{}",
+        matching
+    );
+    assert!(validate_block::<Lights, Std430>(&matching, "Lights")
+        .unwrap()
+        .is_match());
+
+    #[derive(AsStd140, AsStd430)]
+    struct Precise {
+        scale: f32,
+        origin: Vector3<f64>,
+        transform: ColumnMatrix3<f64>,
+        weight: f64,
+    }
+
+    let precise = "
+        layout(std140) uniform Precise {
+            float scale;
+            dvec3 origin;
+            dmat3 transform;
+            double weight;
+        };
+    ";
+    assert!(validate_block::<Precise, Std140>(precise, "Precise")
+        .unwrap()
+        .is_match());
+    let precise = precise.replace("layout(std140) uniform", "layout(std430) buffer");
+    assert!(validate_block::<Precise, Std430>(&precise, "Precise")
+        .unwrap()
+        .is_match());
+
+    assert_eq!(
+        validate_block::<Lights, Std430>(lighting, "Missing"),
+        Err(GlslSourceError::BlockNotFound {
+            name: "Missing".into()
+        })
+    );
+    assert_eq!(
+        validate_block::<Lights, Std430>("uniform Lights { float x[2.5]; };", "Lights")
+            .unwrap_err()
+            .to_string(),
+        "1:26: expected an integer constant, found `2.5`"
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn validate_spirv_blocks() {
+    use crevice::layout::{Std140, Std430};
+    use crevice::spirv::{reflect_block, validate_block, SpirvError};
+    use crevice::std430::AsStd430;
+    use mint::{ColumnMatrix3, ColumnMatrix4, Vector3, Vector4};
+
+    let spirv = include_bytes!("shaders/camera.spv");
+
+    #[derive(AsStd140)]
+    struct Camera {
+        view: ColumnMatrix4<f32>,
+        projection: ColumnMatrix4<f32>,
+        normal_matrix: ColumnMatrix3<f32>,
+        position: Vector3<f32>,
+        exposure: f32,
+        frustum: [Vector4<f32>; 6],
+    }
+
+    let diff = validate_block::<Camera, Std140>(spirv, "Camera").unwrap();
+    assert!(diff.is_match(), "{}", diff);
+
+    #[derive(AsStd430)]
+    struct OldCamera {
+        view: ColumnMatrix4<f32>,
+        normal_matrix: ColumnMatrix3<f32>,
+        projection: ColumnMatrix4<f32>,
+        position: Vector4<f32>,
+        frustum: [Vector4<f32>; 4],
+    }
+
+    let old = validate_block::<OldCamera, Std430>(spirv, "Camera").unwrap();
+
+    insta::assert_snapshot!(format!(
+        "{:#?}\n\n{}",
+        reflect_block(spirv, "Camera").unwrap(),
+        old
+    ));
+
+    assert_eq!(
+        reflect_block(spirv, "Missing"),
+        Err(SpirvError::BlockNotFound {
+            name: "Missing".into()
+        })
+    );
+    assert_eq!(
+        reflect_block(&spirv[4..], "Camera"),
+        Err(SpirvError::InvalidHeader)
+    );
+}
+
+#[test]
+#[cfg(feature = "naga")]
+fn verify_layouts_with_naga() {
+    use crevice::glsl::GlslStruct;
+    use crevice::layout::{Std140, Std430};
+    use crevice::naga::{validate_wgsl_struct, verify_glsl, verify_wgsl};
+    use crevice::reflect::Reflect;
+    use crevice::std430::AsStd430;
+    use crevice::wgsl::WgslStruct;
+    use mint::{ColumnMatrix3, Vector3, Vector4};
+
+    #[derive(AsStd140, AsStd430, GlslStruct, WgslStruct)]
+    struct Light {
+        position: Vector3<f32>,
+        intensity: f32,
+        color: Vector4<f32>,
+    }
+
+    #[derive(AsStd140, AsStd430, GlslStruct, WgslStruct)]
+    struct Scene {
+        key: Light,
+        fill: Light,
+        rotation: ColumnMatrix3<f32>,
+        count: u32,
+    }
+
+    for diff in [
+        verify_glsl::<Scene, Std140>().unwrap(),
+        verify_glsl::<Scene, Std430>().unwrap(),
+        verify_wgsl::<Scene, Std140>().unwrap(),
+        verify_wgsl::<Scene, Std430>().unwrap(),
+    ] {
+        assert!(diff.is_match(), "{}", diff);
+    }
+
+    let wgsl = "
+        struct Light {
+            position: vec3<f32>,
+            color: vec4<f32>,
+        };
+
+        struct Scene {
+            key: Light,
+            @align(64) fill: Light,
+            rotation: mat3x3<f32>,
+            count: vec2<u32>,
+        };
+    ";
+
+    let diff =
+        validate_wgsl_struct(wgsl, "Scene", <Scene as AsStd140>::Output::LAYOUT_INFO).unwrap();
+    insta::assert_snapshot!(diff.to_string());
 }